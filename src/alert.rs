@@ -0,0 +1,86 @@
+// Rust module to raise alerts when a metric reading leaves its acceptable range.
+// Out-of-range readings are posted as their own AlertData blocks, chained to
+// the metric block that triggered them.
+
+use iota_sdk::types::block::BlockId;
+use tracing::warn;
+
+use crate::{
+    block_payload::{AlertData, BlockRef, MetricData},
+    block_store::{post_block, BlockStore},
+    block_tag::BlockTag,
+    custom_error::Error,
+    metric_env_prefix, read_env_parsed,
+    timestamp::{now_timestamp, TimestampFormat},
+};
+
+// Acceptable range of a metric. Any bound that is not configured is not
+// checked.
+pub struct AlertRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl AlertRange {
+    // Read the acceptable range of a metric type from the environment, e.g.
+    // TEMPERATURE_MIN_OK and TEMPERATURE_MAX_OK for the Temperature metric.
+    pub fn from_env(metric_type: &str) -> Result<Self, Error> {
+        let prefix: String = metric_env_prefix(metric_type);
+
+        Ok(Self {
+            min: read_env_parsed::<f64>(&format!("{}_MIN_OK", prefix))?,
+            max: read_env_parsed::<f64>(&format!("{}_MAX_OK", prefix))?,
+        })
+    }
+
+    // Return the threshold violated by the given value, if any.
+    pub fn violated_threshold(&self, value: f64) -> Option<f64> {
+        match (self.min, self.max) {
+            (Some(min), _) if value < min => Some(min),
+            (_, Some(max)) if value > max => Some(max),
+            _ => None,
+        }
+    }
+}
+
+// Compare a posted metric reading against the acceptable range of its type.
+// If the reading is out of bounds, print a warning and post an AlertData block
+// referencing the metric block. Returns the id of the alert block, if any.
+pub async fn check_metric_alert(
+    client: &dyn BlockStore,
+    metric_data: &MetricData,
+    metric_block_id: &BlockId
+) -> Result<Option<BlockId>, Error> {
+    let range: AlertRange = AlertRange::from_env(&metric_data.metric_type)?;
+
+    let threshold: f64 = match range.violated_threshold(metric_data.metric_value) {
+        Some(threshold) => threshold,
+        None => return Ok(None),
+    };
+
+    warn!(
+        "{} reading {} {} violates the threshold {}",
+        metric_data.metric_type,
+        metric_data.metric_value,
+        metric_data.measurement_unit,
+        threshold
+    );
+
+    let alert_data: AlertData = AlertData::new(
+        metric_data.metric_type.to_owned(),
+        metric_data.metric_value,
+        threshold,
+        now_timestamp(TimestampFormat::from_env()?)?,
+        BlockRef::from(*metric_block_id)
+    );
+
+    let data: Vec<u8> = serde_json::to_string(&alert_data)?
+        .as_bytes()
+        .to_vec();
+
+    let tag: Vec<u8> = BlockTag::Alert.as_bytes();
+
+    let block_id: BlockId = post_block(client, tag, data).await?;
+
+    Ok(Some(block_id))
+}
@@ -0,0 +1,125 @@
+// Rust module to check that a delivery can be settled before it is recorded.
+// With SENDER_ADDRESS set, the balance of the sender wallet is queried from
+// the indexer of the node and compared with the SMR cost of the payment info.
+// BALANCE_CHECK selects whether a wallet that falls short only warns or fails
+// the delivery.
+
+use iota_sdk::types::block::address::Bech32Address;
+use std::{fmt, str::FromStr};
+use tracing::{info, warn};
+
+use crate::{
+    block_payload::PaymentInfo,
+    custom_error::Error,
+    read_env_parsed, read_env_var,
+    tangle_client::TangleClient,
+};
+
+// SMR has 6 decimals, and balances are counted in its smallest unit, glow.
+const GLOW_PER_SMR: f64 = 1_000_000.0;
+
+// What a sender wallet that cannot pay the delivery leads to.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BalanceCheck {
+    #[default]
+    Warn,
+    Error,
+}
+
+impl BalanceCheck {
+    // Read BALANCE_CHECK, warning when unset.
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(read_env_parsed::<BalanceCheck>("BALANCE_CHECK")?.unwrap_or_default())
+    }
+}
+
+impl FromStr for BalanceCheck {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "warn" => Ok(BalanceCheck::Warn),
+            "error" => Ok(BalanceCheck::Error),
+            _ => Err(String::from("unknown balance check, expected one of warn, error")),
+        }
+    }
+}
+
+// The cost in glow, rounded up so a wallet short of a fraction of a glow still
+// falls short.
+pub fn smr_to_glow(smr_cost: f64) -> u64 {
+    (smr_cost * GLOW_PER_SMR).ceil() as u64
+}
+
+// The glow missing from the balance to pay the cost, if it falls short.
+pub fn balance_shortfall(balance_glow: u64, smr_cost: f64) -> Option<u64> {
+    let cost_glow: u64 = smr_to_glow(smr_cost);
+
+    if balance_glow < cost_glow {
+        Some(cost_glow - balance_glow)
+    } else {
+        None
+    }
+}
+
+// A glow amount in SMR, e.g. 1.500000 SMR.
+pub struct Smr(pub u64);
+
+impl fmt::Display for Smr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.6} SMR", self.0 as f64 / GLOW_PER_SMR)
+    }
+}
+
+// Check that the sender wallet holds enough to pay the delivery. Without
+// SENDER_ADDRESS there is no wallet to check. A balance that falls short, or
+// cannot be read, fails the delivery with BALANCE_CHECK=error and is only
+// warned about otherwise.
+pub async fn check_sender_balance(
+    client: &TangleClient,
+    payment_info: &PaymentInfo
+) -> Result<(), Error> {
+    let sender_address: String = match read_env_var("SENDER_ADDRESS".to_string()) {
+        Ok(value) => value,
+        Err(_err) => return Ok(()),
+    };
+    let check: BalanceCheck = BalanceCheck::from_env()?;
+
+    let address: Bech32Address = sender_address.trim()
+        .parse::<Bech32Address>()
+        .map_err(|err| Error::Validation(format!(
+            "SENDER_ADDRESS: '{}' is not a valid bech32 address: {}", sender_address, err
+        )))?;
+
+    let problem: String = match client.address_balance(&address).await {
+        Ok(balance_glow) => match balance_shortfall(balance_glow, payment_info.smr_cost) {
+            None => {
+                info!(
+                    "Sender wallet {} holds {}, enough to pay {}",
+                    address, Smr(balance_glow), Smr(smr_to_glow(payment_info.smr_cost))
+                );
+                return Ok(());
+            },
+            Some(shortfall) => format!(
+                "sender wallet {} holds {}, {} short of the {} the delivery costs",
+                address,
+                Smr(balance_glow),
+                Smr(shortfall),
+                Smr(smr_to_glow(payment_info.smr_cost))
+            ),
+        },
+        Err(err) => format!(
+            "failed to read the balance of sender wallet {}: {:#}", address, err
+        ),
+    };
+
+    match check {
+        BalanceCheck::Warn => {
+            warn!("The delivery may not be payable: {}", problem);
+            Ok(())
+        },
+        BalanceCheck::Error => Err(Error::Anyhow(anyhow::Error::msg(format!(
+            "The delivery is not payable: {}", problem
+        )))),
+    }
+}
@@ -1,22 +1,80 @@
-use serde::{Deserialize, Serialize};
+use iota_sdk::types::block::BlockId;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{cid::validate_cid, custom_error::Error, validate_block_id};
+
+// Version of the payload format written by this code. Payloads posted before
+// versioning have no schemaVersion and read as version 0.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+// Reject payloads written by a newer version of the format, which this code
+// may not be able to read correctly.
+pub fn check_schema_version(schema_version: u32) -> Result<(), Error> {
+    if schema_version > CURRENT_SCHEMA_VERSION {
+        return Err(Error::Anyhow(anyhow::Error::msg(format!(
+            "Payload schema version {} is newer than the supported version {}",
+            schema_version, CURRENT_SCHEMA_VERSION
+        ))));
+    }
+
+    Ok(())
+}
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")] // Allows usage of camelCase in React.js and snake_case in Tauri.
 pub struct BlockPayload {
+    #[serde(default)]
+    pub schema_version: u32,
     pub tag: String,
     pub data: BlockData,
 }
 
+impl BlockPayload {
+    pub fn new(tag: String, data: BlockData) -> Self {
+        Self { schema_version: CURRENT_SCHEMA_VERSION, tag, data }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")] // Allows usage of camelCase in React.js and snake_case in Tauri.
 pub struct TaggedDataPayload {
+    #[serde(default)]
+    pub schema_version: u32,
     pub block_type: String,
     pub data: BlockData,
 }
 
+impl TaggedDataPayload {
+    pub fn new(block_type: String, data: BlockData) -> Self {
+        Self { schema_version: CURRENT_SCHEMA_VERSION, block_type, data }
+    }
+}
+
+// Decode the data of a block: a TaggedDataPayload of a supported schema
+// version, or the bare BlockData of a block posted before the payloads were
+// versioned.
+pub fn decode_block_data(data: &[u8]) -> Result<BlockData, Error> {
+    match serde_json::from_slice::<TaggedDataPayload>(data) {
+        Ok(payload) => {
+            check_schema_version(payload.schema_version)?;
+            Ok(payload.data)
+        },
+        Err(_err) => Ok(serde_json::from_slice::<BlockData>(data)?),
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")] // Allows usage of camelCase in React.js and snake_case in Tauri.
 #[serde(untagged)] // Disable additional object wrapping for enums
+// Serde reads a payload as the first variant whose required fields are all
+// present and ignores unknown fields, so the variants must keep distinct
+// required field names. An object carrying the fields of two variants is read
+// as the one declared first, e.g. DistributorBlockData before
+// RetailerBlockData, so new variants go after the ones they may overlap.
 pub enum BlockData {
     BasicBlockData(String),
     RawMaterialsProducerBlockData(RawMaterialsProducerBlockData),
@@ -27,97 +85,349 @@ pub enum BlockData {
     ConsumerBlockData(ConsumerBlockData),
     StartTransportationData(StartTransportationData),
     DeliveredTransportationData(DeliveredTransportationData),
-    MetricData(MetricData)
+    MetricData(MetricData),
+    AlertData(AlertData),
+    LocationMetricData(LocationMetricData),
+    BatchMetricData(BatchMetricData),
+    StuckSensorData(StuckSensorData)
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl BlockData {
+    // Name of the variant, which is the name of the block type the data
+    // belongs to.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BlockData::BasicBlockData(_) => "BasicBlockData",
+            BlockData::RawMaterialsProducerBlockData(_) => "RawMaterialsProducerBlockData",
+            BlockData::SupplierBlockData(_) => "SupplierBlockData",
+            BlockData::ManufacturerBlockData(_) => "ManufacturerBlockData",
+            BlockData::DistributorBlockData(_) => "DistributorBlockData",
+            BlockData::RetailerBlockData(_) => "RetailerBlockData",
+            BlockData::ConsumerBlockData(_) => "ConsumerBlockData",
+            BlockData::StartTransportationData(_) => "StartTransportationData",
+            BlockData::DeliveredTransportationData(_) => "DeliveredTransportationData",
+            BlockData::MetricData(_) => "MetricData",
+            BlockData::AlertData(_) => "AlertData",
+            BlockData::LocationMetricData(_) => "LocationMetricData",
+            BlockData::BatchMetricData(_) => "BatchMetricData",
+            BlockData::StuckSensorData(_) => "StuckSensorData",
+        }
+    }
+
+    // The metadata the block was annotated with, if its type carries any.
+    pub fn metadata(&self) -> Option<&HashMap<String, String>> {
+        match self {
+            BlockData::StartTransportationData(data) => data.metadata.as_ref(),
+            BlockData::DeliveredTransportationData(data) => data.metadata.as_ref(),
+            BlockData::MetricData(data) => data.metadata.as_ref(),
+            BlockData::LocationMetricData(data) => data.metadata.as_ref(),
+            BlockData::BatchMetricData(data) => data.metadata.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+// Name of the block type the data belongs to.
+pub fn block_data_kind(data: &BlockData) -> &'static str {
+    data.kind()
+}
+
+// Longest basic block text shown in a summary.
+const SUMMARY_TEXT_LENGTH: usize = 40;
+
+// A one-line summary of the data: its kind followed by the field that
+// identifies it best, for logs and error messages.
+impl fmt::Display for BlockData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind())?;
+
+        match self {
+            BlockData::BasicBlockData(text) => {
+                let summary: String = text.chars().take(SUMMARY_TEXT_LENGTH).collect();
+                if summary.len() < text.len() {
+                    write!(f, " \"{}...\"", summary)
+                } else {
+                    write!(f, " \"{}\"", summary)
+                }
+            },
+            BlockData::RawMaterialsProducerBlockData(data) => {
+                write!(f, " from {}", data.provider_info)
+            },
+            BlockData::SupplierBlockData(data) => write!(f, " from {}", data.supplier_info),
+            BlockData::ManufacturerBlockData(data) => {
+                write!(f, " from {}", data.manufacturer_info)
+            },
+            BlockData::DistributorBlockData(data) => {
+                write!(f, " from {}", data.distributor_info)
+            },
+            BlockData::RetailerBlockData(data) => write!(f, " from {}", data.retailer_info),
+            BlockData::ConsumerBlockData(data) => write!(f, " from {}", data.consumer_info),
+            BlockData::StartTransportationData(data) => write!(
+                f, " by {} after {}", data.transportation_company_info, data.previous_block
+            ),
+            BlockData::DeliveredTransportationData(data) => write!(
+                f, " at {} with {} metric chains", data.delivery_timestamp, data.metrics.len()
+            ),
+            BlockData::MetricData(data) => write!(
+                f, " {} {} {} at {}",
+                data.metric_type, data.metric_value, data.measurement_unit, data.timestamp
+            ),
+            BlockData::AlertData(data) => write!(
+                f, " {} {} beyond {} at {}",
+                data.metric_type, data.value, data.threshold_violated, data.timestamp
+            ),
+            BlockData::LocationMetricData(data) => write!(
+                f, " ({}, {}) at {}", data.longitude, data.latitude, data.timestamp
+            ),
+            BlockData::BatchMetricData(data) => {
+                write!(f, " of {} readings", data.metrics.len())
+            },
+            BlockData::StuckSensorData(data) => write!(
+                f, " {} stuck at {} for {} readings",
+                data.metric_type, data.value, data.consecutive_readings
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentInfo {
     pub wallet_address: String,
     pub smr_cost: f64,
 }
 
+// Payment info sealed with AES-256-GCM: the base64 encoded ciphertext of the
+// JSON encoded PaymentInfo and the nonce it was sealed with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedPaymentInfo {
+    pub ciphertext: String,
+    pub nonce: String,
+}
+
+// Payment info as it appears in a payload: in cleartext, or encrypted so only
+// the holders of the key can read the wallet address and cost.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum PostedPaymentInfo {
+    Encrypted(EncryptedPaymentInfo),
+    Clear(PaymentInfo),
+}
+
+// The id of the block a block links back to. It can only be created from a
+// valid block id, 0x followed by 64 hex digits, and payloads with any other
+// previous block fail to deserialize, so a malformed link is never posted or
+// read back.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct BlockRef(String);
+
+impl BlockRef {
+    pub fn new(block_id: impl Into<String>) -> Result<Self, Error> {
+        let block_id: String = block_id.into();
+        validate_block_id(&block_id)?;
+
+        Ok(Self(block_id))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<BlockId> for BlockRef {
+    fn from(block_id: BlockId) -> Self {
+        Self(block_id.to_string())
+    }
+}
+
+impl FromStr for BlockRef {
+    type Err = Error;
+
+    fn from_str(block_id: &str) -> Result<Self, Self::Err> {
+        BlockRef::new(block_id)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let block_id: String = String::deserialize(deserializer)?;
+        BlockRef::new(block_id).map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Display for BlockRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl PartialEq<str> for BlockRef {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for BlockRef {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for BlockRef {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Resource {
-    pub previous_block: String,
+    pub previous_block: BlockRef,
     pub transaction_receipt: String
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Resources {
-    pub previous_blocks: Vec<String>,
+    pub previous_blocks: Vec<BlockRef>,
     pub transaction_receipts: Vec<String>
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RawMaterialsProducerBlockData {
     pub provider_info: String,
     pub material_info: ProductInfo,
     pub export_timestamp: String,
     pub export_location: ExportLocation,
-    pub payment_info: PaymentInfo
+    pub payment_info: PostedPaymentInfo
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ProductInfo {
     pub info: String,
-    pub file_cid: Option<String>
+    pub file_cid: Option<String>,
+    // Quantity of the product in the given unit, e.g. 12.5 kg. Left out of
+    // payloads without one, which were all written before it was added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
 }
 
 impl ProductInfo {
-    pub fn new( info: String, file_cid: Option<String>) -> Self {
-        Self { info, file_cid}
+    // Fails when the file CID is not a valid IPFS CID.
+    pub fn new( info: String, file_cid: Option<String>) -> Result<Self, Error> {
+        if let Some(file_cid) = &file_cid {
+            validate_cid(file_cid)?;
+        }
+
+        Ok(Self { info, file_cid, quantity: None, unit: None })
+    }
+
+    // Set the quantity of the product along with its unit.
+    pub fn with_quantity(mut self, quantity: f64, unit: String) -> Self {
+        self.quantity = Some(quantity);
+        self.unit = Some(unit);
+        self
     }
 }
 
+// Provenance of a transportation: the kind of the supply chain block it starts
+// from, along with the payment and product info carried by that block.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
+pub struct OriginInfo {
+    pub kind: String,
+    pub payment_info: PaymentInfo,
+    pub product_info: Option<ProductInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
 pub struct ExportLocation {
     pub longitude: f32,
     pub latitude: f32
 }
 
+impl ExportLocation {
+    pub fn validate(&self) -> Result<(), Error> {
+        validate_coordinates(self.longitude, self.latitude)
+    }
+}
+
+// Check that a longitude is within [-180, 180] and a latitude within [-90, 90].
+// NaN and infinite coordinates are rejected as well.
+pub fn validate_coordinates(longitude: f32, latitude: f32) -> Result<(), Error> {
+    if !longitude.is_finite() || !(-180.0..=180.0).contains(&longitude) {
+        return Err(Error::Validation(format!(
+            "longitude: must be within [-180, 180], got {}", longitude
+        )));
+    }
+
+    if !latitude.is_finite() || !(-90.0..=90.0).contains(&latitude) {
+        return Err(Error::Validation(format!(
+            "latitude: must be within [-90, 90], got {}", latitude
+        )));
+    }
+
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SupplierBlockData {
     pub supplier_info: String,
     pub processed_material_info: ProductInfo,
     pub resources: Resources,
-    pub payment_info: PaymentInfo,
+    pub payment_info: PostedPaymentInfo,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ManufacturerBlockData {
     pub manufacturer_info: String,
     pub product_info: ProductInfo,
     pub resources: Resources,
-    pub payment_info: PaymentInfo
+    pub payment_info: PostedPaymentInfo
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DistributorBlockData {
     pub distributor_info: String,
     pub product_distribution_info: ProductInfo,
     pub resource: Resource,
-    pub payment_info: PaymentInfo,
+    pub payment_info: PostedPaymentInfo,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct RetailerBlockData {
     pub retailer_info: String,
     pub product_retail_info: ProductInfo,
-    pub payment_info: PaymentInfo,
+    pub payment_info: PostedPaymentInfo,
     pub resource: Resource,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ConsumerBlockData {
     pub consumer_info: String,
@@ -125,12 +435,17 @@ pub struct ConsumerBlockData {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct StartTransportationData {
     pub transportation_company_info: String,
     pub transportation_info: ProductInfo,
     pub start_timestamp: String,
-    pub previous_block: String,
+    pub previous_block: BlockRef,
+    // Free-form annotations from METADATA, such as a purchase order number or
+    // the id of the driver. Left out of payloads without any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
 }
 
 impl StartTransportationData {
@@ -138,31 +453,48 @@ impl StartTransportationData {
         transportation_company_info: String,
         transportation_info: ProductInfo,
         start_timestamp: String,
-        previous_block: String,
+        previous_block: BlockRef,
     ) -> Self {
         Self {
             transportation_company_info,
             transportation_info,
             start_timestamp,
             previous_block,
+            metadata: None,
         }
     }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DeliveredTransportationData {
     pub product_delivery_info: ProductInfo,
     pub delivery_timestamp: String,
-    pub payment_info: PaymentInfo,
+    pub payment_info: PostedPaymentInfo,
     pub metrics: Vec<String>,
+    // The readings of the metric chains, embedded so the delivery can be
+    // verified without fetching every metric block. Left out by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_metrics: Option<Vec<MetricData>>,
+    // Set when the shipment is handed over to another carrier: the start
+    // block of the leg this delivery closes. The start block of the next leg
+    // references this delivery as its previous block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_leg_previous_block: Option<BlockRef>,
+    // Every leg of a multi-leg shipment, in order. Only set on the delivery
+    // of the last leg.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legs: Option<Vec<TransportationLeg>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
 }
 
 impl DeliveredTransportationData {
     pub fn new (
         product_delivery_info: ProductInfo,
         delivery_timestamp: String,
-        payment_info: PaymentInfo,
+        payment_info: PostedPaymentInfo,
         metrics: Vec<String>,
     ) -> Self {
         Self {
@@ -170,18 +502,38 @@ impl DeliveredTransportationData {
             delivery_timestamp,
             payment_info,
             metrics,
+            resolved_metrics: None,
+            next_leg_previous_block: None,
+            legs: None,
+            metadata: None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// A leg of a shipment, carried by a single carrier from its start block to
+// its delivery block. The delivery block of the last leg is the block listing
+// the legs, so it is left out.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TransportationLeg {
+    pub carrier: String,
+    pub start_block: BlockRef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery_block: Option<BlockRef>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct MetricData {
     pub metric_type: String,
     pub metric_value: f64,
     pub measurement_unit: String,
     pub timestamp: String,
-    pub previous_block: String,
+    pub previous_block: BlockRef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
 }
 
 impl MetricData {
@@ -190,7 +542,7 @@ impl MetricData {
         metric_value: f64,
         measurement_unit: String,
         timestamp: String,
-        previous_block: String,
+        previous_block: BlockRef,
     ) -> Self {
         Self {
             metric_type,
@@ -198,7 +550,115 @@ impl MetricData {
             measurement_unit,
             timestamp,
             previous_block,
+            metadata: None,
+        }
+    }
+}
+
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AlertData {
+    pub metric_type: String,
+    pub value: f64,
+    pub threshold_violated: f64,
+    pub timestamp: String,
+    pub previous_block: BlockRef,
+}
+
+impl AlertData {
+    pub fn new(
+        metric_type: String,
+        value: f64,
+        threshold_violated: f64,
+        timestamp: String,
+        previous_block: BlockRef,
+    ) -> Self {
+        Self {
+            metric_type,
+            value,
+            threshold_violated,
+            timestamp,
+            previous_block,
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct LocationMetricData {
+    pub longitude: f32,
+    pub latitude: f32,
+    pub timestamp: String,
+    pub previous_block: BlockRef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl LocationMetricData {
+    pub fn new(
+        longitude: f32,
+        latitude: f32,
+        timestamp: String,
+        previous_block: BlockRef,
+    ) -> Self {
+        Self {
+            longitude,
+            latitude,
+            timestamp,
+            previous_block,
+            metadata: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        validate_coordinates(self.longitude, self.latitude)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BatchMetricData {
+    pub metrics: Vec<MetricData>,
+    pub previous_block: BlockRef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl BatchMetricData {
+    pub fn new(metrics: Vec<MetricData>, previous_block: BlockRef) -> Self {
+        Self { metrics, previous_block, metadata: None }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct StuckSensorData {
+    pub metric_type: String,
+    pub value: f64,
+    pub consecutive_readings: usize,
+    pub timestamp: String,
+    pub previous_block: BlockRef,
+}
+
+impl StuckSensorData {
+    pub fn new(
+        metric_type: String,
+        value: f64,
+        consecutive_readings: usize,
+        timestamp: String,
+        previous_block: BlockRef,
+    ) -> Self {
+        Self {
+            metric_type,
+            value,
+            consecutive_readings,
+            timestamp,
+            previous_block,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+// Rust module with the store the board posts its blocks to and reads them
+// back from. The posting and chaining logic only needs to post tagged data and
+// read it back, so it is written against the BlockStore trait rather than the
+// IOTA client, and runs against the in-memory MockStore without a network.
+
+use async_trait::async_trait;
+use iota_sdk::types::block::{BlockDto, BlockId};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{
+    block_payload::{BlockData, TaggedDataPayload},
+    custom_error::Error,
+    digest::payload_digest,
+    extract_raw_tag_and_data, extract_tag_and_data,
+    payload_format::decode_payload,
+    tangle_client::TangleClient,
+    validation::ValidatorRegistry,
+};
+
+#[async_trait]
+pub trait BlockStore: Send + Sync {
+    // Post tagged data and return the id of its block.
+    async fn post(&self, tag: Vec<u8>, data: Vec<u8>) -> Result<String, Error>;
+
+    // Read the data of the block with the given id, decoded to JSON if it was
+    // posted in a binary format.
+    async fn get(&self, id: &str) -> Result<Vec<u8>, Error>;
+
+    // Read the data of the block with the given id as posted, which is what
+    // its digest covers.
+    async fn get_raw(&self, id: &str) -> Result<Vec<u8>, Error>;
+
+    // Whether posts are only printed instead, so there is nothing to read
+    // back from the store.
+    fn dry_run(&self) -> bool {
+        false
+    }
+}
+
+// Post tagged data to a store and parse the id of its block. Data that reads
+// as block data is checked by the enabled payload validators first, and is
+// posted in a TaggedDataPayload stamped with the current schema version.
+pub async fn post_block(
+    store: &dyn BlockStore,
+    tag: Vec<u8>,
+    data: Vec<u8>
+) -> Result<BlockId, Error> {
+    let data: Vec<u8> = match serde_json::from_slice::<BlockData>(&data) {
+        Ok(block_data) => {
+            ValidatorRegistry::from_env()?.validate(&block_data)?;
+
+            let block_type: String = String::from(block_data.kind());
+            serde_json::to_vec(&TaggedDataPayload::new(block_type, block_data))?
+        },
+        Err(_err) => data,
+    };
+
+    let block_id: String = store.post(tag, data).await?;
+
+    Ok(block_id.parse::<BlockId>()?)
+}
+
+#[async_trait]
+impl BlockStore for TangleClient {
+    async fn post(&self, tag: Vec<u8>, data: Vec<u8>) -> Result<String, Error> {
+        let block_id: BlockId = TangleClient::post(self, tag, data).await?;
+        Ok(block_id.to_string())
+    }
+
+    async fn get(&self, id: &str) -> Result<Vec<u8>, Error> {
+        let block: BlockDto = self.get_block(&id.to_owned()).await?;
+
+        let (_tag, data): (Vec<u8>, Vec<u8>) = extract_tag_and_data(block)
+            .map_err(|err| err.with_block_context(id, None, None))?;
+
+        Ok(data)
+    }
+
+    async fn get_raw(&self, id: &str) -> Result<Vec<u8>, Error> {
+        let block: BlockDto = self.get_block(&id.to_owned()).await?;
+
+        let (_tag, payload): (Vec<u8>, Vec<u8>) = extract_raw_tag_and_data(block)
+            .map_err(|err| err.with_block_context(id, None, None))?;
+
+        Ok(payload)
+    }
+
+    fn dry_run(&self) -> bool {
+        TangleClient::dry_run(self)
+    }
+}
+
+// A block held by the MockStore.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredBlock {
+    pub id: String,
+    pub tag: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+// An in-memory block store. Every block gets an id derived from its position
+// and content, formatted like a real block id, so the ids parse as BlockIds.
+#[derive(Debug, Default)]
+pub struct MockStore {
+    blocks: Mutex<Vec<StoredBlock>>,
+    index: Mutex<HashMap<String, usize>>,
+}
+
+impl MockStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A block is only ever pushed whole, so the blocks are still consistent
+    // if a holder of the lock panicked.
+    fn lock_blocks(&self) -> MutexGuard<'_, Vec<StoredBlock>> {
+        self.blocks.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn lock_index(&self) -> MutexGuard<'_, HashMap<String, usize>> {
+        self.index.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Every block posted so far, in posting order.
+    pub fn blocks(&self) -> Vec<StoredBlock> {
+        self.lock_blocks().clone()
+    }
+}
+
+#[async_trait]
+impl BlockStore for MockStore {
+    async fn post(&self, tag: Vec<u8>, data: Vec<u8>) -> Result<String, Error> {
+        let mut blocks: MutexGuard<'_, Vec<StoredBlock>> = self.lock_blocks();
+
+        let mut content: Vec<u8> = blocks.len().to_be_bytes().to_vec();
+        content.extend_from_slice(&tag);
+        content.extend_from_slice(&data);
+        let id: String = format!("0x{}", payload_digest(&content));
+
+        self.lock_index().insert(id.clone(), blocks.len());
+        blocks.push(StoredBlock { id: id.clone(), tag, data });
+
+        Ok(id)
+    }
+
+    // Blocks are read back the way the node returns them: decoded from a
+    // binary payload format by get, as posted by get_raw.
+    async fn get(&self, id: &str) -> Result<Vec<u8>, Error> {
+        decode_payload(self.get_raw(id).await?)
+    }
+
+    async fn get_raw(&self, id: &str) -> Result<Vec<u8>, Error> {
+        let position: Option<usize> = self.lock_index().get(id).copied();
+
+        match position {
+            Some(position) => Ok(self.lock_blocks()[position].data.clone()),
+            None => Err(Error::BlockNotFound(id.to_owned())),
+        }
+    }
+}
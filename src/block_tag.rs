@@ -0,0 +1,119 @@
+// Rust module with the tags of the blocks posted by the board.
+// Every block is posted with one of these tags, so they can be used to filter
+// blocks by their type.
+
+use iota_sdk::types::block::payload::tagged_data::TaggedDataPayload;
+use std::collections::HashMap;
+
+use crate::custom_error::Error;
+
+// The tag of a posted block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockTag {
+    StartTransportation,
+    DeliveredTransportation,
+    TemperatureMetric,
+    HumidityMetric,
+    PressureMetric,
+    ShockMetric,
+    LocationMetric,
+    MetricBatch,
+    Alert,
+    StuckSensor,
+    Consumer,
+    // Tag of a metric defined in the configuration rather than compiled in.
+    Custom(String),
+}
+
+impl BlockTag {
+    // Every built-in tag posted by the board.
+    pub const ALL: [BlockTag; 11] = [
+        BlockTag::StartTransportation,
+        BlockTag::DeliveredTransportation,
+        BlockTag::TemperatureMetric,
+        BlockTag::HumidityMetric,
+        BlockTag::PressureMetric,
+        BlockTag::ShockMetric,
+        BlockTag::LocationMetric,
+        BlockTag::MetricBatch,
+        BlockTag::Alert,
+        BlockTag::StuckSensor,
+        BlockTag::Consumer,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            BlockTag::StartTransportation => "Start Transportation Tag",
+            BlockTag::DeliveredTransportation => "Delivered Transportation Tag",
+            BlockTag::TemperatureMetric => "Temperature Metric Tag",
+            BlockTag::HumidityMetric => "Humidity Metric Tag",
+            BlockTag::PressureMetric => "Pressure Metric Tag",
+            BlockTag::ShockMetric => "Shock Metric Tag",
+            BlockTag::LocationMetric => "Location Metric Tag",
+            BlockTag::MetricBatch => "Metric Batch Tag",
+            BlockTag::Alert => "Alert Tag",
+            BlockTag::StuckSensor => "Stuck Sensor Tag",
+            BlockTag::Consumer => "Consumer Tag",
+            BlockTag::Custom(tag) => tag,
+        }
+    }
+
+    // Encode the tag as the bytes posted in the tagged data payload.
+    pub fn as_bytes(&self) -> Vec<u8> {
+        self.as_str().as_bytes().to_vec()
+    }
+
+    // The tag with the given name: a built-in tag, matched case-insensitively,
+    // or else a custom metric tag.
+    pub fn from_name(name: &str) -> BlockTag {
+        BlockTag::ALL
+            .into_iter()
+            .find(|tag| tag.as_str().eq_ignore_ascii_case(name.trim()))
+            .unwrap_or_else(|| BlockTag::Custom(name.trim().to_owned()))
+    }
+
+    // Decode the tag of a tagged data payload. Returns None for tags that are
+    // not built into the board, including custom metric tags.
+    pub fn from_bytes(bytes: &[u8]) -> Option<BlockTag> {
+        BlockTag::ALL
+            .into_iter()
+            .find(|tag| tag.as_str().as_bytes() == bytes)
+    }
+}
+
+// Longest tag accepted in a tagged data payload, in bytes, as set by the
+// protocol.
+pub fn max_tag_length() -> usize {
+    *TaggedDataPayload::TAG_LENGTH_RANGE.end() as usize
+}
+
+// Check the tags a run posts with before anything is posted: every tag must
+// fit the protocol's tag length, and no two may encode to the same bytes, or
+// their blocks could not be told apart.
+pub fn check_tags(tags: &[BlockTag]) -> Result<(), Error> {
+    let max_length: usize = max_tag_length();
+    let mut seen: HashMap<Vec<u8>, &BlockTag> = HashMap::new();
+
+    for tag in tags {
+        let bytes: Vec<u8> = tag.as_bytes();
+
+        if bytes.is_empty() {
+            return Err(Error::Validation(format!("tag of {:?}: must not be empty", tag)));
+        }
+
+        if bytes.len() > max_length {
+            return Err(Error::Validation(format!(
+                "tag '{}': {} bytes long, longer than the {} bytes the protocol allows",
+                tag.as_str(), bytes.len(), max_length
+            )));
+        }
+
+        if let Some(other) = seen.insert(bytes, tag) {
+            return Err(Error::Validation(format!(
+                "tag '{}': used by both {:?} and {:?}", tag.as_str(), other, tag
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+// Rust module to verify the integrity of the chains a run posted. Every chain
+// is walked back from its head: each previous_block must resolve to a block of
+// the same chain, until the start transportation block the chain hangs off is
+// reached. Links that do not resolve, resolve to a block of another chain or
+// loop back on the chain are reported as broken, with the ids involved, so a
+// shipment can be audited on the Tangle after delivery.
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::{
+    block_payload::{decode_block_data, BlockData},
+    block_store::BlockStore,
+    custom_error::Error,
+    output::JsonOutput,
+    walk_concurrency,
+};
+
+// A link of a chain whose previous block is not the block it should be.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenLink {
+    pub block_id: String,
+    pub previous_block: String,
+    pub reason: String,
+}
+
+// The outcome of walking a chain back from its head.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub head: String,
+    // Name of the chain: the metric type, Batch or Location. None when the
+    // head is the start block itself, as for a chain that posted nothing.
+    pub chain: Option<String>,
+    // Number of chain blocks walked, the start block excluded.
+    pub blocks_checked: usize,
+    // The start transportation block the chain leads back to, if it does.
+    pub start_block: Option<String>,
+    pub broken_links: Vec<BrokenLink>,
+}
+
+impl VerifyReport {
+    pub fn is_intact(&self) -> bool {
+        self.broken_links.is_empty() && self.start_block.is_some()
+    }
+}
+
+// A block of a chain, reduced to what the walk needs.
+enum ChainBlock {
+    // A block of the named chain, linking to its previous block.
+    Link { chain: String, previous_block: String },
+    // The start transportation block every chain of a leg hangs off.
+    Start,
+    // A block that belongs to no chain, named by its kind.
+    Other(&'static str),
+}
+
+fn chain_block(data: &BlockData) -> ChainBlock {
+    let link = |chain: &str, previous_block: &str| ChainBlock::Link {
+        chain: chain.to_owned(),
+        previous_block: previous_block.to_owned(),
+    };
+
+    match data {
+        BlockData::MetricData(data) => link(&data.metric_type, data.previous_block.as_str()),
+        BlockData::StuckSensorData(data) => {
+            link(&data.metric_type, data.previous_block.as_str())
+        },
+        BlockData::BatchMetricData(data) => link("Batch", data.previous_block.as_str()),
+        BlockData::LocationMetricData(data) => link("Location", data.previous_block.as_str()),
+        BlockData::StartTransportationData(_) => ChainBlock::Start,
+        other => ChainBlock::Other(other.kind()),
+    }
+}
+
+async fn read_chain_block(client: &dyn BlockStore, block_id: &str) -> Result<ChainBlock, Error> {
+    let data: Vec<u8> = client.get(block_id).await?;
+    let block_data: BlockData = decode_block_data(&data)?;
+
+    Ok(chain_block(&block_data))
+}
+
+// Walk the chain with the given head back to its start block. Fails only when
+// the head itself cannot be read; every later problem is a broken link of the
+// report, at which the walk stops.
+pub async fn verify_chain(client: &dyn BlockStore, head_id: &str) -> Result<VerifyReport, Error> {
+    let mut report: VerifyReport = VerifyReport {
+        head: head_id.to_owned(),
+        chain: None,
+        blocks_checked: 0,
+        start_block: None,
+        broken_links: Vec::new(),
+    };
+
+    let (chain, mut previous_block): (String, String) =
+        match read_chain_block(client, head_id).await? {
+            ChainBlock::Link { chain, previous_block } => (chain, previous_block),
+            ChainBlock::Start => {
+                report.start_block = Some(head_id.to_owned());
+                return Ok(report);
+            },
+            ChainBlock::Other(kind) => {
+                return Err(Error::Validation(format!(
+                    "chain head {}: is a {} block, not a chain block", head_id, kind
+                )));
+            },
+        };
+    report.chain = Some(chain.clone());
+    report.blocks_checked = 1;
+
+    let mut block_id: String = head_id.to_owned();
+    let mut visited: HashSet<String> = HashSet::from([block_id.clone()]);
+
+    loop {
+        if !visited.insert(previous_block.clone()) {
+            report.broken_links.push(BrokenLink {
+                block_id,
+                previous_block,
+                reason: String::from("loops back on the chain"),
+            });
+            return Ok(report);
+        }
+
+        let reason: String = match read_chain_block(client, &previous_block).await {
+            Ok(ChainBlock::Link { chain: previous_chain, previous_block: next })
+                if previous_chain == chain =>
+            {
+                report.blocks_checked += 1;
+                block_id = std::mem::replace(&mut previous_block, next);
+                continue;
+            },
+            Ok(ChainBlock::Start) => {
+                report.start_block = Some(previous_block);
+                return Ok(report);
+            },
+            Ok(ChainBlock::Link { chain: previous_chain, .. }) => {
+                format!("links to a block of the {} chain", previous_chain)
+            },
+            Ok(ChainBlock::Other(kind)) => format!("links to a {} block", kind),
+            Err(Error::BlockNotFound(_)) => {
+                String::from("is not on the node, it was pruned or never posted")
+            },
+            Err(err) => format!("does not resolve: {:#}", err),
+        };
+
+        report.broken_links.push(BrokenLink { block_id, previous_block, reason });
+        return Ok(report);
+    }
+}
+
+// Verify every chain with the given heads, up to WALK_CONCURRENCY of them at
+// once. The reports are in the order of the heads.
+pub async fn verify_chains(
+    client: &dyn BlockStore,
+    heads: &[String]
+) -> Result<Vec<VerifyReport>, Error> {
+    stream::iter(heads)
+        .map(|head| verify_chain(client, head))
+        .buffered(walk_concurrency()?)
+        .try_collect()
+        .await
+}
+
+// Print the reports, one line per chain followed by its broken links, or one
+// JSON object per chain when requested.
+pub fn print_verify_reports(reports: &[VerifyReport], json: bool) -> Result<(), Error> {
+    for report in reports {
+        if json {
+            JsonOutput::VerifyChain(report).print()?;
+            continue;
+        }
+
+        let chain: &str = report.chain.as_deref().unwrap_or("Empty");
+        match &report.start_block {
+            Some(start_block) if report.is_intact() => println!(
+                "{} chain {}: intact, {} blocks back to start block {}",
+                chain, report.head, report.blocks_checked, start_block
+            ),
+            _ => println!(
+                "{} chain {}: broken after {} blocks",
+                chain, report.head, report.blocks_checked
+            ),
+        };
+
+        for link in report.broken_links.iter() {
+            println!(
+                "  Broken link: block {} -> previous block {}: {}",
+                link.block_id, link.previous_block, link.reason
+            );
+        }
+    }
+
+    Ok(())
+}
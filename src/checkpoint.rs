@@ -0,0 +1,85 @@
+// Rust module to checkpoint a shipment run, so a run restarted with --resume
+// after a crash or reboot continues the chains it was posting, for the rest of
+// the duration, instead of starting the shipment over. The checkpoint is saved
+// after every posted block and removed once the shipment is delivered.
+
+use iota_sdk::types::block::BlockId;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::Path, time::Duration};
+
+use crate::{block_payload::TransportationLeg, custom_error::Error, receipt::ReceiptBlock};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCheckpoint {
+    pub initial_block_id: String,
+    // Position of the leg in progress among the carriers.
+    pub leg_index: usize,
+    // The block the leg in progress started from: the initial block, or the
+    // delivery of the leg before it.
+    pub leg_previous_block: String,
+    pub start_block: String,
+    // The head of every chain of the leg in progress, keyed by chain name.
+    pub heads: BTreeMap<String, String>,
+    // Time the leg in progress was sampled for before the checkpoint.
+    pub elapsed_secs: u64,
+    // The sampling cycles every chain of the leg in progress took, keyed by
+    // chain name, so a leg sampled for a count of cycles only takes the rest.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub cycles: BTreeMap<String, usize>,
+    // The legs delivered so far, and the heads of the reading chains they
+    // delivered.
+    pub legs: Vec<TransportationLeg>,
+    pub reading_heads: Vec<String>,
+    // The transportation blocks posted so far, with the networks they were
+    // posted to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<ReceiptBlock>,
+}
+
+impl RunCheckpoint {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        if !Path::new(path).exists() {
+            return Err(Error::Anyhow(anyhow::Error::msg(format!(
+                "No checkpoint to resume from at {}", path
+            ))));
+        }
+
+        let content: String = fs::read_to_string(path)?;
+
+        serde_json::from_str::<RunCheckpoint>(&content)
+            .map_err(|err| Error::Validation(format!("checkpoint {}: {}", path, err)))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // Remove the checkpoint of a delivered shipment. A missing checkpoint is
+    // already removed.
+    pub fn remove(path: &str) -> Result<(), Error> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs(self.elapsed_secs)
+    }
+
+    // The sampling cycles a chain of the leg in progress took.
+    pub fn cycles(&self, chain: &str) -> usize {
+        self.cycles.get(chain).copied().unwrap_or(0)
+    }
+
+    // The head of a chain of the leg in progress. A chain that posted nothing
+    // yet still starts from the start block.
+    pub fn head(&self, chain: &str) -> Result<BlockId, Error> {
+        let head: &String = self.heads.get(chain).unwrap_or(&self.start_block);
+
+        Ok(head.parse::<BlockId>()?)
+    }
+}
@@ -0,0 +1,166 @@
+// Rust module to check that a file CID is a syntactically valid IPFS content
+// identifier, so a document pointer recorded on the Tangle can resolve.
+// CIDv0 is a base58btc encoded sha2-256 multihash starting with Qm. CIDv1 is a
+// multibase prefix followed by the encoded version, content codec and
+// multihash; the base32, base58btc and base16 encodings are accepted.
+
+use crate::custom_error::Error;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+// Length of a CIDv0, and the start of the multihash it encodes: the sha2-256
+// code and its 32 byte digest length.
+const CID_V0_LENGTH: usize = 46;
+const CID_V0_MULTIHASH_PREFIX: [u8; 2] = [0x12, 0x20];
+const CID_V0_MULTIHASH_LENGTH: usize = 34;
+
+fn invalid_cid(reason: &str) -> Error {
+    Error::Validation(format!("fileCid: {}", reason))
+}
+
+fn decode_base58(encoded: &str) -> Option<Vec<u8>> {
+    // Little-endian digits of the decoded number.
+    let mut bytes: Vec<u8> = Vec::new();
+
+    for c in encoded.bytes() {
+        let mut carry: u32 = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    // Every leading 1 stands for a leading zero byte.
+    let zeros: usize = encoded.bytes().take_while(|&c| c == b'1').count();
+    bytes.extend(std::iter::repeat(0).take(zeros));
+    bytes.reverse();
+
+    Some(bytes)
+}
+
+// Unpadded RFC 4648 base32, in lower case.
+fn decode_base32(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+
+    for c in encoded.bytes() {
+        let value: u32 = BASE32_ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    // Left over bits are padding and must be zero.
+    if buffer != 0 {
+        return None;
+    }
+
+    Some(bytes)
+}
+
+fn decode_base16(encoded: &str) -> Option<Vec<u8>> {
+    if encoded.len() % 2 != 0 || !encoded.is_ascii() {
+        return None;
+    }
+
+    (0..encoded.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&encoded[i..i + 2], 16).ok())
+        .collect()
+}
+
+// Read an unsigned varint off the front of the bytes.
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value: u64 = 0;
+
+    for (i, &byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+// Check that the bytes are exactly one multihash, whose digest has the length
+// it declares.
+fn check_multihash(mut bytes: &[u8]) -> Result<(), Error> {
+    read_varint(&mut bytes).ok_or_else(|| invalid_cid("has no multihash code"))?;
+    let length: u64 = read_varint(&mut bytes)
+        .ok_or_else(|| invalid_cid("has no multihash length"))?;
+
+    if length == 0 || bytes.len() as u64 != length {
+        return Err(invalid_cid(&format!(
+            "declares a {} byte digest but carries {} bytes", length, bytes.len()
+        )));
+    }
+
+    Ok(())
+}
+
+fn validate_cid_v0(cid: &str) -> Result<(), Error> {
+    if cid.len() != CID_V0_LENGTH {
+        return Err(invalid_cid(&format!(
+            "a CIDv0 is {} characters long, got {}", CID_V0_LENGTH, cid.len()
+        )));
+    }
+
+    let bytes: Vec<u8> = decode_base58(cid)
+        .ok_or_else(|| invalid_cid("is not valid base58btc"))?;
+
+    if bytes.len() != CID_V0_MULTIHASH_LENGTH || !bytes.starts_with(&CID_V0_MULTIHASH_PREFIX) {
+        return Err(invalid_cid("a CIDv0 must be a sha2-256 multihash"));
+    }
+
+    Ok(())
+}
+
+fn validate_cid_v1(cid: &str) -> Result<(), Error> {
+    let prefix: char = cid.chars().next().unwrap_or_default();
+    let encoded: &str = &cid[prefix.len_utf8()..];
+    let decoded: Option<Vec<u8>> = match prefix {
+        'b' => decode_base32(encoded),
+        'B' => decode_base32(&encoded.to_ascii_lowercase()),
+        'z' => decode_base58(encoded),
+        'f' | 'F' => decode_base16(encoded),
+        _ => {
+            return Err(invalid_cid(&format!("has the unsupported multibase prefix {}", prefix)));
+        },
+    };
+    let mut bytes: &[u8] = &decoded
+        .ok_or_else(|| invalid_cid(&format!("is not valid for its multibase prefix {}", prefix)))?;
+
+    if read_varint(&mut bytes) != Some(1) {
+        return Err(invalid_cid("is not a version 1 CID"));
+    }
+    read_varint(&mut bytes).ok_or_else(|| invalid_cid("has no content codec"))?;
+
+    check_multihash(bytes)
+}
+
+// Check that the CID is a valid CIDv0 or CIDv1. The error names the fileCid
+// field, to be nested in the field holding the product info.
+pub fn validate_cid(cid: &str) -> Result<(), Error> {
+    if cid.is_empty() {
+        return Err(invalid_cid("must not be empty"));
+    }
+
+    if cid.starts_with("Qm") {
+        validate_cid_v0(cid)
+    } else {
+        validate_cid_v1(cid)
+    }
+}
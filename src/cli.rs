@@ -0,0 +1,195 @@
+// Rust module to parse the command-line arguments of the board.
+
+use std::env;
+
+use crate::{custom_error::Error, network::Network};
+
+// The command to run. Without a subcommand the board records a shipment.
+#[derive(Debug, Default, PartialEq)]
+pub enum Command {
+    #[default]
+    Run,
+    // Print the JSON Schema of the block payloads, or write it to a file.
+    Schema { output: Option<String> },
+    // Fetch a block and pretty-print its decoded payload.
+    Inspect { block_id: String },
+    // Walk the supply chain lineage of a block back to the raw materials.
+    Trace { block_id: String },
+    // Fetch a block and check the digest of its payload.
+    Verify { block_id: String, digest: String },
+    // Check the setup of a run without posting anything.
+    Validate,
+    // Print the health, network and protocol parameters of the node.
+    Info,
+    // Post a block and read it back, to check the node end to end.
+    Selftest,
+    // Post the blocks of a recorded shipment again.
+    Replay { path: String },
+    // List the blocks with the given tag of the shipment a block links back to.
+    Query { tag: String, block_id: String },
+    // Post the consumer block of a delivered shipment.
+    Consume { block_id: String },
+}
+
+impl Command {
+    // Build the command from its name and positional arguments.
+    fn from_positional(positional: Vec<String>) -> Result<Self, Error> {
+        let mut positional = positional.into_iter();
+
+        let command: Command = match positional.next().as_deref() {
+            None | Some("run") => Command::Run,
+            Some("validate") => Command::Validate,
+            Some("info") => Command::Info,
+            Some("selftest") => Command::Selftest,
+            Some("schema") => Command::Schema { output: positional.next() },
+            Some("inspect") => match positional.next() {
+                Some(block_id) => Command::Inspect { block_id },
+                None => return Err(Error::Anyhow(anyhow::Error::msg(
+                    "inspect requires a block id"
+                ))),
+            },
+            Some("trace") => match positional.next() {
+                Some(block_id) => Command::Trace { block_id },
+                None => return Err(Error::Anyhow(anyhow::Error::msg(
+                    "trace requires a block id"
+                ))),
+            },
+            Some("query") => match (positional.next(), positional.next()) {
+                (Some(tag), Some(block_id)) => Command::Query { tag, block_id },
+                _ => return Err(Error::Anyhow(anyhow::Error::msg(
+                    "query requires a tag and the id of a block of the shipment"
+                ))),
+            },
+            Some("consume") => match positional.next() {
+                Some(block_id) => Command::Consume { block_id },
+                None => return Err(Error::Anyhow(anyhow::Error::msg(
+                    "consume requires the id of a delivered transportation block"
+                ))),
+            },
+            Some("replay") => match positional.next() {
+                Some(path) => Command::Replay { path },
+                None => return Err(Error::Anyhow(anyhow::Error::msg(
+                    "replay requires the path of a shipment record"
+                ))),
+            },
+            Some("verify") => match (positional.next(), positional.next()) {
+                (Some(block_id), Some(digest)) => Command::Verify { block_id, digest },
+                _ => return Err(Error::Anyhow(anyhow::Error::msg(
+                    "verify requires a block id and a digest"
+                ))),
+            },
+            Some(name) => return Err(Error::Anyhow(anyhow::Error::msg(format!(
+                "Unknown command: {}", name
+            )))),
+        };
+
+        if let Some(arg) = positional.next() {
+            return Err(Error::Anyhow(anyhow::Error::msg(format!(
+                "Unexpected argument: {}", arg
+            ))));
+        }
+
+        Ok(command)
+    }
+}
+
+// Arguments given on the command line. Every argument is optional, with the
+// environment and interactive input as fallbacks.
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    pub command: Command,
+    pub block_id: Option<String>,
+    pub network: Option<Network>,
+    // Path the blocks posted by the run are recorded to, for a later replay.
+    pub record: Option<String>,
+    // Continue the run saved in the checkpoint instead of starting a new one.
+    pub resume: bool,
+    // Print the command results as JSON objects instead of prose.
+    pub json: bool,
+    // Print the decoded payload of every block a query finds.
+    pub payloads: bool,
+    // Number of sampling cycles of every leg, instead of the total duration.
+    pub count: Option<usize>,
+}
+
+impl CliArgs {
+    // Parse the arguments the program was started with.
+    pub fn parse() -> Result<Self, Error> {
+        Self::parse_from(env::args().skip(1))
+    }
+
+    // Parse the given arguments, without the program name. Options accept
+    // their value either as the next argument or after an equals sign. The
+    // remaining arguments are the subcommand and its arguments.
+    pub fn parse_from<I>(args: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut cli_args: CliArgs = CliArgs::default();
+        let mut positional: Vec<String> = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                positional.push(arg);
+                continue;
+            }
+
+            // Flags take no value.
+            match arg.as_str() {
+                "--resume" => {
+                    cli_args.resume = true;
+                    continue;
+                },
+                "--json" => {
+                    cli_args.json = true;
+                    continue;
+                },
+                "--payloads" => {
+                    cli_args.payloads = true;
+                    continue;
+                },
+                _ => {},
+            };
+
+            let (name, mut inline_value): (String, Option<String>) =
+                match arg.split_once('=') {
+                    Some((name, value)) => (name.to_owned(), Some(value.to_owned())),
+                    None => (arg, None),
+                };
+
+            let mut value = || -> Result<String, Error> {
+                match inline_value.take().or_else(|| args.next()) {
+                    Some(value) => Ok(value),
+                    None => Err(Error::Anyhow(anyhow::Error::msg(format!(
+                        "{} requires a value", name
+                    )))),
+                }
+            };
+
+            match name.as_str() {
+                "--block-id" => cli_args.block_id = Some(value()?),
+                "--record" => cli_args.record = Some(value()?),
+                "--network" => {
+                    let network: Network = value()?.parse::<Network>().map_err(|err| {
+                        Error::Validation(format!("value for --network: {}", err))
+                    })?;
+                    cli_args.network = Some(network);
+                },
+                "--count" => {
+                    let count: usize = value()?.parse::<usize>().map_err(|err| {
+                        Error::Validation(format!("value for --count: {}", err))
+                    })?;
+                    cli_args.count = Some(count);
+                },
+                _ => return Err(Error::Anyhow(anyhow::Error::msg(format!(
+                    "Unknown argument: {}", name
+                )))),
+            }
+        }
+
+        cli_args.command = Command::from_positional(positional)?;
+
+        Ok(cli_args)
+    }
+}
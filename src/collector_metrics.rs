@@ -0,0 +1,89 @@
+// Rust module with the operational metrics of the board itself, rendered in
+// the Prometheus text format: the blocks posted and failed per tag, the time
+// spent posting them, PoW included, and the uptime of the run.
+
+use prometheus::{
+    Encoder, Gauge, Histogram, HistogramOpts, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::time::{Duration, Instant};
+
+use crate::custom_error::Error;
+
+// The metrics are registered in a registry of their own rather than the
+// global one. Every metric is shared, so clones update the same values.
+#[derive(Clone)]
+pub struct CollectorMetrics {
+    registry: Registry,
+    blocks_posted: IntCounterVec,
+    posting_errors: IntCounterVec,
+    posting_duration: Histogram,
+    uptime: Gauge,
+    started: Instant,
+}
+
+fn prometheus_error(err: prometheus::Error) -> Error {
+    Error::Anyhow(anyhow::Error::new(err))
+}
+
+impl CollectorMetrics {
+    pub fn new() -> Result<Self, Error> {
+        let registry: Registry = Registry::new();
+
+        let blocks_posted: IntCounterVec = IntCounterVec::new(
+            Opts::new("board_blocks_posted_total", "Blocks posted, by tag"),
+            &["tag"]
+        ).map_err(prometheus_error)?;
+
+        let posting_errors: IntCounterVec = IntCounterVec::new(
+            Opts::new("board_posting_errors_total", "Blocks that failed to post, by tag"),
+            &["tag"]
+        ).map_err(prometheus_error)?;
+
+        // PoW takes from under a second on a remote node to a minute on a
+        // slow board, so the buckets span both.
+        let posting_duration: Histogram = Histogram::with_opts(
+            HistogramOpts::new(
+                "board_block_posting_seconds",
+                "Time spent posting a block, PoW included"
+            ).buckets(vec![0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0])
+        ).map_err(prometheus_error)?;
+
+        let uptime: Gauge = Gauge::new("board_uptime_seconds", "Seconds since the run started")
+            .map_err(prometheus_error)?;
+
+        registry.register(Box::new(blocks_posted.clone())).map_err(prometheus_error)?;
+        registry.register(Box::new(posting_errors.clone())).map_err(prometheus_error)?;
+        registry.register(Box::new(posting_duration.clone())).map_err(prometheus_error)?;
+        registry.register(Box::new(uptime.clone())).map_err(prometheus_error)?;
+
+        Ok(Self {
+            registry,
+            blocks_posted,
+            posting_errors,
+            posting_duration,
+            uptime,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn record_posted(&self, tag: &str, elapsed: Duration) {
+        self.blocks_posted.with_label_values(&[tag]).inc();
+        self.posting_duration.observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_posting_error(&self, tag: &str) {
+        self.posting_errors.with_label_values(&[tag]).inc();
+    }
+
+    // Render every metric in the Prometheus text format, along with its
+    // content type. The uptime is brought up to date first.
+    pub fn render(&self) -> Result<(String, String), Error> {
+        self.uptime.set(self.started.elapsed().as_secs_f64());
+
+        let encoder: TextEncoder = TextEncoder::new();
+        let mut buffer: Vec<u8> = Vec::new();
+        encoder.encode(&self.registry.gather(), &mut buffer).map_err(prometheus_error)?;
+
+        Ok((encoder.format_type().to_owned(), String::from_utf8(buffer)?))
+    }
+}
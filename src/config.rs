@@ -0,0 +1,521 @@
+// Rust module to load the configuration of the board.
+// Every setting can be given as an environment variable or in a TOML config
+// file, read from CONFIG_PATH or config.toml in the working directory. The
+// keys of the file are the lowercase names of the environment variables.
+// Environment variables override file values, which override the built-in
+// defaults.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, env, fs, path::Path, sync::OnceLock, time::Duration};
+
+use crate::{
+    block_payload::PaymentInfo, custom_error::Error, read_env_flag, read_env_flag_or,
+    read_env_parsed,
+};
+
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+pub const DEFAULT_SAMPLING_INTERVAL_SECS: u64 = 10;
+pub const DEFAULT_TOTAL_DURATION_SECS: u64 = 120;
+pub const DEFAULT_LOCATION_ORIGIN_LONGITUDE: f32 = 23.7275;
+pub const DEFAULT_LOCATION_ORIGIN_LATITUDE: f32 = 37.9838;
+pub const DEFAULT_LOCATION_STEP_DEGREES: f32 = 0.001;
+pub const DEFAULT_METRIC_BATCH_SIZE: usize = 1;
+pub const DEFAULT_MAX_BLOCKS: usize = 10_000;
+pub const DEFAULT_DEDUP_EPSILON: f64 = 0.0;
+pub const DEFAULT_DEDUP_MAX_REPEATS: usize = 3;
+pub const DEFAULT_SHIPMENT_JOURNAL_PATH: &str = "shipments.json";
+pub const DEFAULT_CHECKPOINT_PATH: &str = "checkpoint.json";
+pub const DEFAULT_EXPLORER_BLOCK_TEMPLATE: &str = "{base}/block/{block_id}";
+pub const DEFAULT_CARRIER: &str = "Transportation Company Information Data";
+pub const DEFAULT_CONSUMER_INFO: &str = "Consumer Information Data";
+pub const DEFAULT_METRIC_MODE: &str = "uniform";
+pub const DEFAULT_WALK_STEP_FRACTION: f64 = 0.05;
+pub const DEFAULT_READING_DECIMALS: u32 = 2;
+pub const DEFAULT_WALK_CONCURRENCY: usize = 8;
+pub const DEFAULT_POST_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_CONFIRMATION_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_PROGRESS_INTERVAL_SECS: u64 = 30;
+pub const DEFAULT_MQTT_TOPIC_PREFIX: &str = "board";
+
+// A metric defined in the configuration. Its readings are generated within
+// [min, max] unless a {NAME}_SOURCE_FILE is set, like the built-in metrics.
+// Without sampling_interval_secs it is sampled at the global interval.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomMetricConfig {
+    pub name: String,
+    pub min: f64,
+    pub max: f64,
+    pub unit: String,
+    pub tag: String,
+    #[serde(default)]
+    pub sampling_interval_secs: Option<u64>,
+}
+
+// Every setting of the board. Settings without a default are left unset when
+// they are neither in the environment nor in the config file.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AppConfig {
+    pub network: Option<String>,
+    pub node_url: Option<String>,
+    pub node_urls: Option<String>,
+    pub explorer_url: Option<String>,
+    pub explorer_block_template: Option<String>,
+    pub initial_block_id: Option<String>,
+    pub start_transportation_cid: Option<String>,
+    pub deliver_transportation_cid: Option<String>,
+    pub carriers: Option<String>,
+    pub consumer_info: Option<String>,
+    pub sampling_interval_secs: Option<u64>,
+    pub total_duration_secs: Option<u64>,
+    pub metric_count: Option<usize>,
+    pub temperature_source_file: Option<String>,
+    pub humidity_source_file: Option<String>,
+    pub temperature_sampling_interval_secs: Option<u64>,
+    pub humidity_sampling_interval_secs: Option<u64>,
+    pub pressure_sampling_interval_secs: Option<u64>,
+    pub shock_sampling_interval_secs: Option<u64>,
+    pub temperature_range: Option<String>,
+    pub humidity_range: Option<String>,
+    pub temperature_unit: Option<String>,
+    pub continue_temperature_head: Option<String>,
+    pub continue_humidity_head: Option<String>,
+    pub temperature_min_ok: Option<f64>,
+    pub temperature_max_ok: Option<f64>,
+    pub humidity_min_ok: Option<f64>,
+    pub humidity_max_ok: Option<f64>,
+    pub pressure_range: Option<String>,
+    pub pressure_source_file: Option<String>,
+    pub pressure_min_ok: Option<f64>,
+    pub pressure_max_ok: Option<f64>,
+    pub shock_range: Option<String>,
+    pub shock_source_file: Option<String>,
+    pub shock_min_ok: Option<f64>,
+    pub shock_max_ok: Option<f64>,
+    pub mqtt_broker_url: Option<String>,
+    pub mqtt_max_age_secs: Option<u64>,
+    pub temperature_mqtt_topic: Option<String>,
+    pub humidity_mqtt_topic: Option<String>,
+    pub pressure_mqtt_topic: Option<String>,
+    pub shock_mqtt_topic: Option<String>,
+    pub mode: Option<String>,
+    pub walk_step_fraction: Option<f64>,
+    pub reading_decimals: Option<u32>,
+    pub location_origin_longitude: Option<f32>,
+    pub location_origin_latitude: Option<f32>,
+    pub location_step_degrees: Option<f32>,
+    pub dry_run: Option<bool>,
+    pub random_seed: Option<u64>,
+    pub metrics_csv_path: Option<String>,
+    pub metrics_db_path: Option<String>,
+    pub timestamp_format: Option<String>,
+    pub sim_time_scale: Option<f64>,
+    pub metric_batch_size: Option<usize>,
+    pub max_blocks: Option<usize>,
+    pub http_port: Option<u16>,
+    pub progress_interval_secs: Option<u64>,
+    pub shipment_journal_path: Option<String>,
+    pub checkpoint_path: Option<String>,
+    pub force_new: Option<bool>,
+    pub receipt_json: Option<bool>,
+    pub local_pow: Option<bool>,
+    pub pow_worker_count: Option<usize>,
+    pub post_timeout_secs: Option<u64>,
+    pub wait_confirmation: Option<bool>,
+    pub confirmation_timeout_secs: Option<u64>,
+    pub max_posts_per_minute: Option<u32>,
+    pub verify_previous: Option<bool>,
+    pub disabled_validators: Option<String>,
+    pub dedup_metrics: Option<bool>,
+    pub dedup_epsilon: Option<f64>,
+    pub dedup_max_repeats: Option<usize>,
+    pub custom_metrics: Option<Vec<CustomMetricConfig>>,
+    pub metadata: Option<HashMap<String, String>>,
+    pub digest_log_path: Option<String>,
+    pub encryption_key: Option<String>,
+    pub signing_key: Option<String>,
+    pub payload_format: Option<String>,
+    pub sender_address: Option<String>,
+    pub payment_wallet_address: Option<String>,
+    pub payment_smr_cost: Option<f64>,
+    pub balance_check: Option<String>,
+    pub non_interactive: Option<bool>,
+    pub resolve_delivery_metrics: Option<bool>,
+    pub verify_chains: Option<bool>,
+    pub walk_concurrency: Option<usize>,
+}
+
+impl AppConfig {
+    // Load the settings of a TOML config file.
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        let content: String = fs::read_to_string(path)?;
+
+        toml::from_str::<AppConfig>(&content).map_err(|err| {
+            Error::Validation(format!("config file {}: {}", path, err))
+        })
+    }
+
+    // The built-in defaults.
+    pub fn with_defaults() -> Self {
+        Self {
+            sampling_interval_secs: Some(DEFAULT_SAMPLING_INTERVAL_SECS),
+            explorer_block_template: Some(String::from(DEFAULT_EXPLORER_BLOCK_TEMPLATE)),
+            total_duration_secs: Some(DEFAULT_TOTAL_DURATION_SECS),
+            mode: Some(String::from(DEFAULT_METRIC_MODE)),
+            walk_step_fraction: Some(DEFAULT_WALK_STEP_FRACTION),
+            reading_decimals: Some(DEFAULT_READING_DECIMALS),
+            location_origin_longitude: Some(DEFAULT_LOCATION_ORIGIN_LONGITUDE),
+            location_origin_latitude: Some(DEFAULT_LOCATION_ORIGIN_LATITUDE),
+            location_step_degrees: Some(DEFAULT_LOCATION_STEP_DEGREES),
+            dry_run: Some(false),
+            timestamp_format: Some(String::from("rfc3339")),
+            payload_format: Some(String::from("json")),
+            metric_batch_size: Some(DEFAULT_METRIC_BATCH_SIZE),
+            max_blocks: Some(DEFAULT_MAX_BLOCKS),
+            progress_interval_secs: Some(DEFAULT_PROGRESS_INTERVAL_SECS),
+            shipment_journal_path: Some(String::from(DEFAULT_SHIPMENT_JOURNAL_PATH)),
+            checkpoint_path: Some(String::from(DEFAULT_CHECKPOINT_PATH)),
+            force_new: Some(false),
+            receipt_json: Some(false),
+            local_pow: Some(true),
+            post_timeout_secs: Some(DEFAULT_POST_TIMEOUT_SECS),
+            wait_confirmation: Some(false),
+            confirmation_timeout_secs: Some(DEFAULT_CONFIRMATION_TIMEOUT_SECS),
+            verify_previous: Some(false),
+            non_interactive: Some(false),
+            resolve_delivery_metrics: Some(false),
+            verify_chains: Some(false),
+            walk_concurrency: Some(DEFAULT_WALK_CONCURRENCY),
+            balance_check: Some(String::from("warn")),
+            dedup_metrics: Some(false),
+            dedup_epsilon: Some(DEFAULT_DEDUP_EPSILON),
+            dedup_max_repeats: Some(DEFAULT_DEDUP_MAX_REPEATS),
+            ..Default::default()
+        }
+    }
+
+    pub fn sampling_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.sampling_interval_secs.unwrap_or(DEFAULT_SAMPLING_INTERVAL_SECS)
+        )
+    }
+
+    // Number of readings grouped into one batch block. A size of 1 posts every
+    // reading as its own metric block.
+    pub fn metric_batch_size(&self) -> usize {
+        self.metric_batch_size.unwrap_or(DEFAULT_METRIC_BATCH_SIZE).max(1)
+    }
+
+    // Number of chain blocks after which a run stops sampling and delivers.
+    // A cap of 0 turns it off.
+    pub fn max_blocks(&self) -> Option<usize> {
+        match self.max_blocks.unwrap_or(DEFAULT_MAX_BLOCKS) {
+            0 => None,
+            max_blocks => Some(max_blocks),
+        }
+    }
+
+    // How often the progress of a run is printed when stdout is not a
+    // terminal. An interval of 0 turns the progress report off.
+    pub fn progress_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.progress_interval_secs.unwrap_or(DEFAULT_PROGRESS_INTERVAL_SECS)
+        )
+    }
+
+    pub fn shipment_journal_path(&self) -> String {
+        self.shipment_journal_path
+            .clone()
+            .unwrap_or_else(|| String::from(DEFAULT_SHIPMENT_JOURNAL_PATH))
+    }
+
+    pub fn checkpoint_path(&self) -> String {
+        self.checkpoint_path
+            .clone()
+            .unwrap_or_else(|| String::from(DEFAULT_CHECKPOINT_PATH))
+    }
+
+    // The payment info of the delivery, when given in the configuration
+    // instead of being read from the initial block. Setting only one of its
+    // two settings is an error, rather than silently fetching the block.
+    pub fn payment_info(&self) -> Result<Option<PaymentInfo>, Error> {
+        match (&self.payment_wallet_address, self.payment_smr_cost) {
+            (Some(wallet_address), Some(smr_cost)) => Ok(Some(PaymentInfo {
+                wallet_address: wallet_address.to_owned(),
+                smr_cost,
+            })),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(Error::Validation(String::from(
+                "PAYMENT_SMR_COST: must be set along with PAYMENT_WALLET_ADDRESS"
+            ))),
+            (None, Some(_)) => Err(Error::Validation(String::from(
+                "PAYMENT_WALLET_ADDRESS: must be set along with PAYMENT_SMR_COST"
+            ))),
+        }
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        Duration::from_secs(
+            self.total_duration_secs.unwrap_or(DEFAULT_TOTAL_DURATION_SECS)
+        )
+    }
+}
+
+// The values of the config file, keyed by setting name. The file is read once,
+// the first time a setting is looked up.
+static CONFIG_FILE: OnceLock<Result<toml::Table, String>> = OnceLock::new();
+
+fn load_config_file() -> Result<toml::Table, String> {
+    let path: String = env::var("CONFIG_PATH")
+        .unwrap_or_else(|_err| DEFAULT_CONFIG_PATH.to_owned());
+
+    if !Path::new(&path).exists() {
+        return Ok(toml::Table::new());
+    }
+
+    // Deserialize into AppConfig first, so unknown keys and mistyped values
+    // are reported instead of being silently ignored.
+    AppConfig::from_file(&path).map_err(|err| err.to_string())?;
+
+    let content: String = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    content.parse::<toml::Table>().map_err(|err| err.to_string())
+}
+
+// Look up a setting in the config file by its environment variable name.
+// Returns None when the config file does not exist or does not set it.
+pub fn config_file_value(var: &str) -> Result<Option<String>, Error> {
+    let value: Option<String> = config_file_toml_value(var)?
+        .map(|value| match value {
+            toml::Value::String(value) => value,
+            value => value.to_string(),
+        });
+
+    Ok(value)
+}
+
+// Look up a setting in the config file by its environment variable name,
+// keeping its TOML type, for settings that are not a single value.
+pub fn config_file_toml_value(var: &str) -> Result<Option<toml::Value>, Error> {
+    let table: &toml::Table = CONFIG_FILE
+        .get_or_init(load_config_file)
+        .as_ref()
+        .map_err(|err| Error::Anyhow(anyhow::Error::msg(err.to_owned())))?;
+
+    Ok(table.get(&var.to_lowercase()).cloned())
+}
+
+// Read the custom metrics from CUSTOM_METRICS, given as a JSON array, or from
+// the custom_metrics array of tables of the config file.
+pub fn read_custom_metrics() -> Result<Option<Vec<CustomMetricConfig>>, Error> {
+    let invalid_metrics = |err: String| Error::Validation(format!(
+        "CUSTOM_METRICS: {}", err
+    ));
+
+    if let Ok(value) = env::var("CUSTOM_METRICS") {
+        let metrics: Vec<CustomMetricConfig> = serde_json::from_str(&value)
+            .map_err(|err| invalid_metrics(err.to_string()))?;
+        return Ok(Some(metrics));
+    }
+
+    match config_file_toml_value("CUSTOM_METRICS")? {
+        Some(value) => {
+            let metrics: Vec<CustomMetricConfig> = value
+                .try_into()
+                .map_err(|err: toml::de::Error| invalid_metrics(err.to_string()))?;
+            Ok(Some(metrics))
+        },
+        None => Ok(None),
+    }
+}
+
+// Read the metadata blocks are annotated with from METADATA, given as a JSON
+// object of strings, or from the metadata table of the config file. An empty
+// object annotates nothing.
+pub fn read_metadata() -> Result<Option<HashMap<String, String>>, Error> {
+    let invalid_metadata = |err: String| Error::Validation(format!("METADATA: {}", err));
+
+    let metadata: HashMap<String, String> = if let Ok(value) = env::var("METADATA") {
+        serde_json::from_str(&value).map_err(|err| invalid_metadata(err.to_string()))?
+    } else {
+        match config_file_toml_value("METADATA")? {
+            Some(value) => value
+                .try_into()
+                .map_err(|err: toml::de::Error| invalid_metadata(err.to_string()))?,
+            None => return Ok(None),
+        }
+    };
+
+    Ok(Some(metadata).filter(|metadata| !metadata.is_empty()))
+}
+
+// Check the number of sampling cycles of a leg, which ends a leg after a
+// count of cycles instead of the total duration, so both can not be set.
+pub fn check_metric_count(
+    metric_count: Option<usize>,
+    total_duration_secs: Option<u64>
+) -> Result<(), Error> {
+    match (metric_count, total_duration_secs) {
+        (Some(0), _) => Err(Error::Validation(String::from("METRIC_COUNT: must be positive"))),
+        (Some(_), Some(_)) => Err(Error::Validation(String::from(
+            "METRIC_COUNT: must not be set along with TOTAL_DURATION_SECS"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// Read METRIC_COUNT, checked against the total duration as set, before it
+// falls back to its default.
+pub fn read_metric_count() -> Result<Option<usize>, Error> {
+    let metric_count: Option<usize> = read_env_parsed("METRIC_COUNT")?;
+    check_metric_count(metric_count, read_env_parsed("TOTAL_DURATION_SECS")?)?;
+
+    Ok(metric_count)
+}
+
+// Resolve every setting of the board: the environment overrides the config
+// file, which overrides the built-in defaults.
+pub fn resolve_config() -> Result<AppConfig, Error> {
+    let defaults: AppConfig = AppConfig::with_defaults();
+
+    Ok(AppConfig {
+        network: read_env_parsed("NETWORK")?.or(defaults.network),
+        node_url: read_env_parsed("NODE_URL")?.or(defaults.node_url),
+        node_urls: read_env_parsed("NODE_URLS")?.or(defaults.node_urls),
+        explorer_url: read_env_parsed("EXPLORER_URL")?.or(defaults.explorer_url),
+        explorer_block_template: read_env_parsed("EXPLORER_BLOCK_TEMPLATE")?
+            .or(defaults.explorer_block_template),
+        initial_block_id: read_env_parsed("INITIAL_BLOCK_ID")?
+            .or(defaults.initial_block_id),
+        start_transportation_cid: read_env_parsed("START_TRANSPORTATION_CID")?
+            .or(defaults.start_transportation_cid),
+        deliver_transportation_cid: read_env_parsed("DELIVER_TRANSPORTATION_CID")?
+            .or(defaults.deliver_transportation_cid),
+        carriers: read_env_parsed("CARRIERS")?.or(defaults.carriers),
+        consumer_info: read_env_parsed("CONSUMER_INFO")?.or(defaults.consumer_info),
+        sampling_interval_secs: read_env_parsed("SAMPLING_INTERVAL_SECS")?
+            .or(defaults.sampling_interval_secs),
+        total_duration_secs: read_env_parsed("TOTAL_DURATION_SECS")?
+            .or(defaults.total_duration_secs),
+        metric_count: read_metric_count()?.or(defaults.metric_count),
+        temperature_source_file: read_env_parsed("TEMPERATURE_SOURCE_FILE")?
+            .or(defaults.temperature_source_file),
+        humidity_source_file: read_env_parsed("HUMIDITY_SOURCE_FILE")?
+            .or(defaults.humidity_source_file),
+        temperature_sampling_interval_secs: read_env_parsed("TEMPERATURE_SAMPLING_INTERVAL_SECS")?
+            .or(defaults.temperature_sampling_interval_secs),
+        humidity_sampling_interval_secs: read_env_parsed("HUMIDITY_SAMPLING_INTERVAL_SECS")?
+            .or(defaults.humidity_sampling_interval_secs),
+        pressure_sampling_interval_secs: read_env_parsed("PRESSURE_SAMPLING_INTERVAL_SECS")?
+            .or(defaults.pressure_sampling_interval_secs),
+        shock_sampling_interval_secs: read_env_parsed("SHOCK_SAMPLING_INTERVAL_SECS")?
+            .or(defaults.shock_sampling_interval_secs),
+        temperature_range: read_env_parsed("TEMPERATURE_RANGE")?
+            .or(defaults.temperature_range),
+        humidity_range: read_env_parsed("HUMIDITY_RANGE")?.or(defaults.humidity_range),
+        temperature_unit: read_env_parsed("TEMPERATURE_UNIT")?
+            .or(defaults.temperature_unit),
+        continue_temperature_head: read_env_parsed("CONTINUE_TEMPERATURE_HEAD")?
+            .or(defaults.continue_temperature_head),
+        continue_humidity_head: read_env_parsed("CONTINUE_HUMIDITY_HEAD")?
+            .or(defaults.continue_humidity_head),
+        temperature_min_ok: read_env_parsed("TEMPERATURE_MIN_OK")?
+            .or(defaults.temperature_min_ok),
+        temperature_max_ok: read_env_parsed("TEMPERATURE_MAX_OK")?
+            .or(defaults.temperature_max_ok),
+        humidity_min_ok: read_env_parsed("HUMIDITY_MIN_OK")?
+            .or(defaults.humidity_min_ok),
+        humidity_max_ok: read_env_parsed("HUMIDITY_MAX_OK")?
+            .or(defaults.humidity_max_ok),
+        pressure_range: read_env_parsed("PRESSURE_RANGE")?.or(defaults.pressure_range),
+        pressure_source_file: read_env_parsed("PRESSURE_SOURCE_FILE")?
+            .or(defaults.pressure_source_file),
+        pressure_min_ok: read_env_parsed("PRESSURE_MIN_OK")?
+            .or(defaults.pressure_min_ok),
+        pressure_max_ok: read_env_parsed("PRESSURE_MAX_OK")?
+            .or(defaults.pressure_max_ok),
+        shock_range: read_env_parsed("SHOCK_RANGE")?.or(defaults.shock_range),
+        shock_source_file: read_env_parsed("SHOCK_SOURCE_FILE")?
+            .or(defaults.shock_source_file),
+        shock_min_ok: read_env_parsed("SHOCK_MIN_OK")?.or(defaults.shock_min_ok),
+        shock_max_ok: read_env_parsed("SHOCK_MAX_OK")?.or(defaults.shock_max_ok),
+        mqtt_broker_url: read_env_parsed("MQTT_BROKER_URL")?.or(defaults.mqtt_broker_url),
+        mqtt_max_age_secs: read_env_parsed("MQTT_MAX_AGE_SECS")?
+            .or(defaults.mqtt_max_age_secs),
+        temperature_mqtt_topic: read_env_parsed("TEMPERATURE_MQTT_TOPIC")?
+            .or(defaults.temperature_mqtt_topic),
+        humidity_mqtt_topic: read_env_parsed("HUMIDITY_MQTT_TOPIC")?
+            .or(defaults.humidity_mqtt_topic),
+        pressure_mqtt_topic: read_env_parsed("PRESSURE_MQTT_TOPIC")?
+            .or(defaults.pressure_mqtt_topic),
+        shock_mqtt_topic: read_env_parsed("SHOCK_MQTT_TOPIC")?.or(defaults.shock_mqtt_topic),
+        mode: read_env_parsed("MODE")?.or(defaults.mode),
+        walk_step_fraction: read_env_parsed("WALK_STEP_FRACTION")?
+            .or(defaults.walk_step_fraction),
+        reading_decimals: read_env_parsed("READING_DECIMALS")?.or(defaults.reading_decimals),
+        location_origin_longitude: read_env_parsed("LOCATION_ORIGIN_LONGITUDE")?
+            .or(defaults.location_origin_longitude),
+        location_origin_latitude: read_env_parsed("LOCATION_ORIGIN_LATITUDE")?
+            .or(defaults.location_origin_latitude),
+        location_step_degrees: read_env_parsed("LOCATION_STEP_DEGREES")?
+            .or(defaults.location_step_degrees),
+        dry_run: Some(read_env_flag("DRY_RUN")).or(defaults.dry_run),
+        random_seed: read_env_parsed("RANDOM_SEED")?.or(defaults.random_seed),
+        metrics_csv_path: read_env_parsed("METRICS_CSV_PATH")?
+            .or(defaults.metrics_csv_path),
+        metrics_db_path: read_env_parsed("METRICS_DB_PATH")?
+            .or(defaults.metrics_db_path),
+        timestamp_format: read_env_parsed("TIMESTAMP_FORMAT")?
+            .or(defaults.timestamp_format),
+        sim_time_scale: read_env_parsed("SIM_TIME_SCALE")?.or(defaults.sim_time_scale),
+        metric_batch_size: read_env_parsed("METRIC_BATCH_SIZE")?
+            .or(defaults.metric_batch_size),
+        max_blocks: read_env_parsed("MAX_BLOCKS")?.or(defaults.max_blocks),
+        http_port: read_env_parsed("HTTP_PORT")?.or(defaults.http_port),
+        progress_interval_secs: read_env_parsed("PROGRESS_INTERVAL_SECS")?
+            .or(defaults.progress_interval_secs),
+        shipment_journal_path: read_env_parsed("SHIPMENT_JOURNAL_PATH")?
+            .or(defaults.shipment_journal_path),
+        checkpoint_path: read_env_parsed("CHECKPOINT_PATH")?
+            .or(defaults.checkpoint_path),
+        force_new: Some(read_env_flag("FORCE_NEW")).or(defaults.force_new),
+        receipt_json: Some(read_env_flag("RECEIPT_JSON")).or(defaults.receipt_json),
+        local_pow: Some(read_env_flag_or("LOCAL_POW", true)).or(defaults.local_pow),
+        pow_worker_count: read_env_parsed("POW_WORKER_COUNT")?
+            .or(defaults.pow_worker_count),
+        post_timeout_secs: read_env_parsed("POST_TIMEOUT_SECS")?
+            .or(defaults.post_timeout_secs),
+        wait_confirmation: Some(read_env_flag("WAIT_CONFIRMATION"))
+            .or(defaults.wait_confirmation),
+        confirmation_timeout_secs: read_env_parsed("CONFIRMATION_TIMEOUT_SECS")?
+            .or(defaults.confirmation_timeout_secs),
+        max_posts_per_minute: read_env_parsed("MAX_POSTS_PER_MINUTE")?
+            .or(defaults.max_posts_per_minute),
+        verify_previous: Some(read_env_flag("VERIFY_PREVIOUS"))
+            .or(defaults.verify_previous),
+        disabled_validators: read_env_parsed("DISABLED_VALIDATORS")?
+            .or(defaults.disabled_validators),
+        dedup_metrics: Some(read_env_flag("DEDUP_METRICS")).or(defaults.dedup_metrics),
+        dedup_epsilon: read_env_parsed("DEDUP_EPSILON")?.or(defaults.dedup_epsilon),
+        dedup_max_repeats: read_env_parsed("DEDUP_MAX_REPEATS")?
+            .or(defaults.dedup_max_repeats),
+        custom_metrics: read_custom_metrics()?.or(defaults.custom_metrics),
+        metadata: read_metadata()?.or(defaults.metadata),
+        digest_log_path: read_env_parsed("DIGEST_LOG_PATH")?
+            .or(defaults.digest_log_path),
+        encryption_key: read_env_parsed("ENCRYPTION_KEY")?.or(defaults.encryption_key),
+        signing_key: read_env_parsed("SIGNING_KEY")?.or(defaults.signing_key),
+        payload_format: read_env_parsed("PAYLOAD_FORMAT")?.or(defaults.payload_format),
+        sender_address: read_env_parsed("SENDER_ADDRESS")?.or(defaults.sender_address),
+        payment_wallet_address: read_env_parsed("PAYMENT_WALLET_ADDRESS")?
+            .or(defaults.payment_wallet_address),
+        payment_smr_cost: read_env_parsed("PAYMENT_SMR_COST")?.or(defaults.payment_smr_cost),
+        balance_check: read_env_parsed("BALANCE_CHECK")?.or(defaults.balance_check),
+        non_interactive: Some(read_env_flag("NON_INTERACTIVE"))
+            .or(defaults.non_interactive),
+        resolve_delivery_metrics: Some(read_env_flag("RESOLVE_DELIVERY_METRICS"))
+            .or(defaults.resolve_delivery_metrics),
+        verify_chains: Some(read_env_flag("VERIFY_CHAINS")).or(defaults.verify_chains),
+        walk_concurrency: read_env_parsed("WALK_CONCURRENCY")?.or(defaults.walk_concurrency),
+    })
+}
@@ -0,0 +1,63 @@
+// Rust module to wait for a posted block to be confirmed. A node hands out
+// the id of a block as soon as it accepts it, while the block may still be
+// reattached or never referenced by a milestone, so with WAIT_CONFIRMATION
+// set the next block of a chain is only posted once its parent is referenced.
+
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::DEFAULT_CONFIRMATION_TIMEOUT_SECS, custom_error::Error, read_env_flag,
+    read_env_parsed,
+};
+
+// Time between two checks of the metadata of a block. Milestones are issued
+// every few seconds, so checking more often gains nothing.
+pub const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Read WAIT_CONFIRMATION and CONFIRMATION_TIMEOUT_SECS: how long a posted block
+// is waited on to be referenced, or None if posts return right away.
+pub fn read_confirmation_timeout() -> Result<Option<Duration>, Error> {
+    if !read_env_flag("WAIT_CONFIRMATION") {
+        return Ok(None);
+    }
+
+    let timeout_secs: u64 = read_env_parsed("CONFIRMATION_TIMEOUT_SECS")?
+        .unwrap_or(DEFAULT_CONFIRMATION_TIMEOUT_SECS);
+    if timeout_secs == 0 {
+        return Err(Error::Validation(String::from(
+            "CONFIRMATION_TIMEOUT_SECS: must be positive"
+        )));
+    }
+
+    Ok(Some(Duration::from_secs(timeout_secs)))
+}
+
+// Check whether a block is referenced every poll interval until it is, or
+// fail with a timeout once the timeout has passed. A failing check fails the
+// wait.
+pub async fn wait_until_referenced<F, Fut>(
+    mut is_referenced: F,
+    timeout: Duration,
+    poll_interval: Duration
+) -> Result<(), Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<bool, Error>>,
+{
+    let deadline: Instant = Instant::now() + timeout;
+
+    loop {
+        if is_referenced().await? {
+            return Ok(());
+        }
+
+        let now: Instant = Instant::now();
+        if now >= deadline {
+            return Err(Error::Timeout(timeout));
+        }
+        tokio::time::sleep(poll_interval.min(deadline - now)).await;
+    }
+}
@@ -7,7 +7,8 @@ use iota_sdk::{
 };
 use std::{
     env::VarError, 
-    string::FromUtf8Error
+    string::FromUtf8Error,
+    time::Duration,
 };
 use serde_json::error::Error as SerdeError;
 
@@ -42,4 +43,72 @@ pub enum Error {
     // Serde JSON Errro
     #[error(transparent)]
     SerdeError(#[from] SerdeError),
+
+    // A block id that is not a 0x prefixed, hex encoded 32 byte id, with the
+    // reason it was rejected.
+    #[error("Invalid BlockId: {0}")]
+    InvalidBlockId(String),
+
+    // Block data or a setting that breaks an invariant, such as an empty
+    // field or an out-of-range value, given as the field and the reason.
+    #[error("Invalid {0}")]
+    Validation(String),
+
+    // An operation that did not complete in time, e.g. a block post over a
+    // hung connection to the node. Trying again may succeed.
+    #[error("Timed out after {} seconds", .0.as_secs())]
+    Timeout(Duration),
+
+    // A block the node does not hold, with its id. Public nodes prune old
+    // blocks, so this is the expected end of a walk back through old chains.
+    #[error("Block {0} was not found on the node, it may have been pruned")]
+    BlockNotFound(String),
+
+    // Error fetching or decoding a specific block, with the block id and, if
+    // the block was fetched, its tag and the start of its payload.
+    #[error(
+        "Block {block_id}{}: {cause}",
+        payload_context(.tag, .payload_preview)
+    )]
+    BlockContext {
+        block_id: String,
+        tag: Option<String>,
+        payload_preview: Option<String>,
+        cause: Box<Error>,
+    },
+}
+
+// Number of payload bytes quoted in a block context error.
+const PAYLOAD_PREVIEW_BYTES: usize = 100;
+
+fn payload_context(tag: &Option<String>, payload_preview: &Option<String>) -> String {
+    match (tag, payload_preview) {
+        (Some(tag), Some(preview)) => format!(" (tag {:?}, payload {:?})", tag, preview),
+        (Some(tag), None) => format!(" (tag {:?})", tag),
+        (None, Some(preview)) => format!(" (payload {:?})", preview),
+        (None, None) => String::new(),
+    }
+}
+
+impl Error {
+    // Attach the block the error came from, along with its tag and the first
+    // bytes of its payload when known.
+    pub fn with_block_context(
+        self,
+        block_id: &str,
+        tag: Option<&[u8]>,
+        payload: Option<&[u8]>
+    ) -> Self {
+        let payload_preview: Option<String> = payload.map(|payload| {
+            let end: usize = payload.len().min(PAYLOAD_PREVIEW_BYTES);
+            String::from_utf8_lossy(&payload[..end]).into_owned()
+        });
+
+        Error::BlockContext {
+            block_id: block_id.to_owned(),
+            tag: tag.map(|tag| String::from_utf8_lossy(tag).into_owned()),
+            payload_preview,
+            cause: Box::new(self),
+        }
+    }
 }
\ No newline at end of file
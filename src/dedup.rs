@@ -0,0 +1,133 @@
+// Rust module to keep a stuck sensor from filling a metric chain with identical
+// blocks. Once a reading repeats for the configured number of consecutive
+// readings, a single StuckSensorData note is posted instead, referencing the
+// last metric block, and further repeats are skipped until the reading changes.
+
+use iota_sdk::types::block::BlockId;
+use tracing::warn;
+
+use crate::{
+    block_payload::{BlockRef, MetricData, StuckSensorData},
+    block_store::{post_block, BlockStore},
+    block_tag::BlockTag,
+    config::{DEFAULT_DEDUP_EPSILON, DEFAULT_DEDUP_MAX_REPEATS},
+    custom_error::Error,
+    read_env_flag, read_env_parsed,
+    timestamp::{now_timestamp, TimestampFormat},
+};
+
+// What to do with a new reading of a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateAction {
+    Post,
+    PostStuckSensorNote,
+    Skip,
+}
+
+// The consecutive identical readings of a single metric.
+#[derive(Debug, Clone)]
+pub struct DuplicateTracker {
+    epsilon: f64,
+    max_repeats: usize,
+    last_value: Option<f64>,
+    consecutive_readings: usize,
+}
+
+impl DuplicateTracker {
+    pub fn new(epsilon: f64, max_repeats: usize) -> Self {
+        Self { epsilon, max_repeats, last_value: None, consecutive_readings: 0 }
+    }
+
+    // Read DEDUP_METRICS, DEDUP_EPSILON and DEDUP_MAX_REPEATS. Returns None
+    // when deduplication is off.
+    pub fn from_env() -> Result<Option<Self>, Error> {
+        if !read_env_flag("DEDUP_METRICS") {
+            return Ok(None);
+        }
+
+        let epsilon: f64 = read_env_parsed::<f64>("DEDUP_EPSILON")?
+            .unwrap_or(DEFAULT_DEDUP_EPSILON);
+        let max_repeats: usize = read_env_parsed::<usize>("DEDUP_MAX_REPEATS")?
+            .unwrap_or(DEFAULT_DEDUP_MAX_REPEATS);
+
+        if !epsilon.is_finite() || epsilon < 0.0 {
+            return Err(Error::Validation(String::from(
+                "DEDUP_EPSILON: must be a non-negative number"
+            )));
+        }
+
+        if max_repeats < 2 {
+            return Err(Error::Validation(String::from(
+                "DEDUP_MAX_REPEATS: must be at least 2"
+            )));
+        }
+
+        Ok(Some(Self::new(epsilon, max_repeats)))
+    }
+
+    pub fn consecutive_readings(&self) -> usize {
+        self.consecutive_readings
+    }
+
+    // Record a new reading. Readings within epsilon of the last one count as
+    // repeats. The reading that makes max_repeats consecutive identical
+    // readings is replaced by a stuck sensor note, and later repeats are
+    // skipped.
+    pub fn observe(&mut self, value: f64) -> DuplicateAction {
+        let repeated: bool = matches!(
+            self.last_value,
+            Some(last_value) if (value - last_value).abs() <= self.epsilon
+        );
+
+        if !repeated {
+            self.last_value = Some(value);
+            self.consecutive_readings = 1;
+            return DuplicateAction::Post;
+        }
+
+        self.consecutive_readings += 1;
+
+        if self.consecutive_readings < self.max_repeats {
+            DuplicateAction::Post
+        } else if self.consecutive_readings == self.max_repeats {
+            DuplicateAction::PostStuckSensorNote
+        } else {
+            DuplicateAction::Skip
+        }
+    }
+}
+
+// Post a note that the sensor of a metric looks stuck, referencing the last
+// metric block of its chain. Returns the id of the note block.
+pub async fn post_stuck_sensor_note(
+    client: &dyn BlockStore,
+    metric_data: &MetricData,
+    consecutive_readings: usize,
+    metric_block_id: &String
+) -> Result<BlockId, Error> {
+    warn!(
+        "{} sensor looks stuck at {} {} for {} readings",
+        metric_data.metric_type,
+        metric_data.metric_value,
+        metric_data.measurement_unit,
+        consecutive_readings
+    );
+
+    let stuck_sensor_data: StuckSensorData = StuckSensorData::new(
+        metric_data.metric_type.to_owned(),
+        metric_data.metric_value,
+        consecutive_readings,
+        now_timestamp(TimestampFormat::from_env()?)?,
+        BlockRef::new(metric_block_id.to_owned())?
+    );
+
+    let data: Vec<u8> = serde_json::to_string(&stuck_sensor_data)?
+        .as_bytes()
+        .to_vec();
+
+    let tag: Vec<u8> = BlockTag::StuckSensor.as_bytes();
+
+    let block_id: BlockId = post_block(client, tag, data).await?;
+
+    Ok(block_id)
+}
@@ -0,0 +1,41 @@
+// Rust module to audit the integrity of posted payloads.
+// The SHA-256 digest of every posted payload is logged, and optionally stored,
+// so a block can later be fetched again and checked against it.
+
+use sha2::{Digest, Sha256};
+use std::{fs::OpenOptions, io::Write};
+
+use iota_sdk::types::block::BlockDto;
+
+use crate::{custom_error::Error, extract_raw_tag_and_data, tangle_client::TangleClient};
+
+// Hex encoded SHA-256 digest of a payload.
+pub fn payload_digest(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// Append the digest of a posted block to the digest log at path, one
+// block_id,digest line per block.
+pub fn record_digest(path: &str, block_id: &str, digest: &str) -> Result<(), Error> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{},{}", block_id, digest)?;
+    Ok(())
+}
+
+// Fetch a block again and check the digest of its data against the expected
+// one. Returns whether the digests match.
+pub async fn verify_block(
+    client: &TangleClient,
+    block_id: &String,
+    expected_digest: &str
+) -> Result<bool, Error> {
+    let block: BlockDto = client.get_block(block_id).await?;
+
+    // The digest covers the data as posted, in whatever format.
+    let (_tag, data): (Vec<u8>, Vec<u8>) = extract_raw_tag_and_data(block)?;
+
+    Ok(payload_digest(&data).eq_ignore_ascii_case(expected_digest.trim()))
+}
@@ -0,0 +1,132 @@
+// Rust module to encrypt the payment info of posted payloads.
+// Tagged data is readable by anyone reading the Tangle, so with ENCRYPTION_KEY
+// set the payment info is sealed with AES-256-GCM before it is posted, and
+// opened again with the same key when a block is read. Without the key the
+// payment info is posted in cleartext.
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, Nonce, OsRng},
+    Aes256Gcm, Key,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::{
+    block_payload::{BlockData, EncryptedPaymentInfo, PaymentInfo, PostedPaymentInfo},
+    custom_error::Error,
+    read_env_var,
+};
+
+// AES-256 keys are 32 bytes long and AES-GCM nonces 12 bytes long.
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+fn encryption_error(reason: &str) -> Error {
+    Error::Anyhow(anyhow::Error::msg(format!("Payment info encryption failed: {}", reason)))
+}
+
+fn decode_base64(value: &str, field: &str) -> Result<Vec<u8>, Error> {
+    STANDARD.decode(value.trim()).map_err(|err| Error::Validation(format!(
+        "{}: not valid base64: {}", field, err
+    )))
+}
+
+// Parse a base64 encoded 32 byte AES-256 key.
+pub fn parse_encryption_key(value: &str) -> Result<[u8; KEY_LENGTH], Error> {
+    let key: Vec<u8> = decode_base64(value, "ENCRYPTION_KEY")?;
+
+    key.as_slice().try_into().map_err(|_err| Error::Validation(format!(
+        "ENCRYPTION_KEY: must be {} bytes long, got {}", KEY_LENGTH, key.len()
+    )))
+}
+
+// Read the key from ENCRYPTION_KEY. Returns None when it is not set.
+pub fn read_encryption_key() -> Result<Option<[u8; KEY_LENGTH]>, Error> {
+    match read_env_var("ENCRYPTION_KEY".to_string()) {
+        Ok(value) => Ok(Some(parse_encryption_key(&value)?)),
+        Err(_err) => Ok(None),
+    }
+}
+
+// Seal the payment info with a fresh random nonce.
+pub fn encrypt_payment_info(
+    payment_info: &PaymentInfo,
+    key: &[u8; KEY_LENGTH]
+) -> Result<EncryptedPaymentInfo, Error> {
+    let cipher: Aes256Gcm = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce: Nonce<Aes256Gcm> = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let plaintext: Vec<u8> = serde_json::to_vec(payment_info)?;
+    let ciphertext: Vec<u8> = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_err| encryption_error("could not seal the payment info"))?;
+
+    Ok(EncryptedPaymentInfo {
+        ciphertext: STANDARD.encode(ciphertext),
+        nonce: STANDARD.encode(nonce),
+    })
+}
+
+// Open sealed payment info. Fails when the key is not the one it was sealed
+// with or the ciphertext was tampered with.
+pub fn decrypt_payment_info(
+    encrypted: &EncryptedPaymentInfo,
+    key: &[u8; KEY_LENGTH]
+) -> Result<PaymentInfo, Error> {
+    let nonce: Vec<u8> = decode_base64(&encrypted.nonce, "paymentInfo.nonce")?;
+    if nonce.len() != NONCE_LENGTH {
+        return Err(Error::Validation(format!(
+            "paymentInfo.nonce: must be {} bytes long, got {}", NONCE_LENGTH, nonce.len()
+        )));
+    }
+    let ciphertext: Vec<u8> = decode_base64(&encrypted.ciphertext, "paymentInfo.ciphertext")?;
+
+    let cipher: Aes256Gcm = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let plaintext: Vec<u8> = cipher
+        .decrypt(Nonce::<Aes256Gcm>::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_err| Error::Anyhow(anyhow::Error::msg(
+            "Failed to decrypt the payment info: wrong ENCRYPTION_KEY or tampered data"
+        )))?;
+
+    Ok(serde_json::from_slice::<PaymentInfo>(&plaintext)?)
+}
+
+// Prepare payment info for posting: sealed when a key is given, in cleartext
+// otherwise.
+pub fn seal_payment_info(
+    payment_info: PaymentInfo,
+    key: Option<&[u8; KEY_LENGTH]>
+) -> Result<PostedPaymentInfo, Error> {
+    match key {
+        Some(key) => Ok(PostedPaymentInfo::Encrypted(encrypt_payment_info(&payment_info, key)?)),
+        None => Ok(PostedPaymentInfo::Clear(payment_info)),
+    }
+}
+
+// Read posted payment info, opening it with the key when it is sealed.
+pub fn open_payment_info(
+    posted: PostedPaymentInfo,
+    key: Option<&[u8; KEY_LENGTH]>
+) -> Result<PaymentInfo, Error> {
+    match (posted, key) {
+        (PostedPaymentInfo::Clear(payment_info), _) => Ok(payment_info),
+        (PostedPaymentInfo::Encrypted(encrypted), Some(key)) => {
+            decrypt_payment_info(&encrypted, key)
+        },
+        (PostedPaymentInfo::Encrypted(_), None) => Err(Error::Anyhow(anyhow::Error::msg(
+            "The payment info is encrypted, set ENCRYPTION_KEY to decrypt it"
+        ))),
+    }
+}
+
+// The payment info of the block types that carry it.
+pub fn posted_payment_info(data: &BlockData) -> Option<&PostedPaymentInfo> {
+    match data {
+        BlockData::RawMaterialsProducerBlockData(data) => Some(&data.payment_info),
+        BlockData::SupplierBlockData(data) => Some(&data.payment_info),
+        BlockData::ManufacturerBlockData(data) => Some(&data.payment_info),
+        BlockData::DistributorBlockData(data) => Some(&data.payment_info),
+        BlockData::RetailerBlockData(data) => Some(&data.payment_info),
+        BlockData::DeliveredTransportationData(data) => Some(&data.payment_info),
+        _ => None,
+    }
+}
@@ -0,0 +1,209 @@
+// Rust module to check the environment of a command at startup. Every setting
+// the command reads is reported as set, left to its default or unset, and the
+// command fails before any network call when a required one is missing,
+// instead of mid-run once blocks have already been posted.
+
+use std::io::{self, IsTerminal};
+use tracing::{error, info};
+
+use crate::{
+    cli::{CliArgs, Command},
+    config::{
+        DEFAULT_CARRIER, DEFAULT_CONSUMER_INFO, DEFAULT_EXPLORER_BLOCK_TEMPLATE,
+        DEFAULT_MAX_BLOCKS, DEFAULT_METRIC_MODE, DEFAULT_POST_TIMEOUT_SECS,
+        DEFAULT_SAMPLING_INTERVAL_SECS, DEFAULT_TOTAL_DURATION_SECS,
+    },
+    custom_error::Error,
+    read_env_flag, read_env_var,
+};
+
+// Whether a command needs a setting, and what it falls back to without it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvRequirement {
+    Required,
+    Default(String),
+    Optional,
+}
+
+// A setting read from one of several variables, the first one set is used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvSetting {
+    pub names: Vec<&'static str>,
+    pub requirement: EnvRequirement,
+}
+
+impl EnvSetting {
+    fn new(names: &[&'static str], requirement: EnvRequirement) -> Self {
+        Self { names: names.to_vec(), requirement }
+    }
+
+    fn required(names: &[&'static str]) -> Self {
+        Self::new(names, EnvRequirement::Required)
+    }
+
+    fn with_default(name: &'static str, default: impl ToString) -> Self {
+        Self::new(&[name], EnvRequirement::Default(default.to_string()))
+    }
+
+    fn optional(name: &'static str) -> Self {
+        Self::new(&[name], EnvRequirement::Optional)
+    }
+
+    // The variables of the setting, e.g. NETWORK or NODE_URLS.
+    pub fn label(&self) -> String {
+        self.names.join(" or ")
+    }
+}
+
+// What a setting resolved to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvStatus {
+    // Set by the named variable.
+    Set(&'static str),
+    Default(String),
+    Unset,
+    Missing,
+}
+
+// The status of every setting of a command, in the order they were listed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvReport {
+    pub entries: Vec<(EnvSetting, EnvStatus)>,
+}
+
+impl EnvReport {
+    // Resolve the settings through the given lookup of a variable.
+    pub fn from_lookup<F>(settings: Vec<EnvSetting>, lookup: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let entries: Vec<(EnvSetting, EnvStatus)> = settings
+            .into_iter()
+            .map(|setting| {
+                let set_by: Option<&'static str> = setting.names
+                    .iter()
+                    .copied()
+                    .find(|name| lookup(name).is_some());
+
+                let status: EnvStatus = match (set_by, &setting.requirement) {
+                    (Some(name), _) => EnvStatus::Set(name),
+                    (None, EnvRequirement::Required) => EnvStatus::Missing,
+                    (None, EnvRequirement::Default(default)) => {
+                        EnvStatus::Default(default.to_owned())
+                    },
+                    (None, EnvRequirement::Optional) => EnvStatus::Unset,
+                };
+
+                (setting, status)
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    // Resolve the settings from the environment and the config file.
+    pub fn from_env(settings: Vec<EnvSetting>) -> Self {
+        Self::from_lookup(settings, |name| read_env_var(name.to_string()).ok())
+    }
+
+    // The labels of the required settings that are missing.
+    pub fn missing(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|(_setting, status)| *status == EnvStatus::Missing)
+            .map(|(setting, _status)| setting.label())
+            .collect()
+    }
+
+    // Log the status of every setting, and fail if a required one is missing.
+    pub fn check(&self) -> Result<(), Error> {
+        info!("Environment of the command:");
+        for (setting, status) in self.entries.iter() {
+            match status {
+                EnvStatus::Set(name) => info!("  {}: set by {}", setting.label(), name),
+                EnvStatus::Default(default) => {
+                    info!("  {}: unset, using the default {}", setting.label(), default)
+                },
+                EnvStatus::Unset => info!("  {}: unset", setting.label()),
+                EnvStatus::Missing => error!("  {}: missing, but required", setting.label()),
+            };
+        }
+
+        let missing: Vec<String> = self.missing();
+        if !missing.is_empty() {
+            return Err(Error::Validation(format!(
+                "environment: missing required settings {}", missing.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+// The settings a command reads. Every command talking to the node needs one
+// and its explorer, from NETWORK or given directly. A run also needs its
+// initial block, unless it is given on the command line, resumed from the
+// checkpoint or typed in interactively. The validate command reports its
+// settings through the preflight checks instead, and the schema command reads
+// none.
+pub fn command_settings(cli_args: &CliArgs, interactive: bool) -> Vec<EnvSetting> {
+    let mut settings: Vec<EnvSetting> = match cli_args.command {
+        Command::Schema { .. } | Command::Validate => return Vec::new(),
+        _ => vec![
+            EnvSetting::required(&["NETWORK", "NODE_URLS", "NODE_URL"]),
+            EnvSetting::optional("EXPLORER_URL"),
+            EnvSetting::with_default("EXPLORER_BLOCK_TEMPLATE", DEFAULT_EXPLORER_BLOCK_TEMPLATE),
+            EnvSetting::with_default("POST_TIMEOUT_SECS", DEFAULT_POST_TIMEOUT_SECS),
+            EnvSetting::optional("MAX_POSTS_PER_MINUTE"),
+            EnvSetting::optional("WAIT_CONFIRMATION"),
+            EnvSetting::optional("DISABLED_VALIDATORS"),
+            EnvSetting::optional("ENCRYPTION_KEY"),
+            EnvSetting::optional("SIGNING_KEY"),
+        ],
+    };
+
+    if let Command::Consume { .. } = cli_args.command {
+        settings.push(EnvSetting::with_default("CONSUMER_INFO", DEFAULT_CONSUMER_INFO));
+    }
+
+    if cli_args.command != Command::Run {
+        return settings;
+    }
+
+    if cli_args.block_id.is_some() || cli_args.resume || interactive {
+        settings.push(EnvSetting::optional("INITIAL_BLOCK_ID"));
+    } else {
+        settings.push(EnvSetting::required(&["INITIAL_BLOCK_ID"]));
+    }
+
+    settings.extend([
+        EnvSetting::with_default("CARRIERS", DEFAULT_CARRIER),
+        EnvSetting::with_default("MODE", DEFAULT_METRIC_MODE),
+        EnvSetting::with_default("SAMPLING_INTERVAL_SECS", DEFAULT_SAMPLING_INTERVAL_SECS),
+        EnvSetting::with_default("TOTAL_DURATION_SECS", DEFAULT_TOTAL_DURATION_SECS),
+        EnvSetting::optional("METRIC_COUNT"),
+        EnvSetting::optional("CONTINUE_TEMPERATURE_HEAD"),
+        EnvSetting::optional("CONTINUE_HUMIDITY_HEAD"),
+        EnvSetting::with_default("MAX_BLOCKS", DEFAULT_MAX_BLOCKS),
+        EnvSetting::optional("PAYMENT_WALLET_ADDRESS"),
+        EnvSetting::optional("PAYMENT_SMR_COST"),
+        EnvSetting::optional("SENDER_ADDRESS"),
+        EnvSetting::optional("DIGEST_LOG_PATH"),
+        EnvSetting::optional("METRICS_DB_PATH"),
+    ]);
+
+    settings
+}
+
+// Check the environment of the command about to run. A prompt for the initial
+// block can only be answered when stdin is a terminal and NON_INTERACTIVE is
+// not set.
+pub fn check_environment(cli_args: &CliArgs) -> Result<(), Error> {
+    let interactive: bool = io::stdin().is_terminal() && !read_env_flag("NON_INTERACTIVE");
+    let settings: Vec<EnvSetting> = command_settings(cli_args, interactive);
+    if settings.is_empty() {
+        return Ok(());
+    }
+
+    EnvReport::from_env(settings).check()
+}
@@ -0,0 +1,40 @@
+// Rust module to export the metrics of a shipment for offline analysis.
+
+use std::{fs::File, io::{BufWriter, Write}};
+
+use crate::{block_payload::MetricData, custom_error::Error};
+
+// Quote a CSV field if it contains a delimiter, a quote or a line break.
+fn csv_field(value: &str) -> String {
+    if value.contains(|c: char| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+// Write the given metrics to a CSV file at path, one row per reading.
+pub fn export_metrics_csv(metrics: &[MetricData], path: &str) -> Result<(), Error> {
+    let mut writer: BufWriter<File> = BufWriter::new(File::create(path)?);
+
+    writeln!(
+        writer,
+        "metric_type,metric_value,measurement_unit,timestamp,previous_block"
+    )?;
+
+    for metric in metrics {
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            csv_field(&metric.metric_type),
+            metric.metric_value,
+            csv_field(&metric.measurement_unit),
+            csv_field(&metric.timestamp),
+            csv_field(metric.previous_block.as_str())
+        )?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+// Rust module with the HTTP server streaming live metrics during a run.
+// Endpoints:
+// GET /metrics/latest - the most recently posted reading, 404 before the first
+// GET /metrics/all    - every reading posted so far
+// GET /prometheus     - the operational metrics of the board, in the
+//                       Prometheus text format
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use crate::{
+    block_payload::MetricData, collector_metrics::CollectorMetrics, custom_error::Error,
+    metric_store::MetricStore,
+};
+
+async fn latest_metric(
+    State(store): State<MetricStore>
+) -> Result<Json<MetricData>, StatusCode> {
+    match store.latest() {
+        Some(metric_data) => Ok(Json(metric_data)),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+async fn all_metrics(State(store): State<MetricStore>) -> Json<Vec<MetricData>> {
+    Json(store.all())
+}
+
+async fn prometheus_metrics(
+    State(collector_metrics): State<CollectorMetrics>
+) -> Result<impl IntoResponse, StatusCode> {
+    match collector_metrics.render() {
+        Ok((content_type, body)) => Ok(([(header::CONTENT_TYPE, content_type)], body)),
+        Err(err) => {
+            error!("Failed to render the Prometheus metrics: {:#}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        },
+    }
+}
+
+// Serve the readings of the store and the operational metrics of the board on
+// the given port until the process exits.
+pub async fn serve_metrics(
+    port: u16,
+    store: MetricStore,
+    collector_metrics: CollectorMetrics
+) -> Result<(), Error> {
+    let app: Router = Router::new()
+        .route("/metrics/latest", get(latest_metric))
+        .route("/metrics/all", get(all_metrics))
+        .with_state(store)
+        .merge(
+            Router::new()
+                .route("/prometheus", get(prometheus_metrics))
+                .with_state(collector_metrics)
+        );
+
+    let listener: TcpListener = TcpListener::bind(("0.0.0.0", port)).await?;
+    info!("Serving live metrics on port {}", port);
+
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
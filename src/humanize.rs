@@ -0,0 +1,34 @@
+// Rust module to format the durations and costs shown to the operator in a
+// readable way, instead of the debug output of a Duration or a bare float.
+
+use std::time::Duration;
+
+// Format a duration to a tenth of a second, e.g. 1h 2m 5.0s, 1m 23.4s or
+// 4.2s. Durations under a second, such as the posting time of a block without
+// PoW, are shown in milliseconds instead, e.g. 350ms.
+pub fn humanize_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        return format!("{}ms", duration.as_millis());
+    }
+
+    let tenths: u128 = (duration.as_millis() + 50) / 100;
+    let (hours, minutes): (u128, u128) = (tenths / 36_000, tenths / 600 % 60);
+    let seconds: String = format!("{}.{}s", tenths / 10 % 60, tenths % 10);
+
+    if hours > 0 {
+        format!("{}h {}m {}", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}", minutes, seconds)
+    } else {
+        seconds
+    }
+}
+
+// Format an SMR amount with the token symbol, to the glow, the smallest unit,
+// without trailing zeros, e.g. 1.5 SMR or 0.000125 SMR.
+pub fn humanize_smr(amount: f64) -> String {
+    let digits: String = format!("{:.6}", amount);
+    let digits: &str = digits.trim_end_matches('0').trim_end_matches('.');
+
+    format!("{} SMR", digits)
+}
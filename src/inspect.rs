@@ -0,0 +1,158 @@
+// Rust module to inspect the decoded payload of any block on the Tangle.
+
+use iota_sdk::types::block::BlockDto;
+
+use crate::{
+    block_payload::{
+        BlockData, EncryptedPaymentInfo, PaymentInfo, PostedPaymentInfo, TaggedDataPayload,
+    },
+    custom_error::Error,
+    digest::payload_digest,
+    encryption::{decrypt_payment_info, posted_payment_info, read_encryption_key},
+    extract_raw_tag_and_data,
+    output::{InspectedBlock, JsonOutput},
+    payload_format::{decode_payload, PayloadFormat},
+    signing::verify_payload,
+    tangle_client::TangleClient,
+    validation::validate_block_data,
+};
+
+// Fetch a block and decode its tag and data. Blocks are wrapped in a
+// TaggedDataPayload, while those posted before the payloads were versioned
+// hold the BlockData directly, so both are tried. Data that is neither is kept
+// as raw UTF-8. Encrypted payment info is decrypted as well when
+// ENCRYPTION_KEY is set, and the signature of a signed payload is verified.
+// Data posted in a binary format is decoded to JSON, while its digest covers
+// it as posted.
+pub async fn decode_block(
+    client: &TangleClient,
+    block_id: &String
+) -> Result<InspectedBlock, Error> {
+    let block: BlockDto = client.get_block(block_id).await?;
+
+    let (tag, payload): (Vec<u8>, Vec<u8>) = extract_raw_tag_and_data(block)?;
+    let digest: String = payload_digest(&payload);
+    let payload_format: PayloadFormat = PayloadFormat::detect(&payload)?;
+    let data: Vec<u8> = decode_payload(payload)?;
+    let string_data: String = String::from_utf8_lossy(&data).into_owned();
+
+    let mut inspected: InspectedBlock = InspectedBlock {
+        block_id: block_id.to_owned(),
+        tag: String::from_utf8_lossy(&tag).into_owned(),
+        digest,
+        payload_format: match payload_format {
+            PayloadFormat::Json => None,
+            binary => Some(binary.to_string()),
+        },
+        block_type: None,
+        schema_version: None,
+        data: None,
+        raw_data: None,
+        metadata: None,
+        validation_error: None,
+        payment_info_encrypted: false,
+        decrypted_payment_info: None,
+        signature: verify_payload(&data)?,
+    };
+
+    let block_data: BlockData = if let Ok(payload) =
+        serde_json::from_str::<TaggedDataPayload>(&string_data)
+    {
+        inspected.block_type = Some(payload.block_type);
+        inspected.schema_version = Some(payload.schema_version);
+        payload.data
+    } else if let Ok(block_data) = serde_json::from_str::<BlockData>(&string_data) {
+        block_data
+    } else {
+        inspected.raw_data = Some(string_data);
+        return Ok(inspected);
+    };
+
+    inspected.data = Some(serde_json::to_value(&block_data)?);
+    inspected.metadata = block_data.metadata().map(|metadata| {
+        metadata.iter().map(|(key, value)| (key.to_owned(), value.to_owned())).collect()
+    });
+    inspected.validation_error = validate_block_data(&block_data).err().map(|err| err.to_string());
+    inspected.payment_info_encrypted = matches!(
+        posted_payment_info(&block_data),
+        Some(PostedPaymentInfo::Encrypted(_))
+    );
+    inspected.decrypted_payment_info = match decrypted_payment_info(&block_data)? {
+        Some(payment_info) => Some(serde_json::to_value(&payment_info)?),
+        None => None,
+    };
+
+    Ok(inspected)
+}
+
+// Fetch a block and print its decoded tag and data, pretty-printed, or as a
+// single JSON object when requested.
+pub async fn inspect_block(
+    client: &TangleClient,
+    block_id: &String,
+    json: bool
+) -> Result<(), Error> {
+    let inspected: InspectedBlock = decode_block(client, block_id).await?;
+
+    if json {
+        return JsonOutput::Inspect(&inspected).print();
+    }
+
+    println!("Block: {}", inspected.block_id);
+    println!("Tag: {}", inspected.tag);
+    println!("Digest: {}", inspected.digest);
+    if let Some(payload_format) = &inspected.payload_format {
+        println!("Payload format: {}", payload_format);
+    }
+
+    if let Some(block_type) = &inspected.block_type {
+        println!("Block type: {}", block_type);
+    }
+    if let Some(schema_version) = inspected.schema_version {
+        println!("Schema version: {}", schema_version);
+    }
+
+    match (&inspected.data, &inspected.raw_data) {
+        (Some(data), _) => println!("Data:\n{}", serde_json::to_string_pretty(data)?),
+        (None, Some(raw_data)) => println!("Raw data:\n{}", raw_data),
+        (None, None) => {},
+    };
+
+    if let Some(metadata) = &inspected.metadata {
+        println!("Metadata:");
+        for (key, value) in metadata.iter() {
+            println!("  {}: {}", key, value);
+        }
+    }
+
+    if let Some(validation_error) = &inspected.validation_error {
+        println!("Invalid data: {}", validation_error);
+    }
+
+    if let Some(payment_info) = &inspected.decrypted_payment_info {
+        println!("Decrypted payment info:\n{}", serde_json::to_string_pretty(payment_info)?);
+    } else if inspected.payment_info_encrypted {
+        println!("Payment info is encrypted, set ENCRYPTION_KEY to decrypt it");
+    }
+
+    if let Some(signature) = &inspected.signature {
+        let status: &str = if signature.valid { "valid" } else { "INVALID" };
+        println!("Signed by {}: signature {}", signature.public_key, status);
+    }
+
+    Ok(())
+}
+
+// Decrypt the encrypted payment info of the data. Without the key, or when
+// the payment info is in cleartext, there is nothing to decrypt.
+fn decrypted_payment_info(data: &BlockData) -> Result<Option<PaymentInfo>, Error> {
+    let encrypted: &EncryptedPaymentInfo = match posted_payment_info(data) {
+        Some(PostedPaymentInfo::Encrypted(encrypted)) => encrypted,
+        _ => return Ok(None),
+    };
+
+    match read_encryption_key()? {
+        Some(key) => Ok(Some(decrypt_payment_info(encrypted, &key)?)),
+        None => Ok(None),
+    }
+}
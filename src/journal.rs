@@ -0,0 +1,97 @@
+// Rust module to keep track of the shipments in progress, so a re-run after a
+// crash resumes a shipment instead of posting a second start transportation
+// block against the same initial block.
+// The node cannot find an earlier start block on its own. Stardust nodes do
+// not index tagged data blocks, so they cannot be searched by tag, and the
+// children of the initial block are the blocks that approve it through tip
+// selection, not the blocks whose payload references it. Nor can the start
+// block approve the initial block directly, as a parent older than the max
+// parent age of the protocol is rejected. So the start transportation block of
+// every shipment in progress is journaled locally and confirmed on the node
+// before it is reused.
+
+use iota_sdk::types::block::BlockDto;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+use tracing::warn;
+
+use crate::{
+    block_payload::{decode_block_data, BlockData},
+    block_tag::BlockTag,
+    custom_error::Error,
+    extract_tag_and_data,
+    tangle_client::TangleClient,
+};
+
+// The start transportation block id of every shipment in progress, keyed by
+// the id of the initial block the shipment started from.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ShipmentJournal {
+    started: BTreeMap<String, String>,
+}
+
+impl ShipmentJournal {
+    // Load the journal at path. A missing file is an empty journal.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+
+        let content: String = fs::read_to_string(path)?;
+        let journal: ShipmentJournal = serde_json::from_str(&content)?;
+
+        Ok(journal)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn started_shipment(&self, initial_block_id: &str) -> Option<&String> {
+        self.started.get(initial_block_id)
+    }
+
+    pub fn record_start(&mut self, initial_block_id: &str, start_block_id: &str) {
+        self.started.insert(initial_block_id.to_owned(), start_block_id.to_owned());
+    }
+
+    // A delivered shipment is no longer in progress. Returns whether the
+    // shipment was journaled.
+    pub fn record_delivery(&mut self, initial_block_id: &str) -> bool {
+        self.started.remove(initial_block_id).is_some()
+    }
+}
+
+// Confirm on the node that a journaled block is the start transportation block
+// of a shipment from the given initial block. A block that cannot be fetched
+// or does not match is not reused.
+pub async fn confirm_start_block(
+    client: &TangleClient,
+    start_block_id: &String,
+    initial_block_id: &str
+) -> bool {
+    let block: BlockDto = match client.get_block(start_block_id).await {
+        Ok(block) => block,
+        Err(err) => {
+            warn!("Journaled start block {} not found: {:#}", start_block_id, err);
+            return false;
+        },
+    };
+
+    let (tag, data): (Vec<u8>, Vec<u8>) = match extract_tag_and_data(block) {
+        Ok(tag_and_data) => tag_and_data,
+        Err(_err) => return false,
+    };
+
+    if BlockTag::from_bytes(&tag) != Some(BlockTag::StartTransportation) {
+        return false;
+    }
+
+    match decode_block_data(&data) {
+        Ok(BlockData::StartTransportationData(start_data)) => {
+            start_data.previous_block == initial_block_id
+        },
+        _ => false,
+    }
+}
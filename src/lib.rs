@@ -0,0 +1,1285 @@
+use block_payload::{
+    check_schema_version, decode_block_data, BlockData, BlockRef, ConsumerBlockData,
+    OriginInfo, Resource,
+    PaymentInfo, PostedPaymentInfo, StartTransportationData,
+    DeliveredTransportationData, ProductInfo, 
+    MetricData, ExportLocation, LocationMetricData, BatchMetricData,
+    TransportationLeg
+};
+use dotenv::dotenv;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use iota_sdk::{
+    client::{core::Client, ClientBuilder},
+    types::block::{
+        BlockId, BlockDto, 
+        payload::dto::{PayloadDto, TaggedDataPayloadDto}
+    },
+};
+use rand::Rng;
+use tracing::{debug, error, instrument, warn};
+use std::{
+    collections::HashSet, env, fmt::Display, io::{self, IsTerminal}, path::Path,
+    str::FromStr, time::Duration
+};
+
+pub mod alert;
+
+pub mod balance;
+
+pub mod block_payload;
+
+pub mod block_store;
+use block_store::{post_block, BlockStore};
+
+pub mod block_tag;
+use block_tag::{check_tags, BlockTag};
+
+pub mod chain_verify;
+
+pub mod checkpoint;
+
+pub mod cid;
+
+pub mod cli;
+
+pub mod collector_metrics;
+
+pub mod config;
+use config::{
+    config_file_value, read_metadata, CustomMetricConfig, DEFAULT_CARRIER, DEFAULT_CONSUMER_INFO,
+    DEFAULT_LOCATION_ORIGIN_LATITUDE,
+    DEFAULT_LOCATION_ORIGIN_LONGITUDE, DEFAULT_LOCATION_STEP_DEGREES, DEFAULT_METRIC_MODE,
+    DEFAULT_READING_DECIMALS, DEFAULT_WALK_CONCURRENCY, DEFAULT_WALK_STEP_FRACTION
+};
+
+pub mod confirmation;
+#[cfg(feature = "mqtt")]
+use config::DEFAULT_MQTT_TOPIC_PREFIX;
+
+pub mod custom_error;
+use custom_error::Error;
+
+pub mod dedup;
+use dedup::{post_stuck_sensor_note, DuplicateAction, DuplicateTracker};
+
+pub mod digest;
+use digest::payload_digest;
+
+pub mod encryption;
+use encryption::{open_payment_info, read_encryption_key, seal_payment_info};
+
+pub mod env_check;
+
+pub mod export;
+
+pub mod http_server;
+
+pub mod humanize;
+
+pub mod inspect;
+
+pub mod journal;
+
+pub mod lineage;
+
+pub mod metric_sink;
+
+pub mod metric_store;
+
+pub mod metric_source;
+use metric_source::{
+    round_reading, BoardRng, FileSource, MetricSource, RandomSource, WalkSource,
+};
+
+pub mod metric_stream;
+
+pub mod network;
+use network::resolve_node_urls;
+
+pub mod node_info;
+
+pub mod output;
+
+pub mod payload_builder;
+
+pub mod payload_format;
+use payload_format::decode_payload;
+
+pub mod preflight;
+
+pub mod progress;
+
+pub mod query;
+
+pub mod rate_limit;
+
+pub mod receipt;
+
+pub mod replay;
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt_source;
+#[cfg(feature = "mqtt")]
+use mqtt_source::MqttSource;
+
+#[cfg(feature = "schema")]
+pub mod schema;
+
+pub mod selftest;
+
+pub mod signing;
+
+pub mod stats;
+
+pub mod tangle_client;
+
+pub mod timestamp;
+use timestamp::{now_timestamp, TimestampFormat};
+
+pub mod units;
+
+pub mod validation;
+use validation::{validate_block_data, validate_delivery_payment_info};
+
+use crate::{alert::check_metric_alert, block_payload::TaggedDataPayload};
+
+// Try to read an environment variable. If a .env file exists, try to read from
+// it first. If the variable is not in the environment, fall back to the config
+// file, keyed by the lowercase variable name. In case the setting exists in
+// neither, return an error.
+pub fn read_env_var(var: String) -> Result<String, Error> {
+    if Path::new(".env").exists() {
+        dotenv().ok();
+    }
+
+    if let Ok(value) = env::var(&var) {
+        return Ok(value);
+    }
+
+    match config_file_value(&var)? {
+        Some(value) => Ok(value),
+        None => Err(Error::EnvError(env::VarError::NotPresent)),
+    }
+}
+
+// Check that a block id has the expected format: a 0x prefix followed by the
+// hex encoded 32 bytes of the id.
+pub fn validate_block_id(block_id: &str) -> Result<(), Error> {
+    let hex: &str = match block_id.strip_prefix("0x") {
+        Some(hex) => hex,
+        None => return Err(Error::InvalidBlockId(String::from("must start with 0x"))),
+    };
+
+    let length: usize = block_id.chars().count();
+    if length != 66 {
+        return Err(Error::InvalidBlockId(format!(
+            "must be 66 characters long, got {}", length
+        )));
+    }
+
+    if let Some((position, invalid)) = hex
+        .chars()
+        .enumerate()
+        .find(|(_position, character)| !character.is_ascii_hexdigit())
+    {
+        return Err(Error::InvalidBlockId(format!(
+            "invalid hex digit '{}' at position {}", invalid, position + 2
+        )));
+    }
+
+    Ok(())
+}
+
+// Clean up a block id as typed or pasted by a user: surrounding whitespace,
+// such as the newline of an input line, is dropped and the hex is lowercased.
+// Whitespace within the id is an error rather than being dropped, since it
+// usually means two values were pasted together. The result is validated.
+pub fn normalize_block_id(input: &str) -> Result<String, Error> {
+    let trimmed: &str = input.trim();
+
+    if trimmed.is_empty() {
+        return Err(Error::InvalidBlockId(String::from("must not be empty")));
+    }
+
+    if trimmed.chars().any(char::is_whitespace) {
+        return Err(Error::InvalidBlockId(String::from("must not contain whitespace")));
+    }
+
+    let block_id: String = trimmed.to_ascii_lowercase();
+    validate_block_id(&block_id)?;
+
+    Ok(block_id)
+}
+
+// Get the initial block id. The id given on the command line takes precedence,
+// then the INITIAL_BLOCK_ID environment variable. If neither exists, ask the
+// user to input it, unless NON_INTERACTIVE is set or stdin is not a terminal,
+// in which case nobody can answer and it is an error instead.
+pub fn block_id_input(cli_block_id: Option<String>) -> Result<String, Error> {
+    let input: String = match cli_block_id {
+        Some(value) => value,
+        None => match read_env_var("INITIAL_BLOCK_ID".to_string()) {
+            Ok(value) => value,
+            Err(_err) => {
+                let stdin: io::Stdin = io::stdin();
+                if read_env_flag("NON_INTERACTIVE") || !stdin.is_terminal() {
+                    return Err(Error::Anyhow(anyhow::Error::msg(
+                        "INITIAL_BLOCK_ID is not set and the board is not running \
+                        interactively, set it or pass --block-id"
+                    )));
+                }
+
+                println!("Enter BlockId:");
+                let mut user_input: String = String::new();
+                stdin
+                    .read_line(&mut user_input)?;
+                user_input
+            }
+        },
+    };
+
+    normalize_block_id(&input)
+}
+
+// Split a comma-separated list, ignoring surrounding whitespace and empty
+// entries.
+pub fn parse_comma_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| entry.to_owned())
+        .collect()
+}
+
+// Split a comma-separated list of node URLs.
+pub fn parse_node_urls(value: &str) -> Vec<String> {
+    parse_comma_list(value)
+}
+
+// Create an IOTA client with the resolved node URLs. NODE_URLS (or NODE_URL)
+// may hold a single URL or a comma-separated list, in which case every node is
+// added to the client, which falls back to the others when one is down. When
+// neither is set, the node of the selected NETWORK is used.
+// The client uses local PoW unless LOCAL_POW is false, in which case the PoW
+// is left to the node. Local PoW runs POW_WORKER_COUNT threads, by default the
+// maximum number of threads available on the machine.
+pub async fn create_iota_client() -> Result<Client, Error> {
+    let node_urls: Vec<String> = resolve_node_urls()?;
+
+    if node_urls.is_empty() {
+        return Err(Error::Anyhow(anyhow::Error::msg(
+            "No node URL configured in NODE_URLS or NODE_URL"
+        )));
+    }
+
+    let mut client_builder: ClientBuilder = Client::builder();
+
+    for node_url in node_urls.iter() {
+        client_builder = client_builder.with_node(node_url.as_str())?;
+    }
+
+    let local_pow: bool = read_env_flag_or("LOCAL_POW", true);
+    client_builder = client_builder.with_local_pow(local_pow);
+
+    if local_pow {
+        let pow_worker_count: usize = read_env_parsed::<usize>("POW_WORKER_COUNT")?
+            .unwrap_or_else(num_cpus::get);
+
+        if pow_worker_count == 0 {
+            return Err(Error::Validation(String::from(
+                "POW_WORKER_COUNT: must be at least 1"
+            )));
+        }
+
+        client_builder = client_builder.with_pow_worker_count(pow_worker_count);
+    }
+
+    let client: Client = client_builder.finish().await?;
+    Ok(client)
+}
+
+// Extract the tag and the data of a block's tagged data payload, with data
+// posted in a binary format decoded back to JSON. Blocks without a payload or
+// with a payload other than tagged data are rejected.
+pub fn extract_tag_and_data(block: BlockDto) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let (tag, data): (Vec<u8>, Vec<u8>) = extract_raw_tag_and_data(block)?;
+
+    Ok((tag, decode_payload(data)?))
+}
+
+// Extract the tag and the data of a block's tagged data payload as posted.
+pub fn extract_raw_tag_and_data(block: BlockDto) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let block_payload: PayloadDto = match block.payload {
+        Some(payload) => payload,
+        None => return Err(Error::Anyhow(anyhow::Error::msg(
+            "Block has no payload"
+        ))),
+    };
+
+    let tagged_data: Box<TaggedDataPayloadDto> = match block_payload {
+        PayloadDto::TaggedData(data) => data,
+        _ => return Err(Error::Anyhow(anyhow::Error::msg(
+            "Block payload is not tagged data"
+        )))
+    };
+
+    Ok(((*tagged_data.tag).to_vec(), (*tagged_data.data).to_vec()))
+}
+
+// Extract the tagged data of a block payload as a UTF-8 string.
+pub fn extract_tagged_data(block: BlockDto) -> Result<String, Error> {
+    let (_tag, data): (Vec<u8>, Vec<u8>) = extract_tag_and_data(block)?;
+
+    let string_data: String = String::from_utf8(data)?;
+
+    Ok(string_data)
+}
+
+// Extract the supply chain data of a block. The tagged data must be a
+// TaggedDataPayload of a supported schema version whose data passes
+// validation. Errors carry the block id, the tag and the start of the payload.
+pub fn extract_block_data(block_id: &str, block: BlockDto) -> Result<BlockData, Error> {
+    let (tag, data): (Vec<u8>, Vec<u8>) = extract_tag_and_data(block)
+        .map_err(|err| err.with_block_context(block_id, None, None))?;
+
+    let decode = || -> Result<BlockData, Error> {
+        let block_payload: TaggedDataPayload = serde_json::from_slice(&data)?;
+
+        check_schema_version(block_payload.schema_version)?;
+        validate_block_data(&block_payload.data)?;
+
+        Ok(block_payload.data)
+    };
+
+    decode().map_err(|err| err.with_block_context(block_id, Some(&tag), Some(&data)))
+}
+
+// Take the origin info out of the block data. Only specific block types
+// of our supply chain block model contain payment information.
+// RawMaterialsProducerBlockData, SupplierBlockData, ManufacturerBlockData,
+// DistributorBlockData, RetailerBlockData
+// The other block type are not accepted as input.
+pub fn origin_info_from_data(data: BlockData) -> Result<OriginInfo, Error> {
+    use block_payload::BlockData::*;
+
+    let kind: String = data.kind().to_string();
+
+    let (payment_info, product_info): (PostedPaymentInfo, Option<ProductInfo>) = match data {
+        RawMaterialsProducerBlockData(data) => (data.payment_info, Some(data.material_info)),
+        SupplierBlockData(data) => (data.payment_info, Some(data.processed_material_info)),
+        ManufacturerBlockData(data) => (data.payment_info, Some(data.product_info)),
+        DistributorBlockData(data) => (data.payment_info, Some(data.product_distribution_info)),
+        RetailerBlockData(data) => (data.payment_info, Some(data.product_retail_info)),
+        other => return Err(Error::Anyhow(anyhow::Error::msg(format!(
+            "Block payload {} does not contain payment info data",
+            other
+        ))))
+    };
+
+    // Encrypted payment info is opened with ENCRYPTION_KEY.
+    let payment_info: PaymentInfo = open_payment_info(
+        payment_info, read_encryption_key()?.as_ref()
+    )?;
+
+    Ok(OriginInfo { kind, payment_info, product_info })
+}
+
+// Take the payment info out of the block data.
+pub fn payment_info_from_data(data: BlockData) -> Result<PaymentInfo, Error> {
+    Ok(origin_info_from_data(data)?.payment_info)
+}
+
+// Extract the payment info from the block payload.
+pub fn extract_payment_info(block_id: &str, block: BlockDto) -> Result<PaymentInfo, Error> {
+    payment_info_from_data(extract_block_data(block_id, block)?)
+        .map_err(|err| err.with_block_context(block_id, None, None))
+}
+
+// Extract the origin info from the block payload, carrying the provenance of
+// the shipment into the transportation without fetching the block again.
+pub fn extract_origin_info(block_id: &str, block: BlockDto) -> Result<OriginInfo, Error> {
+    origin_info_from_data(extract_block_data(block_id, block)?)
+        .map_err(|err| err.with_block_context(block_id, None, None))
+}
+
+// Walk a metric chain backwards, starting from its most recent metric block.
+// Every metric block references the previous one of the same metric through
+// previous_block, until the chain reaches the start transportation block,
+// which is neither a metric nor a batch block. Chains of metric batches are
+// walked the same way. The collected metrics are returned in chronological
+// order.
+pub async fn collect_metric_chain(
+    client: &dyn BlockStore,
+    head_block_id: &str
+) -> Result<Vec<MetricData>, Error> {
+    let mut metrics: Vec<MetricData> = Vec::new();
+    let mut block_id: String = head_block_id.to_owned();
+
+    loop {
+        // A pruned block cuts the chain short: the readings after it are
+        // still returned, the older ones are gone.
+        let data: Vec<u8> = match client.get(&block_id).await {
+            Ok(data) => data,
+            Err(Error::BlockNotFound(pruned)) if block_id != head_block_id => {
+                warn!("Metric chain of {} ends at pruned block {}", head_block_id, pruned);
+                break;
+            },
+            Err(err) => return Err(err),
+        };
+
+        // Batches are collected in reverse as well, so the final reversal
+        // restores their order.
+        match decode_block_data(&data)? {
+            BlockData::MetricData(metric_data) => {
+                block_id = metric_data.previous_block.to_string();
+                metrics.push(metric_data);
+            },
+            BlockData::BatchMetricData(batch) => {
+                block_id = batch.previous_block.to_string();
+                metrics.extend(batch.metrics.into_iter().rev());
+            },
+            _ => break,
+        };
+    }
+
+    metrics.reverse();
+
+    Ok(metrics)
+}
+
+#[instrument(skip(client))]
+// Post the start transportation block of a leg carried by the given carrier.
+// The first leg starts from the initial block, every later leg from the
+// delivery block of the leg before it.
+pub async fn start_transportation(
+    client: &dyn BlockStore,
+    previous_block_id: &String,
+    carrier: &str
+) -> Result<BlockId, Error> {
+
+    let file_cid: Option<String> = match read_env_var("START_TRANSPORTATION_CID".to_string()){
+        Ok(value) => Some(value),
+        Err(_err) => None
+    };
+
+    let product_info: ProductInfo = ProductInfo::new(
+        String::from("Transportation Information Data"), file_cid
+    )?;
+
+    let start_transaction_data: StartTransportationData = StartTransportationData::builder()
+        .company_info(carrier)
+        .product_info(product_info)
+        .start_timestamp(now_timestamp(TimestampFormat::from_env()?)?)
+        .previous_block(previous_block_id)
+        .metadata(read_metadata()?)
+        .build()?;
+    
+    let data: Vec<u8> = serde_json::to_string(&start_transaction_data)?
+        .as_bytes()
+        .to_vec();
+
+    let tag: Vec<u8> = BlockTag::StartTransportation.as_bytes();
+
+    let block_id: BlockId = post_block(client, tag, data).await?;
+
+    Ok(block_id)
+}
+
+// Read a boolean flag from the environment. The flag is set when the variable
+// holds 1, true, yes or on, in any case.
+pub fn read_env_flag(var: &str) -> bool {
+    match read_env_var(var.to_string()) {
+        Ok(value) => matches!(
+            value.trim().to_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        ),
+        Err(_err) => false,
+    }
+}
+
+// Read a boolean flag from the environment, falling back to the default when
+// the variable is not set.
+pub fn read_env_flag_or(var: &str, default: bool) -> bool {
+    match read_env_var(var.to_string()) {
+        Ok(_value) => read_env_flag(var),
+        Err(_err) => default,
+    }
+}
+
+// Try to read and parse an environment variable. Returns None if the variable
+// is not set, and an error naming the variable if its value cannot be parsed.
+pub fn read_env_parsed<T>(var: &str) -> Result<Option<T>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value: String = match read_env_var(var.to_string()) {
+        Ok(value) => value,
+        Err(_err) => return Ok(None),
+    };
+
+    let parsed: T = value.trim().parse::<T>().map_err(|err| {
+        Error::Validation(format!("value '{}' for {}: {}", value, var, err))
+    })?;
+
+    Ok(Some(parsed))
+}
+
+// Description of a metric posted by the board. Holds everything that differs
+// between metric types: the name, the range of generated values, the
+// measurement unit, the tag of the posted block and the interval the metric is
+// sampled at, if it differs from the global one.
+pub struct MetricSpec {
+    pub metric_type: String,
+    pub min: f64,
+    pub max: f64,
+    pub unit: String,
+    pub tag: BlockTag,
+    pub sampling_interval: Option<Duration>,
+}
+
+// Build the metric data of a new reading, chained to the previous block of the
+// metric. If {METRIC}_UNIT is set, e.g. TEMPERATURE_UNIT=fahrenheit, the
+// reading is converted from the unit of the spec and recorded in that unit,
+// rounded to READING_DECIMALS decimals like the readings generated.
+pub fn build_metric_data(
+    spec: &MetricSpec,
+    metric_value: f64,
+    previous_block_id: &String
+) -> Result<MetricData, Error> {
+    let unit_var: String = format!("{}_UNIT", metric_env_prefix(&spec.metric_type));
+
+    let (metric_value, measurement_unit): (f64, String) = match read_env_var(unit_var) {
+        Ok(unit) => {
+            let converted: f64 = units::convert(metric_value, &spec.unit, &unit)?;
+            let decimals: u32 = reading_decimals()?;
+            (round_reading(converted, decimals, f64::MIN, f64::MAX), units::canonical_unit(&unit))
+        },
+        Err(_err) => (metric_value, spec.unit.to_owned()),
+    };
+
+    let metric_data: MetricData = MetricData::builder()
+        .metric_type(&spec.metric_type)
+        .metric_value(metric_value)
+        .measurement_unit(measurement_unit)
+        .timestamp(now_timestamp(TimestampFormat::from_env()?)?)
+        .previous_block(previous_block_id)
+        .metadata(read_metadata()?)
+        .build()?;
+
+    Ok(metric_data)
+}
+
+// Read the next value of a metric from its source, chained to the previous
+// block of the metric, without posting it.
+pub fn sample_metric(
+    spec: &MetricSpec,
+    source: &mut dyn MetricSource,
+    previous_block_id: &String
+) -> Result<MetricData, Error> {
+    let metric_value: f64 = source.read(&spec.metric_type)?;
+
+    build_metric_data(spec, metric_value, previous_block_id)
+}
+
+// Read the next value of a metric from its source and post it, chained to the
+// previous block of the metric. Returns the id of the posted block together
+// with the posted reading.
+pub async fn post_metric(
+    client: &dyn BlockStore,
+    spec: &MetricSpec,
+    source: &mut dyn MetricSource,
+    previous_block_id: &String
+) -> Result<(BlockId, MetricData), Error> {
+    verify_previous_block(client, previous_block_id).await?;
+
+    let metric_data: MetricData = sample_metric(spec, source, previous_block_id)?;
+
+    let block_id: BlockId = post_metric_data(client, spec, &metric_data).await?;
+
+    Ok((block_id, metric_data))
+}
+
+// Post a sampled reading of a metric and raise an alert if it is out of range.
+pub async fn post_metric_data(
+    client: &dyn BlockStore,
+    spec: &MetricSpec,
+    metric_data: &MetricData
+) -> Result<BlockId, Error> {
+    let data: Vec<u8> = serde_json::to_string(metric_data)?
+        .as_bytes()
+        .to_vec();
+
+    let tag: Vec<u8> = spec.tag.as_bytes();
+
+    let block_id: BlockId = post_block(client, tag, data).await?;
+
+    // The metric block is already posted, so a failing alert must not make the
+    // caller lose the new head of the metric chain.
+    if let Err(err) = check_metric_alert(client, metric_data, &block_id).await {
+        error!("Failed to post alert: {:#}", err);
+    }
+
+    Ok(block_id)
+}
+
+// Like post_metric, but repeated readings of a stuck sensor are deduplicated
+// with the given tracker. Returns None when the reading was not posted as a
+// metric block, in which case the chain head stays the same.
+pub async fn post_deduplicated_metric(
+    client: &dyn BlockStore,
+    spec: &MetricSpec,
+    source: &mut dyn MetricSource,
+    previous_block_id: &String,
+    tracker: &mut DuplicateTracker
+) -> Result<Option<(BlockId, MetricData)>, Error> {
+    let metric_data: MetricData = sample_metric(spec, source, previous_block_id)?;
+
+    match tracker.observe(metric_data.metric_value) {
+        DuplicateAction::Post => {
+            verify_previous_block(client, previous_block_id).await?;
+            let block_id: BlockId = post_metric_data(client, spec, &metric_data).await?;
+            Ok(Some((block_id, metric_data)))
+        },
+        DuplicateAction::PostStuckSensorNote => {
+            post_stuck_sensor_note(
+                client,
+                &metric_data,
+                tracker.consecutive_readings(),
+                previous_block_id
+            ).await?;
+            Ok(None)
+        },
+        DuplicateAction::Skip => {
+            debug!("Skipped repeated {} reading", spec.metric_type);
+            Ok(None)
+        },
+    }
+}
+
+// With VERIFY_PREVIOUS set, confirm the previous block of a metric chain is on
+// the Tangle before chaining off it, so a post that silently failed does not
+// leave the chain referencing a nonexistent parent. Dry runs post nothing, so
+// there is nothing to verify.
+pub async fn verify_previous_block(
+    client: &dyn BlockStore,
+    previous_block_id: &String
+) -> Result<(), Error> {
+    if !read_env_flag("VERIFY_PREVIOUS") || client.dry_run() {
+        return Ok(());
+    }
+
+    match client.get(previous_block_id).await {
+        Ok(_data) => Ok(()),
+        Err(err) => Err(Error::Anyhow(anyhow::Error::new(err).context(format!(
+            "Previous block {} was not found on the Tangle, not chaining off it",
+            previous_block_id
+        )))),
+    }
+}
+
+// Read CONTINUE_{METRIC}_HEAD, e.g. CONTINUE_TEMPERATURE_HEAD: the last block
+// of the chain of a metric posted by an earlier run of the shipment. The chain
+// continues from it instead of starting over, so a long journey can be
+// recorded in segments and the delivery references the readings of them all.
+pub async fn read_continued_head(
+    client: &dyn BlockStore,
+    metric_type: &str
+) -> Result<Option<BlockId>, Error> {
+    let var: String = format!("CONTINUE_{}_HEAD", metric_env_prefix(metric_type));
+
+    match read_env_var(var.clone()) {
+        Ok(head) => Ok(Some(verify_continued_head(client, metric_type, &head, &var).await?)),
+        Err(_err) => Ok(None),
+    }
+}
+
+// Check the head a metric chain is continued from: the block must be on the
+// Tangle and be a reading of the metric. Errors name the setting the head was
+// read from.
+pub async fn verify_continued_head(
+    client: &dyn BlockStore,
+    metric_type: &str,
+    head: &str,
+    var: &str
+) -> Result<BlockId, Error> {
+    validate_block_id(head.trim())
+        .map_err(|err| Error::Validation(format!("{}: {}", var, err)))?;
+    let head: BlockId = head.trim().parse::<BlockId>()?;
+
+    // Dry runs post nothing, so there is no store to read the block from.
+    if client.dry_run() {
+        return Ok(head);
+    }
+
+    let data: Vec<u8> = client.get(&head.to_string()).await.map_err(|err| {
+        Error::Anyhow(anyhow::Error::new(err).context(format!(
+            "{}: block {} was not found on the Tangle", var, head
+        )))
+    })?;
+
+    match decode_block_data(&data) {
+        Ok(BlockData::MetricData(metric_data)) if metric_data.metric_type == metric_type => {
+            Ok(head)
+        },
+        _ => Err(Error::Validation(format!(
+            "{}: block {} is not a {} reading", var, head, metric_type
+        ))),
+    }
+}
+
+// Post several readings as a single metric batch block, chained to the previous
+// batch block. Alerts for out-of-range readings reference the batch block.
+pub async fn post_metric_batch(
+    client: &dyn BlockStore,
+    readings: Vec<MetricData>,
+    previous_block: &String
+) -> Result<BlockId, Error> {
+    verify_previous_block(client, previous_block).await?;
+
+    // The batch carries the metadata once for all of its readings.
+    let readings: Vec<MetricData> = readings
+        .into_iter()
+        .map(|reading| MetricData { metadata: None, ..reading })
+        .collect();
+    let mut batch_data: BatchMetricData =
+        BatchMetricData::new(readings, BlockRef::new(previous_block.to_owned())?);
+    batch_data.metadata = read_metadata()?;
+
+    let data: Vec<u8> = serde_json::to_string(&batch_data)?
+        .as_bytes()
+        .to_vec();
+
+    let tag: Vec<u8> = BlockTag::MetricBatch.as_bytes();
+
+    let block_id: BlockId = post_block(client, tag, data).await?;
+
+    for metric_data in batch_data.metrics.iter() {
+        if let Err(err) = check_metric_alert(client, metric_data, &block_id).await {
+            error!("Failed to post alert: {:#}", err);
+        }
+    }
+
+    Ok(block_id)
+}
+
+// Prefix of the settings of a metric type, e.g. TEMPERATURE for the
+// Temperature metric.
+pub fn metric_env_prefix(metric_type: &str) -> String {
+    metric_type.to_uppercase().replace(' ', "_")
+}
+
+// Parse a metric range given as min:max. The minimum must be below the maximum.
+pub fn parse_metric_range(value: &str) -> Result<(f64, f64), Error> {
+    let invalid_range = |reason: String| Error::Validation(format!(
+        "metric range '{}': {}", value, reason
+    ));
+
+    let (min, max): (&str, &str) = value
+        .split_once(':')
+        .ok_or_else(|| invalid_range(String::from("expected min:max")))?;
+
+    let min: f64 = min.trim().parse::<f64>()
+        .map_err(|err| invalid_range(format!("invalid min: {}", err)))?;
+    let max: f64 = max.trim().parse::<f64>()
+        .map_err(|err| invalid_range(format!("invalid max: {}", err)))?;
+
+    if !min.is_finite() || !max.is_finite() || min >= max {
+        return Err(invalid_range(String::from("min must be below max")));
+    }
+
+    Ok((min, max))
+}
+
+// Read the range of a metric from the given environment variable, formatted
+// as min:max, falling back to the default range when unset.
+pub fn read_metric_range(var: &str, default: (f64, f64)) -> Result<(f64, f64), Error> {
+    match read_env_var(var.to_string()) {
+        Ok(value) => parse_metric_range(&value),
+        Err(_err) => Ok(default),
+    }
+}
+
+// Create the source of a metric's readings. If {METRIC}_SOURCE_FILE points to
+// a file, e.g. TEMPERATURE_SOURCE_FILE, readings are taken from it, one per
+// line. With MQTT_BROKER_URL set, they are the values published on the topic
+// of the metric instead. Otherwise random readings are generated within the
+// range of the metric spec, using the given generator. With MODE=walk they
+// follow a random walk, stepping by at most WALK_STEP_FRACTION of the range per
+// reading, instead of being drawn independently. Generated readings are
+// rounded to READING_DECIMALS decimals.
+pub fn metric_source_from_env(
+    spec: &MetricSpec,
+    rng: BoardRng
+) -> Result<Box<dyn MetricSource>, Error> {
+    let var: String = format!("{}_SOURCE_FILE", metric_env_prefix(&spec.metric_type));
+
+    if let Ok(path) = read_env_var(var) {
+        return Ok(Box::new(FileSource::open(&path)?));
+    }
+
+    if let Ok(broker_url) = read_env_var("MQTT_BROKER_URL".to_string()) {
+        return mqtt_source(spec, &broker_url);
+    }
+
+    let mode: String = read_env_var("MODE".to_string())
+        .unwrap_or_else(|_err| String::from(DEFAULT_METRIC_MODE));
+
+    let decimals: u32 = reading_decimals()?;
+
+    let source: Box<dyn MetricSource> = match mode.trim().to_lowercase().as_str() {
+        "uniform" => Box::new(RandomSource::new(spec.min, spec.max, rng).with_decimals(decimals)),
+        "walk" => {
+            let step_fraction: f64 = read_env_parsed("WALK_STEP_FRACTION")?
+                .unwrap_or(DEFAULT_WALK_STEP_FRACTION);
+
+            if !(step_fraction > 0.0 && step_fraction <= 1.0) {
+                return Err(Error::Validation(format!(
+                    "WALK_STEP_FRACTION: must be within (0, 1], got {}", step_fraction
+                )));
+            }
+
+            let max_delta: f64 = (spec.max - spec.min) * step_fraction;
+            Box::new(
+                WalkSource::new(spec.min, spec.max, max_delta, rng).with_decimals(decimals)
+            )
+        },
+        other => return Err(Error::Anyhow(anyhow::Error::msg(format!(
+            "Unknown MODE {}, expected uniform or walk", other
+        )))),
+    };
+
+    Ok(source)
+}
+
+// Subscribe to the readings of a metric on the MQTT broker. The topic is read
+// from {METRIC}_MQTT_TOPIC, e.g. TEMPERATURE_MQTT_TOPIC, and defaults to
+// board/{metric}. The latest reading is posted until it is older than
+// MQTT_MAX_AGE_SECS, if set.
+#[cfg(feature = "mqtt")]
+fn mqtt_source(spec: &MetricSpec, broker_url: &str) -> Result<Box<dyn MetricSource>, Error> {
+    let prefix: String = metric_env_prefix(&spec.metric_type);
+
+    let topic: String = read_env_var(format!("{}_MQTT_TOPIC", prefix)).unwrap_or_else(|_err| {
+        format!("{}/{}", DEFAULT_MQTT_TOPIC_PREFIX, prefix.to_lowercase())
+    });
+    let max_age: Option<Duration> = read_env_parsed::<u64>("MQTT_MAX_AGE_SECS")?
+        .map(Duration::from_secs);
+
+    Ok(Box::new(MqttSource::connect(broker_url, &topic, max_age)?))
+}
+
+#[cfg(not(feature = "mqtt"))]
+fn mqtt_source(_spec: &MetricSpec, _broker_url: &str) -> Result<Box<dyn MetricSource>, Error> {
+    Err(Error::Anyhow(anyhow::Error::msg(
+        "MQTT_BROKER_URL is set, but the board was built without the mqtt feature"
+    )))
+}
+
+// The range of generated temperature readings, in Celsius, is read from
+// TEMPERATURE_RANGE, e.g. -25:-15 for a freezer truck.
+pub fn temperature_spec() -> Result<MetricSpec, Error> {
+    let (min, max): (f64, f64) = read_metric_range("TEMPERATURE_RANGE", (-5.0, 30.0))?;
+
+    Ok(MetricSpec {
+        metric_type: String::from("Temperature"),
+        min,
+        max,
+        unit: String::from("Celsius"),
+        tag: BlockTag::TemperatureMetric,
+        sampling_interval: None,
+    })
+}
+
+// The range of generated humidity readings is read from HUMIDITY_RANGE.
+pub fn humidity_spec() -> Result<MetricSpec, Error> {
+    let (min, max): (f64, f64) = read_metric_range("HUMIDITY_RANGE", (0.0, 100.0))?;
+
+    Ok(MetricSpec {
+        metric_type: String::from("Humidity"),
+        min,
+        max,
+        unit: String::from("%"),
+        tag: BlockTag::HumidityMetric,
+        sampling_interval: None,
+    })
+}
+
+// The range of generated pressure readings is read from PRESSURE_RANGE.
+pub fn pressure_spec() -> Result<MetricSpec, Error> {
+    let (min, max): (f64, f64) = read_metric_range("PRESSURE_RANGE", (950.0, 1050.0))?;
+
+    Ok(MetricSpec {
+        metric_type: String::from("Pressure"),
+        min,
+        max,
+        unit: String::from("hPa"),
+        tag: BlockTag::PressureMetric,
+        sampling_interval: None,
+    })
+}
+
+// The range of generated shock readings is read from SHOCK_RANGE.
+pub fn shock_spec() -> Result<MetricSpec, Error> {
+    let (min, max): (f64, f64) = read_metric_range("SHOCK_RANGE", (0.0, 10.0))?;
+
+    Ok(MetricSpec {
+        metric_type: String::from("Shock"),
+        min,
+        max,
+        unit: String::from("g"),
+        tag: BlockTag::ShockMetric,
+        sampling_interval: None,
+    })
+}
+
+// The interval a metric is sampled at. {METRIC}_SAMPLING_INTERVAL_SECS, e.g.
+// HUMIDITY_SAMPLING_INTERVAL_SECS=300, overrides the interval of the spec.
+// None means the metric is sampled at the global SAMPLING_INTERVAL_SECS.
+pub fn metric_sampling_interval(spec: &MetricSpec) -> Result<Option<Duration>, Error> {
+    let var: String = format!(
+        "{}_SAMPLING_INTERVAL_SECS", metric_env_prefix(&spec.metric_type)
+    );
+
+    match read_env_parsed::<u64>(&var)? {
+        Some(secs) => Ok(Some(Duration::from_secs(secs))),
+        None => Ok(spec.sampling_interval),
+    }
+}
+
+// Check every tag a run posts with: the built-in tags along with the tags of
+// the custom metrics.
+pub fn check_run_tags(specs: &[MetricSpec]) -> Result<(), Error> {
+    let mut tags: Vec<BlockTag> = BlockTag::ALL.to_vec();
+    tags.extend(
+        specs
+            .iter()
+            .filter(|spec| matches!(spec.tag, BlockTag::Custom(_)))
+            .map(|spec| spec.tag.clone())
+    );
+
+    check_tags(&tags)
+}
+
+// Build the specs of the metrics defined in the configuration. Every metric
+// needs a unique name and tag, neither of which may clash with the built-in
+// metrics, since the settings of a metric are keyed by its name and its blocks
+// are filtered by their tag.
+pub fn custom_metric_specs(
+    metrics: &[CustomMetricConfig],
+    builtin_specs: &[MetricSpec]
+) -> Result<Vec<MetricSpec>, Error> {
+    let invalid_metric = |name: &str, reason: String| Error::Validation(
+        format!("custom metric '{}': {}", name, reason)
+    );
+
+    let mut names: HashSet<String> = builtin_specs
+        .iter()
+        .map(|spec| metric_env_prefix(&spec.metric_type))
+        .collect();
+    names.insert(metric_env_prefix("Location"));
+
+    let mut tags: HashSet<String> = BlockTag::ALL
+        .iter()
+        .map(|tag| tag.as_str().to_owned())
+        .collect();
+
+    let mut specs: Vec<MetricSpec> = Vec::new();
+    for metric in metrics {
+        if metric.name.trim().is_empty() {
+            return Err(invalid_metric(&metric.name, String::from("the name is empty")));
+        }
+        if metric.tag.is_empty() {
+            return Err(invalid_metric(&metric.name, String::from("the tag is empty")));
+        }
+        if !metric.min.is_finite() || !metric.max.is_finite() || metric.min >= metric.max {
+            return Err(invalid_metric(&metric.name, String::from("min must be below max")));
+        }
+        if !names.insert(metric_env_prefix(&metric.name)) {
+            return Err(invalid_metric(&metric.name, String::from("the name is not unique")));
+        }
+        if !tags.insert(metric.tag.to_owned()) {
+            return Err(invalid_metric(
+                &metric.name,
+                format!("the tag '{}' is not unique", metric.tag)
+            ));
+        }
+
+        specs.push(MetricSpec {
+            metric_type: metric.name.to_owned(),
+            min: metric.min,
+            max: metric.max,
+            unit: metric.unit.to_owned(),
+            tag: BlockTag::Custom(metric.tag.to_owned()),
+            sampling_interval: metric.sampling_interval_secs.map(Duration::from_secs),
+        });
+    }
+
+    Ok(specs)
+}
+
+pub async fn temperature_metric(
+    client: &dyn BlockStore,
+    source: &mut dyn MetricSource,
+    previous_block_id: &String
+) -> Result<(BlockId, MetricData), Error>{
+    post_metric(client, &temperature_spec()?, source, previous_block_id).await
+}
+
+pub async fn humidity_metric(
+    client: &dyn BlockStore,
+    source: &mut dyn MetricSource,
+    previous_block_id: &String
+) -> Result<(BlockId, MetricData), Error>{
+    post_metric(client, &humidity_spec()?, source, previous_block_id).await
+}
+
+pub async fn pressure_metric(
+    client: &dyn BlockStore,
+    source: &mut dyn MetricSource,
+    previous_block_id: &String
+) -> Result<(BlockId, MetricData), Error>{
+    post_metric(client, &pressure_spec()?, source, previous_block_id).await
+}
+
+pub async fn shock_metric(
+    client: &dyn BlockStore,
+    source: &mut dyn MetricSource,
+    previous_block_id: &String
+) -> Result<(BlockId, MetricData), Error>{
+    post_metric(client, &shock_spec()?, source, previous_block_id).await
+}
+
+// Read the origin of the shipment's location random walk from the
+// environment. Defaults to Athens when unset.
+pub fn location_origin() -> Result<ExportLocation, Error> {
+    let origin: ExportLocation = ExportLocation {
+        longitude: read_env_parsed::<f32>("LOCATION_ORIGIN_LONGITUDE")?
+            .unwrap_or(DEFAULT_LOCATION_ORIGIN_LONGITUDE),
+        latitude: read_env_parsed::<f32>("LOCATION_ORIGIN_LATITUDE")?
+            .unwrap_or(DEFAULT_LOCATION_ORIGIN_LATITUDE),
+    };
+
+    origin.validate().map_err(|err| match err {
+        Error::Validation(reason) => Error::Validation(format!("location origin {}", reason)),
+        other => other,
+    })?;
+
+    Ok(origin)
+}
+
+// Move the shipment's position by a small random step and post it as a
+// location metric, chained to the previous location block. The step size in
+// degrees is read from LOCATION_STEP_DEGREES.
+pub async fn location_metric(
+    client: &dyn BlockStore,
+    rng: &mut BoardRng,
+    position: &mut ExportLocation,
+    previous_block_id: &String
+) -> Result<BlockId, Error> {
+    verify_previous_block(client, previous_block_id).await?;
+
+    let step: f32 = read_env_parsed::<f32>("LOCATION_STEP_DEGREES")?
+        .unwrap_or(DEFAULT_LOCATION_STEP_DEGREES);
+
+    position.longitude = (position.longitude + rng.gen_range(-step..=step))
+        .clamp(-180.0, 180.0);
+    position.latitude = (position.latitude + rng.gen_range(-step..=step))
+        .clamp(-90.0, 90.0);
+
+    let mut location_data: LocationMetricData = LocationMetricData::new(
+        position.longitude,
+        position.latitude,
+        now_timestamp(TimestampFormat::from_env()?)?,
+        BlockRef::new(previous_block_id.to_owned())?
+    );
+    location_data.metadata = read_metadata()?;
+
+    let data: Vec<u8> = serde_json::to_string(&location_data)?
+        .as_bytes()
+        .to_vec();
+
+    let tag: Vec<u8> = BlockTag::LocationMetric.as_bytes();
+
+    let block_id: BlockId = post_block(client, tag, data).await?;
+
+    Ok(block_id)
+}
+
+// Read back the readings of the metric chains with the given heads. Chains
+// of other blocks, like the location chain, hold no readings.
+pub async fn resolve_metric_chains(
+    client: &dyn BlockStore,
+    heads: &[String]
+) -> Result<Vec<MetricData>, Error> {
+    // Each chain can only be walked a block at a time, so the chains are
+    // walked side by side instead, in the order of their heads.
+    let chains: Vec<Vec<MetricData>> = stream::iter(heads)
+        .map(|head| collect_metric_chain(client, head))
+        .buffered(walk_concurrency()?)
+        .try_collect()
+        .await?;
+
+    Ok(chains.into_iter().flatten().collect())
+}
+
+// Most decimals a generated reading can be rounded to. An f64 holds about 15
+// significant digits, so more would not round at all.
+const MAX_READING_DECIMALS: u32 = 10;
+
+// Read READING_DECIMALS, the decimals generated readings are rounded to.
+pub fn reading_decimals() -> Result<u32, Error> {
+    let decimals: u32 = read_env_parsed("READING_DECIMALS")?
+        .unwrap_or(DEFAULT_READING_DECIMALS);
+
+    if decimals > MAX_READING_DECIMALS {
+        return Err(Error::Validation(format!(
+            "READING_DECIMALS: must be at most {}, got {}", MAX_READING_DECIMALS, decimals
+        )));
+    }
+
+    Ok(decimals)
+}
+
+// Read WALK_CONCURRENCY, the most blocks fetched at once when walking chains
+// and lineages.
+pub fn walk_concurrency() -> Result<usize, Error> {
+    let concurrency: usize = read_env_parsed("WALK_CONCURRENCY")?
+        .unwrap_or(DEFAULT_WALK_CONCURRENCY);
+
+    if concurrency == 0 {
+        return Err(Error::Validation(String::from("WALK_CONCURRENCY: must be positive")));
+    }
+
+    Ok(concurrency)
+}
+
+// The carriers of the shipment, in the order they carry it, from the
+// comma-separated CARRIERS. Every carrier carries a leg of its own, and
+// without CARRIERS the shipment is a single leg.
+pub fn read_carriers() -> Vec<String> {
+    let carriers: Vec<String> = match read_env_var("CARRIERS".to_string()) {
+        Ok(value) => parse_comma_list(&value),
+        Err(_err) => Vec::new(),
+    };
+
+    if carriers.is_empty() {
+        vec![String::from(DEFAULT_CARRIER)]
+    } else {
+        carriers
+    }
+}
+
+// The consumer the shipment is delivered to, from CONSUMER_INFO.
+pub fn read_consumer_info() -> String {
+    read_env_var("CONSUMER_INFO".to_string())
+        .unwrap_or_else(|_err| String::from(DEFAULT_CONSUMER_INFO))
+}
+
+// Post the consumer block closing the supply chain of a delivered shipment.
+// Its resource points at the delivery block, with the SHA-256 digest of the
+// delivery payload as posted as its receipt, the digest inspect and verify
+// compute, so the consumer block pins the delivery it received. Only a
+// delivered transportation block can be consumed.
+pub async fn post_consumer_block(
+    client: &dyn BlockStore,
+    consumer_info: &str,
+    previous_block: &String
+) -> Result<BlockId, Error> {
+    let payload: Vec<u8> = client.get_raw(previous_block).await?;
+    let data: Vec<u8> = decode_payload(payload.clone())?;
+
+    match decode_block_data(&data) {
+        Ok(BlockData::DeliveredTransportationData(_)) => {},
+        Ok(other) => return Err(Error::Validation(format!(
+            "resource: block {} is a {}, not a delivered transportation block",
+            previous_block, other.kind()
+        ))),
+        Err(err) => return Err(Error::Validation(format!(
+            "resource: block {} is not a delivered transportation block: {}",
+            previous_block, err
+        ))),
+    };
+
+    let consumer_data: BlockData = BlockData::ConsumerBlockData(ConsumerBlockData {
+        consumer_info: consumer_info.to_owned(),
+        resource: Resource {
+            previous_block: BlockRef::new(previous_block.to_owned())?,
+            transaction_receipt: payload_digest(&payload),
+        },
+    });
+    validate_block_data(&consumer_data)?;
+
+    let data: Vec<u8> = serde_json::to_string(&consumer_data)?
+        .as_bytes()
+        .to_vec();
+
+    let tag: Vec<u8> = BlockTag::Consumer.as_bytes();
+
+    let block_id: BlockId = post_block(client, tag, data).await?;
+
+    Ok(block_id)
+}
+
+// Post the delivered transportation block of a leg. A leg handed over to
+// another carrier sets next_leg_previous_block to its start block, and the
+// last leg of a multi-leg shipment lists every leg.
+// With ENCRYPTION_KEY set the payment info is posted encrypted.
+#[instrument(skip_all)]
+pub async fn deliver_transportation(
+    client: &dyn BlockStore,
+    payment_info: PaymentInfo,
+    metrics: Vec<String>,
+    next_leg_previous_block: Option<BlockRef>,
+    legs: Option<Vec<TransportationLeg>>
+) -> Result<BlockId, Error> {
+    // Never record a delivery that references an unpayable payment.
+    validate_delivery_payment_info(&payment_info)?;
+
+    let file_cid: Option<String> = match read_env_var("DELIVER_TRANSPORTATION_CID".to_string()){
+        Ok(value) => Some(value),
+        Err(_err) => None
+    };
+
+    let product_info: ProductInfo = ProductInfo::new(
+        String::from("Product Delivery Information"), file_cid
+    )?;
+
+    // With RESOLVE_DELIVERY_METRICS set, the readings of every metric chain are
+    // embedded as well. Dry runs post no chains to read back.
+    let resolved_metrics: Option<Vec<MetricData>> =
+        if read_env_flag("RESOLVE_DELIVERY_METRICS") && !client.dry_run() {
+            match resolve_metric_chains(client, &metrics).await {
+                Ok(readings) => Some(readings),
+                Err(err) => {
+                    warn!("Delivering without resolved metrics: {:#}", err);
+                    None
+                },
+            }
+        } else {
+            None
+        };
+
+    let delivered_transportation_data: DeliveredTransportationData =
+        DeliveredTransportationData::builder()
+            .product_info(product_info)
+            .delivery_timestamp(now_timestamp(TimestampFormat::from_env()?)?)
+            .payment_info(seal_payment_info(payment_info, read_encryption_key()?.as_ref())?)
+            .metrics(metrics)
+            .resolved_metrics(resolved_metrics)
+            .next_leg_previous_block(next_leg_previous_block)
+            .legs(legs)
+            .metadata(read_metadata()?)
+            .build()?;
+
+    let data: Vec<u8> = serde_json::to_string(&delivered_transportation_data)?
+        .as_bytes()
+        .to_vec();
+    
+    let tag: Vec<u8> = BlockTag::DeliveredTransportation.as_bytes();
+
+    let block_id: BlockId = post_block(client, tag, data).await?;
+
+    Ok(block_id)
+}
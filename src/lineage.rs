@@ -0,0 +1,171 @@
+// Rust module to reconstruct the supply chain lineage of a block.
+// Every supply chain block references the blocks it was made from through its
+// Resource or Resources, forming a provenance DAG that leads back to the raw
+// materials producers. The blocks of a generation of the DAG are fetched
+// together, up to WALK_CONCURRENCY at once, before the DAG is walked.
+
+use futures::stream::{self, StreamExt};
+use iota_sdk::types::block::BlockDto;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+use crate::{
+    block_payload::{decode_block_data, BlockData, BlockRef},
+    custom_error::Error,
+    extract_tag_and_data,
+    tangle_client::TangleClient,
+    walk_concurrency,
+};
+
+fn block_ids(block_refs: &[BlockRef]) -> Vec<String> {
+    block_refs.iter().map(|block_ref| block_ref.to_string()).collect()
+}
+
+// The blocks a block of the supply chain was made from. Producer blocks are the
+// origin of the chain and reference none.
+pub fn lineage_parents(data: &BlockData) -> Vec<String> {
+    match data {
+        BlockData::SupplierBlockData(data) => block_ids(&data.resources.previous_blocks),
+        BlockData::ManufacturerBlockData(data) => block_ids(&data.resources.previous_blocks),
+        BlockData::DistributorBlockData(data) => vec![data.resource.previous_block.to_string()],
+        BlockData::RetailerBlockData(data) => vec![data.resource.previous_block.to_string()],
+        BlockData::ConsumerBlockData(data) => vec![data.resource.previous_block.to_string()],
+        BlockData::StartTransportationData(data) => vec![data.previous_block.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+// Decode the data of a lineage block, wrapped in a TaggedDataPayload or, if
+// posted before the payloads were versioned, bare.
+fn decode_lineage_block(block_id: &str, block: BlockDto) -> Result<BlockData, Error> {
+    let (tag, data): (Vec<u8>, Vec<u8>) = extract_tag_and_data(block)
+        .map_err(|err| err.with_block_context(block_id, None, None))?;
+
+    decode_block_data(&data)
+        .map_err(|err| err.with_block_context(block_id, Some(&tag), Some(&data)))
+}
+
+// Fetch and decode a block of the lineage. A pruned ancestor ends its branch
+// of the lineage, which is returned as far as the node still holds it, so it
+// is None. Only a pruned start block is an error.
+async fn fetch_lineage_block(
+    client: &TangleClient,
+    block_id: &String,
+    start_block_id: &str
+) -> Result<Option<BlockData>, Error> {
+    let block: BlockDto = match client.get_block(block_id).await {
+        Ok(block) => block,
+        Err(Error::BlockNotFound(pruned)) if pruned != start_block_id => {
+            warn!("Lineage branch ends at block {}, not found on the node", pruned);
+            return Ok(None);
+        },
+        Err(err) => return Err(err),
+    };
+
+    Ok(Some(decode_lineage_block(block_id, block)?))
+}
+
+// Fetch every block of the lineage a generation at a time: the parents of the
+// blocks fetched last are fetched together, at most concurrency at once. Every
+// block is fetched once, so a cycle ends the fetching like any other block
+// seen before.
+async fn fetch_lineage(
+    client: &TangleClient,
+    start_block_id: &str,
+    concurrency: usize
+) -> Result<HashMap<String, Option<BlockData>>, Error> {
+    let mut fetched: HashMap<String, Option<BlockData>> = HashMap::new();
+    let mut seen: HashSet<String> = HashSet::from([start_block_id.to_owned()]);
+    let mut generation: Vec<String> = vec![start_block_id.to_owned()];
+
+    while !generation.is_empty() {
+        let blocks: Vec<(String, Result<Option<BlockData>, Error>)> = stream::iter(generation)
+            .map(|block_id| async move {
+                let data: Result<Option<BlockData>, Error> =
+                    fetch_lineage_block(client, &block_id, start_block_id).await;
+                (block_id, data)
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        generation = Vec::new();
+        for (block_id, data) in blocks {
+            let data: Option<BlockData> = data?;
+
+            if let Some(data) = &data {
+                for parent in lineage_parents(data) {
+                    if seen.insert(parent.clone()) {
+                        generation.push(parent);
+                    }
+                }
+            }
+
+            fetched.insert(block_id, data);
+        }
+    }
+
+    Ok(fetched)
+}
+
+enum Visit {
+    Enter(String),
+    Exit(String),
+}
+
+// Walk the lineage of a block depth first, from the block itself back to the
+// raw materials. A block reached through several branches is only returned
+// once, and a block that leads back to itself is an error. Returns every block
+// of the lineage with its decoded data, each one before the blocks it was
+// made from.
+pub async fn trace_lineage(
+    client: &TangleClient,
+    start_block_id: &str
+) -> Result<Vec<(String, BlockData)>, Error> {
+    let mut fetched: HashMap<String, Option<BlockData>> =
+        fetch_lineage(client, start_block_id, walk_concurrency()?).await?;
+
+    let mut lineage: Vec<(String, BlockData)> = Vec::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut on_path: HashSet<String> = HashSet::new();
+    let mut stack: Vec<Visit> = vec![Visit::Enter(start_block_id.to_owned())];
+
+    while let Some(visit) = stack.pop() {
+        let block_id: String = match visit {
+            Visit::Enter(block_id) => block_id,
+            Visit::Exit(block_id) => {
+                on_path.remove(&block_id);
+                continue;
+            },
+        };
+
+        if on_path.contains(&block_id) {
+            return Err(Error::Anyhow(anyhow::Error::msg(format!(
+                "Lineage cycle detected at block {}", block_id
+            ))));
+        }
+
+        if !visited.insert(block_id.clone()) {
+            continue;
+        }
+
+        // Every block is visited once, so its data is taken out of the
+        // fetched blocks. Pruned blocks have none.
+        let data: BlockData = match fetched.remove(&block_id).flatten() {
+            Some(data) => data,
+            None => continue,
+        };
+
+        on_path.insert(block_id.clone());
+        stack.push(Visit::Exit(block_id.clone()));
+
+        // Pushed in reverse, so the parents are walked in the order listed.
+        for parent in lineage_parents(&data).into_iter().rev() {
+            stack.push(Visit::Enter(parent));
+        }
+
+        lineage.push((block_id, data));
+    }
+
+    Ok(lineage)
+}
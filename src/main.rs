@@ -1,340 +1,1166 @@
-use block_payload::{
-    PaymentInfo, StartTransportationData, 
-    DeliveredTransportationData, ProductInfo, 
-    MetricData
-};
-use chrono::Local;
-use dotenv::dotenv;
-use iota_sdk::{
-    client::core::Client,
-    types::block::{
-        Block, BlockId, BlockDto, 
-        payload::dto::{PayloadDto, TaggedDataPayloadDto}
+use futures::future::join_all;
+use iota_sdk::types::block::{BlockId, BlockDto};
+use metrics_board_demo::{
+    block_id_input, extract_block_data,
+    origin_info_from_data,
+    start_transportation, deliver_transportation,
+    metric_source_from_env, temperature_spec, humidity_spec, pressure_spec,
+    shock_spec, location_origin, location_metric, resolve_metric_chains,
+    read_carriers, validate_block_id, sample_metric, post_metric, post_deduplicated_metric,
+    post_metric_batch, check_run_tags, custom_metric_specs, metric_sampling_interval, MetricSpec,
+    post_consumer_block, read_consumer_info, read_continued_head,
+    alert::AlertRange,
+    balance::check_sender_balance,
+    block_payload::{
+        BlockData, BlockRef, ExportLocation, MetricData, OriginInfo, PaymentInfo,
+        TransportationLeg
+    },
+    chain_verify::{print_verify_reports, verify_chains, VerifyReport},
+    checkpoint::RunCheckpoint,
+    cli::{CliArgs, Command},
+    collector_metrics::CollectorMetrics,
+    config::{resolve_config, AppConfig},
+    custom_error::Error,
+    dedup::DuplicateTracker,
+    digest::verify_block,
+    env_check::check_environment,
+    export::export_metrics_csv,
+    http_server::serve_metrics,
+    inspect::inspect_block,
+    lineage::trace_lineage,
+    node_info::{check_node_health, print_node_info},
+    output::{JsonOutput, PreflightReport, SelftestReport},
+    preflight::run_preflight,
+    progress::{format_duration, ProgressReporter, ShipmentProgress},
+    query::print_blocks_by_tag,
+    journal::{confirm_start_block, ShipmentJournal},
+    metric_source::{BoardRng, MetricSource},
+    metric_sink::MetricSinks,
+    metric_store::MetricStore,
+    receipt::{ReceiptBlock, ShipmentReceipt},
+    replay::replay_shipment,
+    selftest::{print_selftest_report, run_selftest},
+    stats::{
+        print_metric_summaries, print_posting_stats, summarize_breaches, summarize_metrics,
+        BreachSummary
     },
+    tangle_client::TangleClient,
+    validation::validate_delivery_payment_info,
 };
-use std::{env, io, path::Path, time::{Instant, Duration}};
-use rand::{rngs::ThreadRng, Rng};
+#[cfg(feature = "schema")]
+use metrics_board_demo::schema::block_payload_schemas;
+#[cfg(feature = "schema")]
+use std::fs;
+use std::{collections::BTreeMap, env, process, time::{Instant, Duration}};
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, EnvFilter};
+
+// Wrap an error with a message describing the step that failed. The original
+// error is kept as the cause and printed after the context.
+fn with_context<T>(result: Result<T, Error>, context: &str) -> Result<T, Error> {
+    result.map_err(|err| {
+        Error::Anyhow(anyhow::Error::new(err).context(context.to_owned()))
+    })
+}
 
+// Print the JSON Schema of the block payloads, or write it to the given file.
+#[cfg(feature = "schema")]
+fn write_schema(output: Option<String>) -> Result<(), Error> {
+    let schema: String = serde_json::to_string_pretty(&block_payload_schemas()?)?;
+
+    match output {
+        Some(path) => {
+            fs::write(&path, schema)?;
+            info!("Schema written to: {}", path);
+        },
+        None => println!("{}", schema),
+    };
 
-mod block_payload;
+    Ok(())
+}
 
-mod custom_error;
-use custom_error::Error;
+#[cfg(not(feature = "schema"))]
+fn write_schema(_output: Option<String>) -> Result<(), Error> {
+    Err(Error::Anyhow(anyhow::Error::msg(
+        "The schema command requires building with --features schema"
+    )))
+}
 
-use crate::block_payload::TaggedDataPayload;
+async fn run(cli_args: CliArgs) -> Result<(), Error> {
 
-// Try to read an environment variable. If a .env file exists, try to read from
-// it first. In case the environment variable does not exist in neither the 
-// .env file nor the environment, return an error.
-fn read_env_var(var: String) -> Result<String, Error> {
-    if Path::new(".env").exists() {
-        dotenv().ok();
+    // The --network flag takes precedence over the NETWORK environment
+    // variable, which every later step resolves the node and explorer from.
+    if let Some(network) = cli_args.network {
+        env::set_var("NETWORK", network.to_string());
+    }
+    // Likewise, --count takes precedence over METRIC_COUNT.
+    if let Some(count) = cli_args.count {
+        env::set_var("METRIC_COUNT", count.to_string());
     }
 
-    let value: String = match env::var(&var) {
-        Ok(value) => value,
-        Err(_) => return Err(Error::EnvError(env::VarError::NotPresent)),
+    // Resolve the configuration up front, so an invalid config file or setting
+    // is reported before anything is posted.
+    let config: AppConfig =
+        with_context(resolve_config(), "Failed to load the configuration")?;
+
+    // Report the settings of the command, and stop before any network call if
+    // a required one is missing.
+    with_context(check_environment(&cli_args), "Failed to check the environment")?;
+
+    match cli_args.command {
+        Command::Run => {
+            let receipt_json: bool = cli_args.json || config.receipt_json.unwrap_or(false);
+            let receipt: ShipmentReceipt = run_shipment(cli_args, config).await?;
+            receipt.print(receipt_json)
+        },
+        Command::Schema { output } => write_schema(output),
+        Command::Inspect { block_id } => {
+            validate_block_id(&block_id)?;
+            let iota_client: TangleClient = with_context(
+                TangleClient::new().await,
+                "Failed to create the IOTA client"
+            )?;
+            inspect_block(&iota_client, &block_id, cli_args.json).await
+        },
+        Command::Trace { block_id } => {
+            validate_block_id(&block_id)?;
+            let iota_client: TangleClient = with_context(
+                TangleClient::new().await,
+                "Failed to create the IOTA client"
+            )?;
+
+            let lineage: Vec<(String, BlockData)> = with_context(
+                trace_lineage(&iota_client, &block_id).await,
+                "Failed to trace the lineage"
+            )?;
+            for (lineage_block_id, data) in lineage.iter() {
+                println!("{} {}", lineage_block_id, data);
+            }
+
+            Ok(())
+        },
+        Command::Verify { block_id, digest } => {
+            validate_block_id(&block_id)?;
+            let iota_client: TangleClient = with_context(
+                TangleClient::new().await,
+                "Failed to create the IOTA client"
+            )?;
+
+            if verify_block(&iota_client, &block_id, &digest).await? {
+                println!("Block {} matches the digest", block_id);
+                Ok(())
+            } else {
+                Err(Error::Anyhow(anyhow::Error::msg(format!(
+                    "Block {} does not match the digest {}", block_id, digest
+                ))))
+            }
+        },
+        Command::Query { tag, block_id } => {
+            validate_block_id(&block_id)?;
+            let iota_client: TangleClient = with_context(
+                TangleClient::new().await,
+                "Failed to create the IOTA client"
+            )?;
+
+            with_context(
+                print_blocks_by_tag(
+                    &iota_client, &block_id, &tag, cli_args.payloads, cli_args.json
+                ).await,
+                "Failed to query the blocks"
+            )
+        },
+        Command::Consume { block_id } => {
+            validate_block_id(&block_id)?;
+            let mut iota_client: TangleClient = with_context(
+                TangleClient::new().await,
+                "Failed to create the IOTA client"
+            )?;
+            if cli_args.json {
+                iota_client.enable_json_output();
+            }
+
+            let consumer_block_id: BlockId = with_context(
+                post_consumer_block(&iota_client, &read_consumer_info(), &block_id).await,
+                "Failed to post the consumer block"
+            )?;
+            if !cli_args.json {
+                println!("Shipment consumed by block {}", consumer_block_id);
+                if let Some(explorer_link) =
+                    iota_client.explorer_link(&consumer_block_id.to_string())
+                {
+                    println!("Consumer block on explorer: {}", explorer_link);
+                }
+            }
+
+            Ok(())
+        },
+        Command::Replay { path } => {
+            let iota_client: TangleClient = with_context(
+                TangleClient::new().await,
+                "Failed to create the IOTA client"
+            )?;
+
+            let delivery_block_id: BlockId = with_context(
+                replay_shipment(&iota_client, &path).await,
+                "Failed to replay the shipment"
+            )?;
+            println!("Shipment replayed, delivered by block {}", delivery_block_id);
+            if let Some(explorer_link) =
+                iota_client.explorer_link(&delivery_block_id.to_string())
+            {
+                println!("Delivery on explorer: {}", explorer_link);
+            }
+
+            Ok(())
+        },
+        Command::Validate => {
+            let report: PreflightReport =
+                run_preflight(cli_args.block_id, &config, cli_args.json).await;
+            if cli_args.json {
+                JsonOutput::Validate(&report).print()?;
+            }
+
+            if report.passed {
+                if !cli_args.json {
+                    println!("The setup is ready for a shipment run");
+                }
+                Ok(())
+            } else {
+                Err(Error::Anyhow(anyhow::Error::msg("Some preflight checks failed")))
+            }
+        },
+        Command::Info => {
+            let iota_client: TangleClient = with_context(
+                TangleClient::new().await,
+                "Failed to create the IOTA client"
+            )?;
+
+            with_context(
+                print_node_info(&iota_client).await,
+                "Failed to read the node info"
+            )
+        },
+        Command::Selftest => {
+            let mut iota_client: TangleClient = with_context(
+                TangleClient::new().await,
+                "Failed to create the IOTA client"
+            )?;
+            // The self-test block is a plain string, which cannot carry a
+            // signature.
+            iota_client.disable_signing();
+
+            let report: SelftestReport = run_selftest(&iota_client).await;
+            if cli_args.json {
+                JsonOutput::Selftest(&report).print()?;
+            } else {
+                print_selftest_report(&report);
+            }
+
+            if report.passed {
+                Ok(())
+            } else {
+                Err(Error::Anyhow(anyhow::Error::msg("The self-test failed")))
+            }
+        },
+    }
+}
+
+// Record a shipment: post the start transportation block, sample the metrics
+// for the configured duration and post the delivered transportation block.
+// Returns the receipt of the recorded shipment.
+async fn run_shipment(
+    cli_args: CliArgs,
+    config: AppConfig
+) -> Result<ShipmentReceipt, Error> {
+    // A resumed run continues the shipment of its checkpoint, from the same
+    // initial block.
+    let checkpoint_path: String = config.checkpoint_path();
+    let mut resumed: Option<RunCheckpoint> = if cli_args.resume {
+        Some(with_context(
+            RunCheckpoint::load(&checkpoint_path),
+            "Failed to load the checkpoint"
+        )?)
+    } else {
+        None
     };
 
-    Ok(value)
-}
+    let block_id: String = match &resumed {
+        Some(checkpoint) => checkpoint.initial_block_id.clone(),
+        None => with_context(
+            block_id_input(cli_args.block_id),
+            "Failed to read the initial block id"
+        )?,
+    };
 
-// Try to read the initial block id from the environment. If it does not exist,
-// ask the user to input it.
-fn block_id_input() -> Result<String, Error> {
-    let input: String = match read_env_var("INITIAL_BLOCK_ID".to_string()) {
-        Ok(value) => value,
-        Err(_err) => {
-            let stdin: io::Stdin = io::stdin();
-            println!("Enter BlockId:");
-            let mut user_input: String = String::new();
-            stdin
-                .read_line(&mut user_input)?;
-            user_input.to_string()
-        }
+    let mut iota_client: TangleClient = with_context(
+        TangleClient::new().await,
+        "Failed to create the IOTA client"
+    )?;
+    if cli_args.record.is_some() {
+        iota_client.enable_recording();
+    }
+    if cli_args.json {
+        iota_client.enable_json_output();
+    }
+    check_node_health(&iota_client).await;
+
+    // With the payment info in the configuration, the initial block is only
+    // referenced as the previous block of the transportation, never fetched.
+    let configured_payment_info: Option<PaymentInfo> = with_context(
+        config.payment_info(),
+        "Invalid configured payment info"
+    )?;
+    let payment_info: PaymentInfo = match configured_payment_info {
+        Some(payment_info) => {
+            with_context(
+                validate_delivery_payment_info(&payment_info),
+                "Invalid configured payment info"
+            )?;
+            info!("Using the configured payment info, the initial block is not fetched");
+            payment_info
+        },
+        None => initial_payment_info(&iota_client, &block_id).await?,
+    };
+
+    // Resolve the metrics up front, so an invalid metric is reported before
+    // the transportation starts. The metrics defined in the configuration
+    // follow the built-in ones.
+    let mut specs: Vec<MetricSpec> = vec![
+        with_context(temperature_spec(), "Invalid temperature range")?,
+        with_context(humidity_spec(), "Invalid humidity range")?,
+        with_context(pressure_spec(), "Invalid pressure range")?,
+        with_context(shock_spec(), "Invalid shock range")?,
+    ];
+    let custom_specs: Vec<MetricSpec> = with_context(
+        custom_metric_specs(config.custom_metrics.as_deref().unwrap_or(&[]), &specs),
+        "Invalid custom metrics"
+    )?;
+    specs.extend(custom_specs);
+    with_context(check_run_tags(&specs), "Invalid block tags")?;
+
+    // The shipment is recorded in resumable legs, one per carrier.
+    let journal_path: String = config.shipment_journal_path();
+    let mut journal: ShipmentJournal = with_context(
+        ShipmentJournal::load(&journal_path),
+        "Failed to load the shipment journal"
+    )?;
+    let force_new: bool = config.force_new.unwrap_or(false);
+    let carriers: Vec<String> = read_carriers();
+
+    // The sampling interval is applied between each full metric cycle.
+    // An interval of 0 posts the cycles back-to-back without any delay.
+    let sampling_interval: Duration = config.sampling_interval();
+    let total_duration: Duration = config.total_duration();
+    // With METRIC_COUNT, every leg lasts a count of sampling cycles instead,
+    // which the progress estimates at the global interval.
+    let metric_count: Option<usize> = config.metric_count;
+    let leg_duration: Duration = match metric_count {
+        Some(count) => sampling_interval
+            .saturating_mul(u32::try_from(count - 1).unwrap_or(u32::MAX)),
+        None => total_duration,
     };
 
-    if !input.starts_with("0x") {
-        return Err(Error::Anyhow(anyhow::Error::msg(
-            "BlockId must start with 0x",
-        )));
-    } else if input.len() != 66 {
-        return Err(Error::Anyhow(anyhow::Error::msg(
-            "BlockId must be 66 characters long",
-        )));
+    // Every random stream gets its own generator. When a seed is configured,
+    // each one is seeded with an offset of it so the streams differ from each
+    // other while staying reproducible.
+    let seed: Option<u64> = config.random_seed;
+    let stream_seed = |stream: u64| seed.map(|seed| seed.wrapping_add(stream));
+
+    // Every metric forms a chain of its own, starting from the start
+    // transportation block of each leg. The sources carry over from one leg
+    // to the next.
+    let mut chains: Vec<MetricChain> = Vec::new();
+    for (index, spec) in (0u64..).zip(specs) {
+        // Stream 2 belongs to the location, so seeded runs keep the readings
+        // they had before more metrics were added.
+        let stream: u64 = if index < 2 { index } else { index + 1 };
+
+        let source: Box<dyn MetricSource> = with_context(
+            metric_source_from_env(&spec, BoardRng::new(stream_seed(stream))),
+            &format!("Failed to open the {} source", spec.metric_type.to_lowercase())
+        )?;
+
+        let interval: Duration = with_context(
+            metric_sampling_interval(&spec),
+            &format!("Invalid {} sampling interval", spec.metric_type.to_lowercase())
+        )?.unwrap_or(sampling_interval);
+
+        chains.push(MetricChain {
+            spec,
+            source,
+            interval,
+            previous_block: BlockId::null(),
+            duplicates: with_context(
+                DuplicateTracker::from_env(),
+                "Invalid metric deduplication settings"
+            )?,
+        });
     }
 
-    Ok(input)
-}
+    let mut location_rng: BoardRng = BoardRng::new(stream_seed(2));
+    let mut position: ExportLocation = location_origin()?;
+
+    // With a batch size above 1, the metric readings are sampled without being
+    // posted and are grouped into batch blocks instead, which form a single
+    // chain of their own.
+    let batch_size: usize = config.metric_batch_size();
+
+    // From here on, Ctrl-C stops sampling but still delivers the shipment.
+    let shutdown: watch::Receiver<bool> = spawn_shutdown_listener();
+
+    // Every reading posted to the Tangle is kept by the in-run store and the
+    // local database, if any, as well.
+    let readings: MetricStore = MetricStore::new();
+    let sinks: MetricSinks = with_context(
+        MetricSinks::from_env(readings.clone()),
+        "Failed to open the metric sinks"
+    )?;
+    let mut metric_blocks: BTreeMap<String, usize> = BTreeMap::new();
+
+    // Serve the posted readings live while the run lasts, if a port is set.
+    if let Some(port) = config.http_port {
+        let store: MetricStore = readings.clone();
+        let collector_metrics: CollectorMetrics = iota_client.collector_metrics();
+        tokio::spawn(async move {
+            if let Err(err) = serve_metrics(port, store, collector_metrics).await {
+                error!("HTTP server stopped: {:#}", err);
+            }
+        });
+    }
 
-// Create an IOTA client with the given node URL. The client will use local PoW
-// with the maximum number of threads available on the machine.
-async fn create_iota_client() -> Result<Client, Error> {
-    let node_url: String = read_env_var("NODE_URL".to_string())?;
-
-    let client: Client = Client::builder()
-        .with_node(node_url.as_str())?
-        .with_local_pow(true)
-        .with_pow_worker_count(num_cpus::get())
-        .finish()
-        .await?;
-    Ok(client)
-}
+    // Report the progress of the run while it lasts, unless turned off.
+    let progress: ShipmentProgress = ShipmentProgress::new(leg_duration, carriers.len())
+        .with_max_blocks(config.max_blocks());
+    let progress_interval: Duration = config.progress_interval();
+    // The status line would break the JSON output, so it is left out of it.
+    let reporter: Option<ProgressReporter> = if progress_interval.is_zero() || cli_args.json {
+        None
+    } else {
+        Some(progress.spawn_reporter(progress_interval))
+    };
 
-async fn get_block(client: &Client, block_id: &String) -> Result<BlockDto, Error> {
-    let block_id: BlockId = block_id.parse()?;
+    let mut legs: Vec<TransportationLeg> = Vec::new();
+    let mut all_reading_heads: Vec<BlockId> = Vec::new();
+    let mut leg_previous_block: String = block_id.clone();
+    let mut deliver_transportation_block_id: BlockId = BlockId::null();
+
+    // The heads of every chain posted, to verify once the shipment is delivered.
+    let mut chain_heads: Vec<String> = Vec::new();
+
+    // The transportation blocks posted, paired with the network each one went
+    // to, for their explorer links on the receipt.
+    let mut receipt_blocks: Vec<ReceiptBlock> = Vec::new();
+
+    // A resumed run skips the legs its checkpoint already delivered.
+    let mut first_leg: usize = 0;
+    if let Some(checkpoint) = &resumed {
+        if checkpoint.leg_index >= carriers.len() {
+            return Err(Error::Validation(format!(
+                "checkpoint: leg {} is in progress, but there are only {} carriers",
+                checkpoint.leg_index + 1, carriers.len()
+            )));
+        }
 
-    let block: Block = client.get_block(&block_id).await?;
+        first_leg = checkpoint.leg_index;
+        legs = checkpoint.legs.clone();
+        for head in checkpoint.reading_heads.iter() {
+            all_reading_heads.push(with_context(
+                head.parse::<BlockId>().map_err(Error::from),
+                "Invalid reading chain head in the checkpoint"
+            )?);
+        }
+        chain_heads = checkpoint.reading_heads.clone();
+        receipt_blocks = checkpoint.blocks.clone();
+        leg_previous_block = checkpoint.leg_previous_block.clone();
+        info!(
+            "Resuming leg {} of {} from the checkpoint, {} into the run",
+            first_leg + 1, carriers.len(), format_duration(checkpoint.elapsed())
+        );
+    }
 
-    let block_dto: BlockDto = BlockDto::from(&block);
+    for (leg_index, carrier) in carriers.iter().enumerate().skip(first_leg) {
+        if carriers.len() > 1 {
+            info!(
+                "Starting leg {} of {}, carried by {}",
+                leg_index + 1, carriers.len(), carrier
+            );
+        }
 
-    Ok(block_dto)
-}
+        // Only the first leg of a resumed run continues from the checkpoint.
+        let resumed_leg: Option<RunCheckpoint> = resumed.take();
+
+        let start_transportation_block_id: BlockId = match &resumed_leg {
+            Some(checkpoint) => with_context(
+                checkpoint.start_block.parse::<BlockId>().map_err(Error::from),
+                "Invalid start block in the checkpoint"
+            )?,
+            None => {
+                let start_block_id: BlockId = start_leg(
+                    &iota_client,
+                    &mut journal,
+                    &journal_path,
+                    &leg_previous_block,
+                    carrier,
+                    force_new
+                ).await?;
+                receipt_blocks.push(iota_client.receipt_block(
+                    format!("Start transportation, leg {}", leg_index + 1),
+                    &start_block_id
+                ));
+                start_block_id
+            },
+        };
 
-// Extract the payment info from the block payload. Only specific block types
-// of our supply chain block model contain payment information.
-// RawMaterialsProducerBlockData, SupplierBlockData, ManufacturerBlockData,
-// DistributorBlockData, RetailerBlockData
-// The other block type are not accepted as input.
-fn extract_payment_info(block: BlockDto) -> Result<PaymentInfo, Error> {
-    use block_payload::BlockData::*;
-
-    let block_payload: PayloadDto = match block.payload {
-        Some(payload) => payload,
-        None => return Err(Error::Anyhow(anyhow::Error::msg(
-            "Block has no payload"
-        ))),
-    };
+        // Every chain continues from its head in the checkpoint, if resumed,
+        // and from the start block of the leg otherwise.
+        let mut location_previous_block: BlockId = start_transportation_block_id;
+        let mut batch_start_block: BlockId = start_transportation_block_id;
+        let mut elapsed: Duration = Duration::ZERO;
+        match &resumed_leg {
+            Some(checkpoint) => {
+                for chain in chains.iter_mut() {
+                    chain.previous_block = with_context(
+                        checkpoint.head(&chain.spec.metric_type),
+                        "Invalid metric chain head in the checkpoint"
+                    )?;
+                }
+                location_previous_block = with_context(
+                    checkpoint.head("Location"),
+                    "Invalid location chain head in the checkpoint"
+                )?;
+                batch_start_block = with_context(
+                    checkpoint.head("Batch"),
+                    "Invalid batch chain head in the checkpoint"
+                )?;
+                elapsed = checkpoint.elapsed();
+
+                let mut heads: BTreeMap<String, BlockId> = BTreeMap::new();
+                for chain in checkpoint.heads.keys() {
+                    heads.insert(chain.clone(), checkpoint.head(chain)?);
+                }
+                progress.resume_leg(leg_index + 1, elapsed, heads, checkpoint.cycles.clone());
+            },
+            None => {
+                for chain in chains.iter_mut() {
+                    chain.previous_block = start_transportation_block_id;
+
+                    // The first leg of a new run may continue the chains of an
+                    // earlier run of the shipment instead.
+                    if leg_index > 0 {
+                        continue;
+                    }
+                    let continued_head: Option<BlockId> = with_context(
+                        read_continued_head(&iota_client, &chain.spec.metric_type).await,
+                        "Cannot continue the metric chain of an earlier run"
+                    )?;
+                    if let Some(head) = continued_head {
+                        info!(
+                            "Continuing the {} chain from block {}",
+                            chain.spec.metric_type, head
+                        );
+                        chain.previous_block = head;
+                    }
+                }
+                progress.start_leg(leg_index + 1);
+            },
+        };
 
-    let tagged_data: Box<TaggedDataPayloadDto> = match block_payload {
-        PayloadDto::TaggedData(data) => data,
-        _ => return Err(Error::Anyhow(anyhow::Error::msg(
-            "Block payload is not tagged data"
-        )))
-    };
+        // Dry runs post nothing, so there is no run to resume.
+        if start_transportation_block_id != BlockId::null() {
+            progress.checkpoint_to(&checkpoint_path, RunCheckpoint {
+                initial_block_id: block_id.clone(),
+                leg_index,
+                leg_previous_block: leg_previous_block.clone(),
+                start_block: start_transportation_block_id.to_string(),
+                heads: BTreeMap::new(),
+                elapsed_secs: elapsed.as_secs(),
+                cycles: BTreeMap::new(),
+                legs: legs.clone(),
+                reading_heads: all_reading_heads
+                    .iter()
+                    .map(|head| head.to_string())
+                    .collect(),
+                blocks: receipt_blocks.clone(),
+            });
+        }
 
-    let string_data: String = String::from_utf8((*tagged_data.data).to_vec())?;
+        // Every chain is sampled on a timer of its own until the leg ends,
+        // when the total duration has passed or the chain took its count of
+        // readings, the block cap was reached or a shutdown was requested.
+        let timer: LegTimer = LegTimer {
+            deadline: match metric_count {
+                Some(_count) => None,
+                None => Some(Instant::now() + total_duration.saturating_sub(elapsed)),
+            },
+            remaining_cycles: metric_count,
+            chain: String::new(),
+            shutdown: shutdown.clone(),
+            progress: progress.clone(),
+        };
 
-    let block_payload: TaggedDataPayload = serde_json::from_str(&string_data)?;
+        // The metrics track independent chains, so they are posted
+        // concurrently, next to the location, and each chain head only
+        // advances if its own post succeeded. Batches hold a reading of every
+        // metric, so batched metrics are sampled together at the global
+        // interval instead of their own.
+        let (reading_heads, location_blocks): (Vec<BlockId>, usize) = tokio::join!(
+            async {
+                if batch_size > 1 {
+                    let (batch_head, batch_blocks): (BlockId, usize) = run_metric_batches(
+                        &iota_client,
+                        &mut chains,
+                        batch_size,
+                        batch_start_block,
+                        &sinks,
+                        &progress,
+                        sampling_interval,
+                        timer.for_chain("Batch")
+                    ).await;
+                    *metric_blocks.entry(String::from("Batch")).or_insert(0) += batch_blocks;
+
+                    vec![batch_head]
+                } else {
+                    let chain_blocks: Vec<usize> = join_all(chains.iter_mut().map(|chain| {
+                        let timer: LegTimer = timer.for_chain(&chain.spec.metric_type);
+                        chain.run(&iota_client, &sinks, &progress, timer)
+                    })).await;
+
+                    for (chain, blocks) in chains.iter().zip(chain_blocks) {
+                        *metric_blocks.entry(chain.spec.metric_type.clone()).or_insert(0) += blocks;
+                    }
+
+                    chains.iter().map(|chain| chain.previous_block).collect()
+                }
+            },
+            run_location_chain(
+                &iota_client,
+                &mut location_rng,
+                &mut position,
+                &mut location_previous_block,
+                &progress,
+                sampling_interval,
+                timer.for_chain("Location")
+            )
+        );
+        *metric_blocks.entry(String::from("Location")).or_insert(0) += location_blocks;
+
+        let mut metrics: Vec<String> =
+            reading_heads.iter().map(|block_id| block_id.to_string()).collect();
+        metrics.push(location_previous_block.to_string());
+        all_reading_heads.extend(reading_heads);
+        chain_heads.extend(metrics.iter().cloned());
+
+        // An interrupted or capped shipment is delivered by the carrier
+        // holding it.
+        let last_leg: bool = leg_index + 1 == carriers.len()
+            || *shutdown.borrow()
+            || progress.block_cap_reached();
+
+        legs.push(TransportationLeg {
+            carrier: carrier.to_owned(),
+            start_block: BlockRef::from(start_transportation_block_id),
+            delivery_block: None,
+        });
+
+        let (next_leg_previous_block, delivered_legs):
+            (Option<BlockRef>, Option<Vec<TransportationLeg>>) = if !last_leg {
+            (Some(BlockRef::from(start_transportation_block_id)), None)
+        } else if legs.len() > 1 {
+            (None, Some(legs.clone()))
+        } else {
+            (None, None)
+        };
 
-    let payment_info: PaymentInfo = match block_payload.data {
-        RawMaterialsProducerBlockData(data) => data.payment_info,
-        SupplierBlockData(data) => data.payment_info,
-        ManufacturerBlockData(data) => data.payment_info,
-        DistributorBlockData(data) => data.payment_info,
-        RetailerBlockData(data) => data.payment_info,
-        _ => return Err(Error::Anyhow(anyhow::Error::msg(
-            "Block payload does not contain payment info data"
-        )))
-    };
+        // Surface a delivery the sender wallet cannot settle before recording it.
+        check_sender_balance(&iota_client, &payment_info).await?;
+
+        deliver_transportation_block_id = with_context(
+            deliver_transportation(
+                &iota_client,
+                payment_info.clone(),
+                metrics,
+                next_leg_previous_block,
+                delivered_legs
+            ).await,
+            "Failed to post the delivered transportation block"
+        )?;
+        receipt_blocks.push(iota_client.receipt_block(
+            format!("Delivery, leg {}", leg_index + 1),
+            &deliver_transportation_block_id
+        ));
+
+        if journal.record_delivery(&leg_previous_block) {
+            if let Err(err) = journal.save(&journal_path) {
+                warn!("Failed to save the shipment journal: {:#}", err);
+            }
+        }
 
-    Ok(payment_info)
-}
+        if last_leg {
+            break;
+        }
 
-async fn post_iota_block(
-    client: &Client,
-    tag: Vec<u8>,
-    data: Vec<u8>
-) -> Result<BlockId, Error> {
+        // The next carrier takes the shipment over from this delivery.
+        leg_previous_block = deliver_transportation_block_id.to_string();
+        if let Some(leg) = legs.last_mut() {
+            leg.delivery_block = Some(BlockRef::from(deliver_transportation_block_id));
+        }
+    }
+
+    if let Some(reporter) = reporter {
+        reporter.stop();
+    }
+
+    // The shipment is delivered, so there is nothing left to resume.
+    if let Err(err) = RunCheckpoint::remove(&checkpoint_path) {
+        warn!("Failed to remove the checkpoint: {:#}", err);
+    }
+
+    if !cli_args.json {
+        print_metric_summaries(&summarize_metrics(&readings.all()));
+    }
+
+    // Walk every chain back to its start block, if requested. Dry runs post no
+    // chains to walk.
+    if config.verify_chains.unwrap_or(false) && !iota_client.dry_run() {
+        let reports: Vec<VerifyReport> = with_context(
+            verify_chains(&iota_client, &chain_heads).await,
+            "Failed to verify the chains"
+        )?;
+        print_verify_reports(&reports, cli_args.json)?;
+
+        let broken_chains: usize = reports.iter().filter(|report| !report.is_intact()).count();
+        if broken_chains > 0 {
+            return Err(Error::Anyhow(anyhow::Error::msg(format!(
+                "{} of {} chains are broken", broken_chains, reports.len()
+            ))));
+        }
+        info!("All {} chains are intact", reports.len());
+    }
+
+    // Export the metric readings read back from their chains, if an export
+    // path is configured.
+    if let Some(csv_path) = config.metrics_csv_path {
+        let heads: Vec<String> =
+            all_reading_heads.iter().map(|head| head.to_string()).collect();
+        let chain_readings: Vec<MetricData> = with_context(
+            resolve_metric_chains(&iota_client, &heads).await,
+            "Failed to collect a metric chain"
+        )?;
+
+        with_context(
+            export_metrics_csv(&chain_readings, &csv_path),
+            "Failed to export the metrics CSV"
+        )?;
+        info!("Metrics exported to: {}", csv_path);
+    }
+
+    if !cli_args.json {
+        print_posting_stats(&iota_client.posting_stats());
+    }
+
+    // The shipment is delivered, so a failing record must not fail the run.
+    if let Some(record_path) = &cli_args.record {
+        match iota_client.take_shipment_record().save(record_path) {
+            Ok(()) => info!("Shipment recorded to: {}", record_path),
+            Err(err) => warn!("Failed to record the shipment: {:#}", err),
+        };
+    }
 
-    print!("--------------------------------------------------\n");
-    println!("Posting block...");
-    let start: Instant = Instant::now();
-    
-    let block: Block = client
-        .build_block()
-        .with_tag(tag)
-        .with_data(data)
-        .finish()
-        .await?;
-    
-    let block_id: BlockId = client.post_block(&block).await?;
-
-    println!("Block posted ---- {:?}", start.elapsed());
-    print_block_on_explorer(&block_id.to_string())?;
-    print!("--------------------------------------------------\n");
-
-    Ok(block_id)
+    let delivery_block_id: String = deliver_transportation_block_id.to_string();
+
+    // The readings are already posted, so their breaches are only counted.
+    let breaches: BTreeMap<String, BreachSummary> = with_context(
+        summarize_breaches(&readings.all(), AlertRange::from_env),
+        "Invalid metric OK range"
+    )?;
+
+    Ok(ShipmentReceipt {
+        initial_block_id: block_id,
+        start_transportation_block_id: legs[0].start_block.to_string(),
+        metric_blocks,
+        delivery_explorer_url: iota_client.explorer_link(&delivery_block_id),
+        delivery_block_id,
+        smr_cost: Some(payment_info.smr_cost),
+        legs: if legs.len() > 1 { legs } else { Vec::new() },
+        blocks: receipt_blocks,
+        breaches,
+    })
 }
 
-async fn start_transportation(
-    client: &Client,
-    initial_block_id: &String
+// Start a leg of the shipment from the given previous block: the initial block
+// for the first leg, the delivery of the leg before it otherwise. The leg is
+// resumed if a previous run already started it from the same block, unless a
+// new one is forced. Returns the start transportation block of the leg.
+async fn start_leg(
+    client: &TangleClient,
+    journal: &mut ShipmentJournal,
+    journal_path: &str,
+    previous_block_id: &String,
+    carrier: &str,
+    force_new: bool
 ) -> Result<BlockId, Error> {
-
-    let file_cid: Option<String> = match read_env_var("START_TRANSPORTATION_CID".to_string()){
-        Ok(value) => Some(value),
-        Err(_err) => None
+    let resumed_block_id: Option<BlockId> = match journal.started_shipment(previous_block_id) {
+        Some(start_block_id) if !force_new => {
+            if confirm_start_block(client, start_block_id, previous_block_id).await {
+                start_block_id.parse::<BlockId>().ok()
+            } else {
+                None
+            }
+        },
+        _ => None,
     };
 
-    let product_info: ProductInfo = ProductInfo::new(
-        String::from("Transportation Information Data"), file_cid
-    );
-
-    let start_transaction_data: StartTransportationData = 
-        StartTransportationData::new(
-            String::from("Transportation Company Information Data"),
-            product_info,
-            Local::now().to_string(),
-            initial_block_id.to_owned()
-        );
-    
-    let data: Vec<u8> = serde_json::to_string(&start_transaction_data)?
-        .as_bytes()
-        .to_vec();
+    if let Some(start_block_id) = resumed_block_id {
+        info!("Resuming the shipment started by block {}", start_block_id);
+        return Ok(start_block_id);
+    }
 
-    let tag: Vec<u8> = String::from("Start Transportation Tag").as_bytes().to_vec();
+    let start_block_id: BlockId = with_context(
+        start_transportation(client, previous_block_id, carrier).await,
+        "Failed to post the start transportation block"
+    )?;
 
-    let block_id: BlockId = post_iota_block(client, tag, data).await?;
+    // Dry runs post nothing, so there is no shipment to resume.
+    if start_block_id != BlockId::null() {
+        journal.record_start(previous_block_id, &start_block_id.to_string());
+        if let Err(err) = journal.save(journal_path) {
+            warn!("Failed to save the shipment journal: {:#}", err);
+        }
+    }
 
-    Ok(block_id)
+    Ok(start_block_id)
 }
 
-fn print_block_on_explorer(block_id: &String) -> Result<(), Error> {
-    let explorer_url: String = read_env_var("EXPLORER_URL".to_string())?;
-    let block_explorer_url: String = format!("{}/block/{}", explorer_url, block_id);
-    println!("Block posted on: {}", block_explorer_url);
-    Ok(())
+// A metric posted as a chain of its own, along with the source of its readings
+// and, when deduplication is on, its repeated readings.
+struct MetricChain {
+    spec: MetricSpec,
+    source: Box<dyn MetricSource>,
+    interval: Duration,
+    previous_block: BlockId,
+    duplicates: Option<DuplicateTracker>,
 }
 
-fn gen_random_number(min: f64, max: f64) -> Result<f64, Error>{
-    let mut rng: ThreadRng = rand::thread_rng();
-    let random_number: f64 = rng.gen::<f64>();
+impl MetricChain {
+    // Post the next reading of the metric. The chain head only advances if
+    // the reading was posted as a metric block. Returns the posted reading,
+    // or None if it was deduplicated.
+    async fn post(&mut self, client: &TangleClient) -> Result<Option<MetricData>, Error> {
+        let previous: String = self.previous_block.to_string();
+
+        let posted: Option<(BlockId, MetricData)> = match self.duplicates.as_mut() {
+            Some(tracker) => post_deduplicated_metric(
+                client,
+                &self.spec,
+                self.source.as_mut(),
+                &previous,
+                tracker
+            ).await?,
+            None => Some(
+                post_metric(client, &self.spec, self.source.as_mut(), &previous).await?
+            ),
+        };
 
-    // Specify range
-    let number: f64 = min + (max - min) * random_number;
+        Ok(posted.map(|(block_id, metric_data)| {
+            self.previous_block = block_id;
+            metric_data
+        }))
+    }
+
+    // Post a reading of the metric at its own interval until the leg ends.
+    // Returns the number of metric blocks posted.
+    async fn run(
+        &mut self,
+        client: &TangleClient,
+        sinks: &MetricSinks,
+        progress: &ShipmentProgress,
+        mut timer: LegTimer
+    ) -> usize {
+        let mut posted_blocks: usize = 0;
+
+        if timer.finished() {
+            return posted_blocks;
+        }
 
-    let res: f64 = (number * 100.0).round() / 100.0;
-    Ok(res)
+        loop {
+            match self.post(client).await {
+                Ok(Some(metric_data)) => {
+                    posted_blocks += 1;
+                    progress.record_block(&self.spec.metric_type, self.previous_block);
+                    sinks.post(&metric_data);
+                },
+                Ok(None) => {},
+                Err(err) => error!("{:#}", err)
+            };
+
+            if !timer.wait(self.interval).await {
+                return posted_blocks;
+            }
+        }
+    }
 }
 
-async fn temperature_metric(
-    client: &Client,
-    previous_block_id: &String
-) -> Result<BlockId, Error>{
-    let metric_data: MetricData = MetricData::new(
-        String::from("Temperature"),
-        gen_random_number(-5.0, 30.0)?,
-        String::from("Celsius"),
-        Local::now().to_string(),
-        previous_block_id.to_owned()
-    );
+// The end of the sampling of a leg: its deadline or count of cycles, the block
+// cap of the run, or a shutdown request. Every chain sampled during the leg
+// holds a timer of its own, so each one counts its own cycles.
+#[derive(Clone)]
+struct LegTimer {
+    deadline: Option<Instant>,
+    remaining_cycles: Option<usize>,
+    // The chain the cycles are counted for.
+    chain: String,
+    shutdown: watch::Receiver<bool>,
+    progress: ShipmentProgress,
+}
 
-    let data: Vec<u8> = serde_json::to_string(&metric_data)?
-        .as_bytes()
-        .to_vec();
+impl LegTimer {
+    // The timer of the given chain. A chain of a resumed leg only takes the
+    // cycles it had left.
+    fn for_chain(&self, chain: &str) -> LegTimer {
+        LegTimer {
+            remaining_cycles: self.remaining_cycles
+                .map(|cycles| cycles.saturating_sub(self.progress.cycles(chain))),
+            chain: chain.to_owned(),
+            ..self.clone()
+        }
+    }
 
-    let tag: Vec<u8> = String::from("Temperature Metric Tag").as_bytes().to_vec();
+    // Whether the chain took all of its cycles, so it takes no more readings.
+    fn finished(&self) -> bool {
+        self.remaining_cycles == Some(0)
+    }
 
-    let block_id: BlockId = post_iota_block(client, tag, data).await?;
+    fn stopped(&self) -> bool {
+        *self.shutdown.borrow() || self.progress.block_cap_reached()
+    }
 
-    Ok(block_id)
-}
+    // Wait for the next reading, taken the given interval from now. It is
+    // called once a cycle is done. Returns false without waiting if that was
+    // the last cycle, the next one is past the deadline or the block cap was
+    // reached, and as soon as a shutdown is requested, so no reading is taken
+    // after the leg ended.
+    async fn wait(&mut self, interval: Duration) -> bool {
+        if let Some(remaining_cycles) = self.remaining_cycles.as_mut() {
+            *remaining_cycles = remaining_cycles.saturating_sub(1);
+            self.progress.record_cycle(&self.chain);
+        }
 
-async fn humidity_metric(
-    client: &Client,
-    previous_block_id: &String
-) -> Result<BlockId, Error>{
-    let metric_data: MetricData = MetricData::new(
-        String::from("Humidity"),
-        gen_random_number(0.0, 100.0)?,
-        String::from("%"),
-        Local::now().to_string(),
-        previous_block_id.to_owned()
-    );
+        let past_deadline: bool = self.deadline
+            .is_some_and(|deadline| Instant::now() + interval > deadline);
+        if self.stopped() || past_deadline || self.remaining_cycles == Some(0) {
+            return false;
+        }
 
-    let data: Vec<u8> = serde_json::to_string(&metric_data)?
-        .as_bytes()
-        .to_vec();
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => !self.stopped(),
+            Ok(()) = self.shutdown.changed() => false,
+        }
+    }
+}
 
-    let tag: Vec<u8> = String::from("Humidity Metric Tag").as_bytes().to_vec();
+// Post the position of the shipment at the global interval until the leg
+// ends. Returns the number of location blocks posted.
+async fn run_location_chain(
+    client: &TangleClient,
+    rng: &mut BoardRng,
+    position: &mut ExportLocation,
+    previous_block: &mut BlockId,
+    progress: &ShipmentProgress,
+    interval: Duration,
+    mut timer: LegTimer
+) -> usize {
+    let mut posted_blocks: usize = 0;
+
+    if timer.finished() {
+        return posted_blocks;
+    }
 
-    let block_id: BlockId = post_iota_block(client, tag, data).await?;
+    loop {
+        match location_metric(client, rng, position, &previous_block.to_string()).await {
+            Ok(block_id) => {
+                *previous_block = block_id;
+                posted_blocks += 1;
+                progress.record_block("Location", block_id);
+            },
+            Err(err) => error!("{:#}", err)
+        };
 
-    Ok(block_id)
+        if !timer.wait(interval).await {
+            return posted_blocks;
+        }
+    }
 }
 
-async fn deliver_transportation(
-    client: &Client,
-    payment_info: PaymentInfo,
-    metrics: Vec<String>
-) -> Result<BlockId, Error> {
-    let file_cid: Option<String> = match read_env_var("DELIVER_TRANSPORTATION_CID".to_string()){
-        Ok(value) => Some(value),
-        Err(_err) => None
-    };
+// Sample every metric at the global interval until the leg ends, grouping the
+// readings into batch blocks chained from the start block of the leg. The
+// readings still pending when the leg ends are flushed as a last batch.
+// Returns the head of the batch chain and the number of batch blocks posted.
+async fn run_metric_batches(
+    client: &TangleClient,
+    chains: &mut [MetricChain],
+    batch_size: usize,
+    start_block: BlockId,
+    sinks: &MetricSinks,
+    progress: &ShipmentProgress,
+    interval: Duration,
+    mut timer: LegTimer
+) -> (BlockId, usize) {
+    let mut batch_previous_block: BlockId = start_block;
+    let mut pending_batch: Vec<MetricData> = Vec::new();
+    let mut posted_blocks: usize = 0;
+
+    if timer.finished() {
+        return (batch_previous_block, posted_blocks);
+    }
 
-    let product_info: ProductInfo = ProductInfo::new(
-        String::from("Product Delivery Information"), file_cid
-    );
+    loop {
+        let batch_previous: String = batch_previous_block.to_string();
 
-    let delivered_transportation_data: DeliveredTransportationData = 
-        DeliveredTransportationData::new(
-            product_info,
-            Local::now().to_string(),
-            payment_info,
-            metrics
-        );
+        for chain in chains.iter_mut() {
+            match sample_metric(&chain.spec, chain.source.as_mut(), &batch_previous) {
+                Ok(metric_data) => pending_batch.push(metric_data),
+                Err(err) => error!("{:#}", err)
+            };
+        }
 
-    let data: Vec<u8> = serde_json::to_string(&delivered_transportation_data)?
-        .as_bytes()
-        .to_vec();
-    
-    let tag: Vec<u8> = String::from("Delivered Transportation Tag")
-        .as_bytes()
-        .to_vec();
+        if pending_batch.len() >= batch_size && flush_metric_batch(
+            client,
+            &mut pending_batch,
+            &mut batch_previous_block,
+            sinks
+        ).await {
+            posted_blocks += 1;
+            progress.record_block("Batch", batch_previous_block);
+        }
 
-    let block_id: BlockId = post_iota_block(client, tag, data).await?;
+        if !timer.wait(interval).await {
+            break;
+        }
+    }
 
-    Ok(block_id)
-}
+    if !pending_batch.is_empty() && flush_metric_batch(
+        client,
+        &mut pending_batch,
+        &mut batch_previous_block,
+        sinks
+    ).await {
+        posted_blocks += 1;
+        progress.record_block("Batch", batch_previous_block);
+    }
 
-#[tokio::main]
-async fn main() {
-    let block_id: String = block_id_input().unwrap();
+    (batch_previous_block, posted_blocks)
+}
 
-    let iota_client: Client = create_iota_client().await.unwrap();
+// Fetch the initial block and read the payment info of the delivery from it.
+// Confirms the initial block is a supply chain block carrying payment info
+// before the transportation starts from it.
+async fn initial_payment_info(
+    iota_client: &TangleClient,
+    block_id: &String
+) -> Result<PaymentInfo, Error> {
+    let initial_block: BlockDto = with_context(
+        iota_client.get_block(block_id).await,
+        "Failed to fetch the initial block"
+    )?;
+
+    let initial_data: BlockData = with_context(
+        extract_block_data(block_id, initial_block),
+        "Failed to decode the initial block"
+    )?;
+
+    let origin_info: OriginInfo = with_context(
+        origin_info_from_data(initial_data),
+        "The initial block is not a producer, supplier, manufacturer, \
+        distributor or retailer block"
+    )?;
+    info!("Initial block is a {} block", origin_info.kind);
+    if let Some(product_info) = &origin_info.product_info {
+        info!("Transporting product: {}", product_info.info);
+    }
 
-    let initial_block: BlockDto = 
-        get_block(&iota_client, &block_id)
-        .await
-        .unwrap();
-    
-    let payment_info: PaymentInfo = 
-        extract_payment_info(initial_block)
-        .unwrap();
+    Ok(origin_info.payment_info)
+}
 
-    let start_transportation_block_id: BlockId =
-        start_transportation(&iota_client, &block_id).await.unwrap();
+// Listen for Ctrl-C in the background. The first interrupt asks the metric
+// loop to stop, so the chain heads are still delivered. A second interrupt
+// exits immediately.
+fn spawn_shutdown_listener() -> watch::Receiver<bool> {
+    let (sender, receiver) = watch::channel(false);
 
-    let start_time: Instant = Instant::now();
-    let one_minute: Duration = Duration::from_secs(120);
-    
-    let mut temperature_previous_block: BlockId = start_transportation_block_id;
-    let mut humidity_previous_block: BlockId = start_transportation_block_id;
-    let mut metrics: Vec<String> = Vec::new();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
 
-    loop {
+        warn!("Interrupted, delivering the shipment. Press Ctrl-C again to exit");
+        let _ = sender.send(true);
 
-        match temperature_metric(&iota_client, &temperature_previous_block.to_string()).await {
-            Ok(block_id) => temperature_previous_block = block_id,
-            Err(err) => println!("Error: {:?}", err)
-        };
+        if tokio::signal::ctrl_c().await.is_ok() {
+            process::exit(130);
+        }
+    });
 
-        match humidity_metric(&iota_client, &humidity_previous_block.to_string()).await {
-            Ok(block_id) => humidity_previous_block = block_id,
-            Err(err) => println!("Error: {:?}", err)
-        };
+    receiver
+}
 
-        if start_time.elapsed() >= one_minute {
-            metrics.push(temperature_previous_block.to_string());
-            metrics.push(humidity_previous_block.to_string());
-            break;
+// Post the pending readings as one batch block. On success the batch chain
+// advances and the readings move to the posted ones. On failure they stay
+// pending and are posted with the next flush. Returns whether the batch was
+// posted.
+async fn flush_metric_batch(
+    client: &TangleClient,
+    pending_batch: &mut Vec<MetricData>,
+    batch_previous_block: &mut BlockId,
+    sinks: &MetricSinks
+) -> bool {
+    match post_metric_batch(
+        client,
+        pending_batch.clone(),
+        &batch_previous_block.to_string()
+    ).await {
+        Ok(block_id) => {
+            *batch_previous_block = block_id;
+            sinks.post_all(pending_batch);
+            pending_batch.clear();
+            true
+        },
+        Err(err) => {
+            error!("{:#}", err);
+            false
         }
     }
+}
 
-    let _deliver_transportation_block_id: BlockId =
-        deliver_transportation(&iota_client, payment_info, metrics)
-        .await.unwrap();
+// Run the board and exit with a nonzero status code if any step fails, printing
+// the error and its context instead of panicking.
+#[tokio::main]
+async fn main() {
+    let cli_args: CliArgs = match CliArgs::parse() {
+        Ok(cli_args) => cli_args,
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            process::exit(1);
+        },
+    };
 
+    // Log at info level unless RUST_LOG selects otherwise. With --json, stdout
+    // only carries the JSON output, so the logs go to stderr.
+    let log_writer: BoxMakeWriter = if cli_args.json {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_err| EnvFilter::new("info"))
+        )
+        .with_writer(log_writer)
+        .init();
+
+    if let Err(err) = run(cli_args).await {
+        eprintln!("Error: {:#}", err);
+        process::exit(1);
+    }
 }
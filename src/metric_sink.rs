@@ -0,0 +1,186 @@
+// Rust module with the sinks every posted metric reading is fanned out to.
+// The Tangle is the immutable audit trail of the readings, but walking a
+// chain back block by block is slow, so the readings the Tangle accepted are
+// also kept by local sinks: the in-run store served over HTTP and, with
+// METRICS_DB_PATH set, a SQLite database that can be queried after the run.
+
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection, Statement};
+#[cfg(feature = "sqlite")]
+use std::sync::{Mutex, MutexGuard};
+use tracing::warn;
+
+use crate::{
+    block_payload::MetricData, custom_error::Error, metric_store::MetricStore, read_env_var,
+};
+#[cfg(feature = "sqlite")]
+use crate::block_payload::BlockRef;
+
+pub trait MetricSink: Send + Sync {
+    // The name the sink is reported by when it fails.
+    fn name(&self) -> &str;
+
+    fn post(&self, metric_data: &MetricData) -> Result<(), Error>;
+}
+
+impl MetricSink for MetricStore {
+    fn name(&self) -> &str {
+        "in-run store"
+    }
+
+    fn post(&self, metric_data: &MetricData) -> Result<(), Error> {
+        self.push(metric_data.clone());
+        Ok(())
+    }
+}
+
+// The sinks a posted reading is fanned out to, in order.
+pub struct MetricSinks {
+    sinks: Vec<Box<dyn MetricSink>>,
+}
+
+impl MetricSinks {
+    pub fn new(sinks: Vec<Box<dyn MetricSink>>) -> Self {
+        Self { sinks }
+    }
+
+    // The in-run store of the readings, along with the SQLite database at
+    // METRICS_DB_PATH, if set.
+    pub fn from_env(readings: MetricStore) -> Result<Self, Error> {
+        let mut sinks: Vec<Box<dyn MetricSink>> = vec![Box::new(readings)];
+
+        if let Ok(path) = read_env_var("METRICS_DB_PATH".to_string()) {
+            sinks.push(sqlite_sink(&path)?);
+        }
+
+        Ok(Self::new(sinks))
+    }
+
+    // Hand a reading to every sink. A failing sink is logged and skipped, so
+    // it does not keep the reading from the others.
+    pub fn post(&self, metric_data: &MetricData) {
+        for sink in self.sinks.iter() {
+            if let Err(err) = sink.post(metric_data) {
+                warn!(
+                    "Failed to store the {} reading of {} in the {}: {:#}",
+                    metric_data.metric_type, metric_data.timestamp, sink.name(), err
+                );
+            }
+        }
+    }
+
+    pub fn post_all(&self, readings: &[MetricData]) {
+        for metric_data in readings.iter() {
+            self.post(metric_data);
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_sink(path: &str) -> Result<Box<dyn MetricSink>, Error> {
+    Ok(Box::new(SqliteSink::open(path)?))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn sqlite_sink(_path: &str) -> Result<Box<dyn MetricSink>, Error> {
+    Err(Error::Anyhow(anyhow::Error::msg(
+        "METRICS_DB_PATH is set, but the board was built without the sqlite feature"
+    )))
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_error(err: rusqlite::Error) -> Error {
+    Error::Anyhow(anyhow::Error::new(err).context("SQLite metric database"))
+}
+
+// A SQLite database of the readings, one row per reading, indexed by metric
+// type and timestamp. Readings of earlier runs are kept, so the database
+// holds every run that wrote to it.
+#[cfg(feature = "sqlite")]
+pub struct SqliteSink {
+    // A connection cannot be shared between threads, so the concurrent
+    // chains take turns on it.
+    connection: Mutex<Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteSink {
+    // Open the database at the given path, created if missing. ":memory:"
+    // opens a database that only lasts as long as the sink.
+    pub fn open(path: &str) -> Result<Self, Error> {
+        let connection: Connection = Connection::open(path).map_err(sqlite_error)?;
+
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS metrics (
+                id INTEGER PRIMARY KEY,
+                metric_type TEXT NOT NULL,
+                metric_value REAL NOT NULL,
+                measurement_unit TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                previous_block TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS metrics_by_type ON metrics (metric_type, timestamp);"
+        ).map_err(sqlite_error)?;
+
+        Ok(Self { connection: Mutex::new(connection) })
+    }
+
+    // A row is only ever inserted whole, so the connection is still usable if
+    // a holder of the lock panicked.
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // The stored readings of a metric type, or of every type, in the order
+    // they were stored.
+    pub fn readings(&self, metric_type: Option<&str>) -> Result<Vec<MetricData>, Error> {
+        let connection: MutexGuard<'_, Connection> = self.lock();
+        let mut statement: Statement<'_> = connection.prepare(
+            "SELECT metric_type, metric_value, measurement_unit, timestamp, previous_block
+            FROM metrics WHERE ?1 IS NULL OR metric_type = ?1 ORDER BY id"
+        ).map_err(sqlite_error)?;
+
+        let rows: Vec<(String, f64, String, String, String)> = statement
+            .query_map(params![metric_type], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+            })
+            .and_then(|rows| rows.collect())
+            .map_err(sqlite_error)?;
+
+        rows.into_iter()
+            .map(|(metric_type, metric_value, measurement_unit, timestamp, previous_block)| {
+                Ok(MetricData::new(
+                    metric_type,
+                    metric_value,
+                    measurement_unit,
+                    timestamp,
+                    BlockRef::new(previous_block)?
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl MetricSink for SqliteSink {
+    fn name(&self) -> &str {
+        "SQLite metric database"
+    }
+
+    fn post(&self, metric_data: &MetricData) -> Result<(), Error> {
+        self.lock().execute(
+            "INSERT INTO metrics
+                (metric_type, metric_value, measurement_unit, timestamp, previous_block)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                metric_data.metric_type,
+                metric_data.metric_value,
+                metric_data.measurement_unit,
+                metric_data.timestamp,
+                metric_data.previous_block.as_str()
+            ],
+        ).map_err(sqlite_error)?;
+
+        Ok(())
+    }
+}
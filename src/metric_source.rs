@@ -0,0 +1,303 @@
+// Rust module with the sources metric readings can be taken from.
+// The board posts whatever value the configured source returns, so the same
+// posting logic works for generated demo data, recorded traces and real sensors.
+
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Lines},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{config::DEFAULT_READING_DECIMALS, custom_error::Error};
+
+// A source of metric readings. Each call to read returns the next value of the
+// given metric type.
+pub trait MetricSource: Send {
+    fn read(&mut self, metric_type: &str) -> Result<f64, Error>;
+}
+
+// Random number generator of the board. When created with a seed it is backed
+// by a StdRng, so runs can be reproduced. Otherwise it draws from thread_rng.
+pub struct BoardRng {
+    seeded: Option<StdRng>,
+}
+
+impl BoardRng {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self { seeded: seed.map(StdRng::seed_from_u64) }
+    }
+}
+
+impl RngCore for BoardRng {
+    fn next_u32(&mut self) -> u32 {
+        match &mut self.seeded {
+            Some(rng) => rng.next_u32(),
+            None => rand::thread_rng().next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match &mut self.seeded {
+            Some(rng) => rng.next_u64(),
+            None => rand::thread_rng().next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match &mut self.seeded {
+            Some(rng) => rng.fill_bytes(dest),
+            None => rand::thread_rng().fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match &mut self.seeded {
+            Some(rng) => rng.try_fill_bytes(dest),
+            None => rand::thread_rng().try_fill_bytes(dest),
+        }
+    }
+}
+
+// Generates uniformly distributed random readings within the given range.
+pub struct RandomSource {
+    min: f64,
+    max: f64,
+    decimals: u32,
+    rng: BoardRng,
+}
+
+impl RandomSource {
+    pub fn new(min: f64, max: f64, rng: BoardRng) -> Self {
+        Self { min, max, decimals: DEFAULT_READING_DECIMALS, rng }
+    }
+
+    // Round the readings to the given number of decimals.
+    pub fn with_decimals(mut self, decimals: u32) -> Self {
+        self.decimals = decimals;
+        self
+    }
+}
+
+impl MetricSource for RandomSource {
+    fn read(&mut self, _metric_type: &str) -> Result<f64, Error> {
+        gen_random_number_with(&mut self.rng, self.min, self.max, self.decimals)
+    }
+}
+
+// Generates readings as a random walk: the first reading is uniform within the
+// range, and every later one moves from the previous reading by at most
+// max_delta, staying within the range.
+pub struct WalkSource {
+    min: f64,
+    max: f64,
+    max_delta: f64,
+    decimals: u32,
+    previous: Option<f64>,
+    rng: BoardRng,
+}
+
+impl WalkSource {
+    pub fn new(min: f64, max: f64, max_delta: f64, rng: BoardRng) -> Self {
+        Self { min, max, max_delta, decimals: DEFAULT_READING_DECIMALS, previous: None, rng }
+    }
+
+    // Round the readings to the given number of decimals.
+    pub fn with_decimals(mut self, decimals: u32) -> Self {
+        self.decimals = decimals;
+        self
+    }
+}
+
+impl MetricSource for WalkSource {
+    fn read(&mut self, _metric_type: &str) -> Result<f64, Error> {
+        let value: f64 = match self.previous {
+            Some(previous) => next_walk_value(
+                previous, self.max_delta, self.min, self.max, self.decimals, &mut self.rng
+            ),
+            None => gen_random_number_with(&mut self.rng, self.min, self.max, self.decimals)?,
+        };
+
+        self.previous = Some(value);
+        Ok(value)
+    }
+}
+
+// Reads recorded readings from a newline-delimited file, one reading per line.
+// Empty lines are skipped. Once every line has been consumed, reading returns
+// an error.
+pub struct FileSource {
+    path: String,
+    lines: Lines<BufReader<File>>,
+}
+
+impl FileSource {
+    pub fn open(path: &str) -> Result<Self, Error> {
+        let file: File = File::open(path)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            lines: BufReader::new(file).lines(),
+        })
+    }
+}
+
+impl MetricSource for FileSource {
+    fn read(&mut self, metric_type: &str) -> Result<f64, Error> {
+        for line in self.lines.by_ref() {
+            let line: String = line?;
+            let line: &str = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let value: f64 = line.parse::<f64>().map_err(|err| {
+                Error::Validation(format!(
+                    "{} reading '{}' in {}: {}",
+                    metric_type, line, self.path, err
+                ))
+            })?;
+
+            return Ok(value);
+        }
+
+        Err(Error::Anyhow(anyhow::Error::msg(format!(
+            "No more {} readings in {}", metric_type, self.path
+        ))))
+    }
+}
+
+// The latest reading pushed by an external feed, such as an MQTT
+// subscription. Clones share the reading, so the feed updates it in the
+// background while the source of the metric reads it.
+#[derive(Clone, Default)]
+pub struct LatestReading {
+    latest: Arc<Mutex<Option<(f64, Instant)>>>,
+}
+
+impl LatestReading {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&self, value: f64) {
+        self.update_at(value, Instant::now());
+    }
+
+    pub fn update_at(&self, value: f64, received: Instant) {
+        *self.latest.lock().unwrap_or_else(|err| err.into_inner()) = Some((value, received));
+    }
+
+    // The last known reading, as of now. With a max age, a reading older than
+    // it is an error instead, so a silent sensor does not post the same stale
+    // value for the rest of the run.
+    pub fn read(&self, metric_type: &str, max_age: Option<Duration>) -> Result<f64, Error> {
+        self.read_at(metric_type, max_age, Instant::now())
+    }
+
+    pub fn read_at(
+        &self,
+        metric_type: &str,
+        max_age: Option<Duration>,
+        now: Instant
+    ) -> Result<f64, Error> {
+        let latest: Option<(f64, Instant)> =
+            *self.latest.lock().unwrap_or_else(|err| err.into_inner());
+
+        match (latest, max_age) {
+            (None, _) => Err(Error::Anyhow(anyhow::Error::msg(format!(
+                "No {} reading received yet", metric_type
+            )))),
+            (Some((_value, received)), Some(max_age))
+                if now.saturating_duration_since(received) > max_age => {
+                Err(Error::Anyhow(anyhow::Error::msg(format!(
+                    "The latest {} reading is {} seconds old, more than the allowed {}",
+                    metric_type,
+                    now.saturating_duration_since(received).as_secs(),
+                    max_age.as_secs()
+                ))))
+            },
+            (Some((value, _received)), _) => Ok(value),
+        }
+    }
+}
+
+// Parse the payload of a pushed reading: a number as text, e.g. 21.5.
+pub fn parse_reading_payload(payload: &[u8]) -> Result<f64, Error> {
+    let text: &str = std::str::from_utf8(payload)
+        .map_err(|err| Error::Validation(format!("reading payload: {}", err)))?
+        .trim();
+
+    let value: f64 = text
+        .parse::<f64>()
+        .map_err(|err| Error::Validation(format!("reading '{}': {}", text, err)))?;
+
+    if !value.is_finite() {
+        return Err(Error::Validation(format!("reading '{}': must be finite", text)));
+    }
+
+    Ok(value)
+}
+
+// Round a reading to the given number of decimals, keeping it within
+// [min, max]. A bound with more decimals than that would otherwise round to a
+// reading past it.
+pub fn round_reading(value: f64, decimals: u32, min: f64, max: f64) -> f64 {
+    let scale: f64 = 10_f64.powi(decimals as i32);
+
+    ((value * scale).round() / scale).clamp(min, max)
+}
+
+pub fn gen_random_number(min: f64, max: f64, decimals: u32) -> Result<f64, Error>{
+    gen_random_number_with(&mut rand::thread_rng(), min, max, decimals)
+}
+
+// Generate a random number within [min, max] with the given generator, rounded
+// to the given number of decimals. The bounds must be finite, and min must not
+// exceed max.
+pub fn gen_random_number_with<R: Rng>(
+    rng: &mut R,
+    min: f64,
+    max: f64,
+    decimals: u32
+) -> Result<f64, Error>{
+    if !min.is_finite() || !max.is_finite() {
+        return Err(Error::Validation(format!(
+            "range [{}, {}]: the bounds must be finite", min, max
+        )));
+    }
+    if min > max {
+        return Err(Error::Validation(format!(
+            "range [{}, {}]: min must not exceed max", min, max
+        )));
+    }
+
+    let random_number: f64 = rng.gen::<f64>();
+
+    // Specify range
+    let number: f64 = min + (max - min) * random_number;
+
+    Ok(round_reading(number, decimals, min, max))
+}
+
+// Move a random walk one step from the previous value, by a uniform delta of
+// at most max_delta either way, clamped to [min, max] and rounded to the given
+// number of decimals.
+pub fn next_walk_value<R: Rng>(
+    prev: f64,
+    max_delta: f64,
+    min: f64,
+    max: f64,
+    decimals: u32,
+    rng: &mut R
+) -> f64 {
+    let delta: f64 = if max_delta > 0.0 {
+        rng.gen_range(-max_delta..=max_delta)
+    } else {
+        0.0
+    };
+
+    round_reading(prev + delta, decimals, min, max)
+}
@@ -0,0 +1,42 @@
+// Rust module with the in-run store of posted metric readings.
+// The store is shared between the metric loop, which pushes every posted
+// reading, and the HTTP server, which serves them while the run is live.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::block_payload::MetricData;
+
+#[derive(Debug, Clone, Default)]
+pub struct MetricStore {
+    readings: Arc<Mutex<Vec<MetricData>>>,
+}
+
+impl MetricStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A reading is only ever pushed whole, so the readings are still
+    // consistent if a holder of the lock panicked.
+    fn lock(&self) -> MutexGuard<'_, Vec<MetricData>> {
+        self.readings.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub fn push(&self, metric_data: MetricData) {
+        self.lock().push(metric_data);
+    }
+
+    pub fn extend(&self, readings: Vec<MetricData>) {
+        self.lock().extend(readings);
+    }
+
+    // The most recently posted reading, if any.
+    pub fn latest(&self) -> Option<MetricData> {
+        self.lock().last().cloned()
+    }
+
+    // Every reading posted so far, in posting order.
+    pub fn all(&self) -> Vec<MetricData> {
+        self.lock().clone()
+    }
+}
@@ -0,0 +1,119 @@
+// Rust module to collect the metrics as a stream, for embedding the board in
+// a larger async application. The stream drives the sampling of every metric
+// chain itself and yields each reading once it is posted, so the application
+// forwards them to its own sinks instead of running the shipment loop of the
+// binary. The location chain, transportation blocks and deliveries are left
+// to the application.
+
+use futures::stream::{self, Stream};
+use iota_sdk::types::block::BlockId;
+use std::time::{Duration, Instant};
+
+use crate::{
+    block_payload::MetricData,
+    block_store::BlockStore,
+    check_run_tags,
+    config::AppConfig,
+    custom_error::Error,
+    custom_metric_specs, humidity_spec, metric_sampling_interval, metric_source_from_env,
+    metric_source::{BoardRng, MetricSource},
+    post_metric, pressure_spec, shock_spec, temperature_spec, MetricSpec,
+};
+
+// A metric chain sampled by the stream.
+struct StreamChain {
+    spec: MetricSpec,
+    source: Box<dyn MetricSource>,
+    interval: Duration,
+    previous_block: BlockId,
+    next_reading: Instant,
+}
+
+struct StreamState<'a> {
+    client: &'a dyn BlockStore,
+    chains: Vec<StreamChain>,
+    deadline: Instant,
+}
+
+// The built-in metrics followed by the metrics defined in the configuration,
+// the same ones a run posts.
+fn stream_specs(config: &AppConfig) -> Result<Vec<MetricSpec>, Error> {
+    let mut specs: Vec<MetricSpec> =
+        vec![temperature_spec()?, humidity_spec()?, pressure_spec()?, shock_spec()?];
+    let custom_specs: Vec<MetricSpec> =
+        custom_metric_specs(config.custom_metrics.as_deref().unwrap_or(&[]), &specs)?;
+    specs.extend(custom_specs);
+    check_run_tags(&specs)?;
+
+    Ok(specs)
+}
+
+// Stream the readings of every metric chain, chained from the given start
+// block, as they are posted. Each chain is sampled at its own interval until
+// the total duration of the configuration has passed, when the stream ends.
+// The readings are posted one at a time, the chain due first going first. A
+// failed post is yielded as an error and the stream carries on, with the head
+// of that chain unchanged. Dropping the stream stops the sampling.
+pub fn metric_stream<'a>(
+    client: &'a dyn BlockStore,
+    config: &AppConfig,
+    start_block: BlockId
+) -> Result<impl Stream<Item = Result<(BlockId, MetricData), Error>> + 'a, Error> {
+    let started: Instant = Instant::now();
+
+    // Seeded the way a run seeds them, so a seeded stream yields the readings
+    // of a run with the same seed.
+    let seed: Option<u64> = config.random_seed;
+
+    let mut chains: Vec<StreamChain> = Vec::new();
+    for (index, spec) in (0u64..).zip(stream_specs(config)?) {
+        let stream: u64 = if index < 2 { index } else { index + 1 };
+
+        let source: Box<dyn MetricSource> = metric_source_from_env(
+            &spec, BoardRng::new(seed.map(|seed| seed.wrapping_add(stream)))
+        )?;
+        let interval: Duration =
+            metric_sampling_interval(&spec)?.unwrap_or(config.sampling_interval());
+
+        chains.push(StreamChain {
+            spec,
+            source,
+            interval,
+            previous_block: start_block,
+            next_reading: started,
+        });
+    }
+
+    let state: StreamState<'a> = StreamState {
+        client,
+        chains,
+        deadline: started + config.total_duration(),
+    };
+
+    Ok(stream::unfold(state, |mut state| async move {
+        let chain: &mut StreamChain = state.chains
+            .iter_mut()
+            .min_by_key(|chain| chain.next_reading)?;
+        if chain.next_reading >= state.deadline {
+            return None;
+        }
+
+        tokio::time::sleep_until(tokio::time::Instant::from_std(chain.next_reading)).await;
+
+        let result: Result<(BlockId, MetricData), Error> = post_metric(
+            state.client,
+            &chain.spec,
+            chain.source.as_mut(),
+            &chain.previous_block.to_string()
+        ).await;
+        if let Ok((block_id, _metric_data)) = &result {
+            chain.previous_block = *block_id;
+        }
+
+        // A chain that fell behind, or has no interval, is not due again
+        // before the others had their turn.
+        chain.next_reading = (chain.next_reading + chain.interval).max(Instant::now());
+
+        Some((result, state))
+    }))
+}
@@ -0,0 +1,116 @@
+// Rust module to take metric readings from sensors publishing to an MQTT
+// broker. Every metric subscribes to a topic of its own, and each read returns
+// the latest value received on it, so the board posts whatever the sensors
+// report at its own sampling interval.
+
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, QoS};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::{
+    custom_error::Error,
+    metric_source::{parse_reading_payload, LatestReading, MetricSource},
+};
+
+const DEFAULT_MQTT_PORT: u16 = 1883;
+// Pause before polling again after a connection error, so an unreachable
+// broker is not retried in a busy loop.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+// Split a broker URL such as mqtt://broker.local:1883 into its host and port.
+pub fn parse_broker_url(url: &str) -> Result<(String, u16), Error> {
+    let address: &str = url
+        .trim()
+        .trim_start_matches("mqtt://")
+        .trim_start_matches("tcp://")
+        .trim_end_matches('/');
+
+    let (host, port): (&str, u16) = match address.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse::<u16>().map_err(|err| {
+            Error::Validation(format!("MQTT_BROKER_URL port '{}': {}", port, err))
+        })?),
+        None => (address, DEFAULT_MQTT_PORT),
+    };
+
+    if host.is_empty() {
+        return Err(Error::Validation(format!("MQTT_BROKER_URL '{}': no host", url)));
+    }
+
+    Ok((host.to_owned(), port))
+}
+
+// Readings of a metric received on an MQTT topic.
+pub struct MqttSource {
+    latest: LatestReading,
+    max_age: Option<Duration>,
+    subscription: JoinHandle<()>,
+}
+
+impl MqttSource {
+    // Connect to the broker and subscribe to the topic in the background. The
+    // subscription is renewed whenever the connection is established again.
+    pub fn connect(
+        broker_url: &str,
+        topic: &str,
+        max_age: Option<Duration>
+    ) -> Result<Self, Error> {
+        let (host, port): (String, u16) = parse_broker_url(broker_url)?;
+        let client_id: String = format!(
+            "metrics-board-{}-{}", topic.replace('/', "-"), rand::random::<u32>()
+        );
+
+        let mut options: MqttOptions = MqttOptions::new(client_id, host, port);
+        options.set_keep_alive(Duration::from_secs(30));
+
+        let (client, eventloop): (AsyncClient, EventLoop) = AsyncClient::new(options, 10);
+        let latest: LatestReading = LatestReading::new();
+
+        let subscription: JoinHandle<()> = tokio::spawn(subscribe(
+            client, eventloop, topic.to_owned(), latest.clone()
+        ));
+
+        Ok(Self { latest, max_age, subscription })
+    }
+}
+
+async fn subscribe(
+    client: AsyncClient,
+    mut eventloop: EventLoop,
+    topic: String,
+    latest: LatestReading
+) {
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                debug!("Connected to the MQTT broker, subscribing to {}", topic);
+                if let Err(err) = client.subscribe(topic.as_str(), QoS::AtMostOnce).await {
+                    warn!("Failed to subscribe to {}: {}", topic, err);
+                }
+            },
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                match parse_reading_payload(&publish.payload) {
+                    Ok(value) => latest.update(value),
+                    Err(err) => warn!("Ignoring a reading on {}: {:#}", topic, err),
+                };
+            },
+            Ok(_event) => {},
+            Err(err) => {
+                warn!("MQTT connection error on {}: {}", topic, err);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            },
+        };
+    }
+}
+
+impl MetricSource for MqttSource {
+    fn read(&mut self, metric_type: &str) -> Result<f64, Error> {
+        self.latest.read(metric_type, self.max_age)
+    }
+}
+
+impl Drop for MqttSource {
+    fn drop(&mut self) {
+        self.subscription.abort();
+    }
+}
@@ -0,0 +1,180 @@
+// Rust module with the presets of the networks the board can post to.
+// Selecting a network sets both the node and the explorer URLs, so blocks are
+// never posted to one network while the explorer links point to another.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use tracing::{info, warn};
+
+use crate::{
+    config::DEFAULT_EXPLORER_BLOCK_TEMPLATE,
+    custom_error::Error,
+    parse_node_urls, read_env_parsed, read_env_var,
+};
+
+// Placeholders of an explorer block link template.
+const BASE_PLACEHOLDER: &str = "{base}";
+const BLOCK_ID_PLACEHOLDER: &str = "{block_id}";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Network {
+    Mainnet,
+    Shimmer,
+    ShimmerTestnet,
+}
+
+impl Network {
+    pub fn node_url(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "https://api.stardust-mainnet.iotaledger.net",
+            Network::Shimmer => "https://api.shimmer.network",
+            Network::ShimmerTestnet => "https://api.testnet.shimmer.network",
+        }
+    }
+
+    pub fn explorer_url(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "https://explorer.iota.org/mainnet",
+            Network::Shimmer => "https://explorer.shimmer.network/shimmer",
+            Network::ShimmerTestnet => "https://explorer.shimmer.network/testnet",
+        }
+    }
+
+    // Human-readable part of the bech32 addresses of the network.
+    pub fn bech32_hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "iota",
+            Network::Shimmer => "smr",
+            Network::ShimmerTestnet => "rms",
+        }
+    }
+
+    // Read the selected network from the NETWORK environment variable.
+    pub fn from_env() -> Result<Option<Network>, Error> {
+        read_env_parsed::<Network>("NETWORK")
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "mainnet" | "iota" => Ok(Network::Mainnet),
+            "shimmer" => Ok(Network::Shimmer),
+            "shimmer-testnet" | "testnet" => Ok(Network::ShimmerTestnet),
+            _ => Err(String::from(
+                "unknown network, expected one of mainnet, shimmer, shimmer-testnet"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Shimmer => write!(f, "shimmer"),
+            Network::ShimmerTestnet => write!(f, "shimmer-testnet"),
+        }
+    }
+}
+
+// Resolve the URLs of the nodes to post to. NODE_URLS (or NODE_URL) overrides
+// the node of the selected network.
+pub fn resolve_node_urls() -> Result<Vec<String>, Error> {
+    let node_urls: String = match read_env_var("NODE_URLS".to_string())
+        .or_else(|_err| read_env_var("NODE_URL".to_string()))
+    {
+        Ok(value) => value,
+        Err(_err) => match Network::from_env()? {
+            Some(network) => network.node_url().to_owned(),
+            None => return Err(Error::Anyhow(anyhow::Error::msg(
+                "No node configured, set NETWORK or NODE_URLS/NODE_URL"
+            ))),
+        },
+    };
+
+    Ok(parse_node_urls(&node_urls))
+}
+
+// Resolve the base URL of the explorer. EXPLORER_URL overrides the explorer of
+// the selected network. None if neither is set.
+pub fn resolve_explorer_url() -> Result<Option<String>, Error> {
+    match read_env_var("EXPLORER_URL".to_string()) {
+        Ok(value) => Ok(Some(value)),
+        Err(_err) => Ok(Network::from_env()?.map(|network| network.explorer_url().to_owned())),
+    }
+}
+
+// Check that the explorer URL is an http(s) URL with a host.
+pub fn check_explorer_url(explorer_url: &str) -> Result<(), Error> {
+    let host: &str = explorer_url
+        .strip_prefix("https://")
+        .or_else(|| explorer_url.strip_prefix("http://"))
+        .ok_or_else(|| Error::Anyhow(anyhow::Error::msg(format!(
+            "{} is not an http(s) URL", explorer_url
+        ))))?;
+
+    if host.split('/').next().unwrap_or("").is_empty() {
+        return Err(Error::Anyhow(anyhow::Error::msg(format!(
+            "{} has no host", explorer_url
+        ))));
+    }
+
+    Ok(())
+}
+
+// The base URL of the explorer block links are printed with, if any. The
+// links are only informational, so a missing or malformed explorer URL is
+// noted and blocks are posted without links instead of failing the run.
+pub fn read_explorer_url() -> Result<Option<String>, Error> {
+    let explorer_url: String = match resolve_explorer_url()? {
+        Some(explorer_url) => explorer_url,
+        None => {
+            info!("No explorer configured, blocks are posted without explorer links");
+            return Ok(None);
+        },
+    };
+
+    match check_explorer_url(&explorer_url) {
+        Ok(()) => Ok(Some(explorer_url)),
+        Err(err) => {
+            warn!("Invalid explorer URL, blocks are posted without explorer links: {:#}", err);
+            Ok(None)
+        },
+    }
+}
+
+// Check that a template of the explorer block links holds the {block_id}
+// placeholder, without which every link would point to the same page.
+pub fn parse_explorer_block_template(template: &str) -> Result<String, Error> {
+    if !template.contains(BLOCK_ID_PLACEHOLDER) {
+        return Err(Error::Validation(format!(
+            "EXPLORER_BLOCK_TEMPLATE: '{}' must contain the {} placeholder",
+            template, BLOCK_ID_PLACEHOLDER
+        )));
+    }
+
+    Ok(template.trim().to_owned())
+}
+
+// Resolve the template of the explorer block links from
+// EXPLORER_BLOCK_TEMPLATE, e.g. {base}?block={block_id} for an explorer taking
+// the block id as a query parameter.
+pub fn resolve_explorer_block_template() -> Result<String, Error> {
+    match read_env_var("EXPLORER_BLOCK_TEMPLATE".to_string()) {
+        Ok(value) => parse_explorer_block_template(&value),
+        Err(_err) => Ok(String::from(DEFAULT_EXPLORER_BLOCK_TEMPLATE)),
+    }
+}
+
+// Link of a block on the explorer: the template with {base} replaced by the
+// base URL of the explorer, without its trailing slash, and {block_id} by the
+// block id.
+pub fn explorer_block_link(template: &str, explorer_url: &str, block_id: &str) -> String {
+    template
+        .replace(BASE_PLACEHOLDER, explorer_url.trim_end_matches('/'))
+        .replace(BLOCK_ID_PLACEHOLDER, block_id)
+}
@@ -0,0 +1,117 @@
+// Rust module to report the health and network of the node the board posts to.
+// A desynced node or one of another network than the selected one is caught
+// before any block of a run is posted.
+
+use chrono::Utc;
+use iota_sdk::types::api::core::response::InfoResponse;
+use tracing::{info, warn};
+
+use crate::{custom_error::Error, network::Network, tangle_client::TangleClient};
+
+// A latest milestone older than this means the node is not keeping up.
+const MAX_MILESTONE_AGE_SECS: i64 = 60;
+
+// Describe what is wrong with a node: being unhealthy, lagging behind the
+// milestones, or belonging to another network than the expected one.
+pub fn node_warnings(
+    is_healthy: bool,
+    latest_milestone_timestamp: Option<u32>,
+    bech32_hrp: &str,
+    expected_network: Option<Network>,
+    now_secs: i64
+) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+
+    if !is_healthy {
+        warnings.push(String::from("the node reports itself as unhealthy"));
+    }
+
+    if let Some(timestamp) = latest_milestone_timestamp {
+        let age_secs: i64 = now_secs - i64::from(timestamp);
+        if age_secs > MAX_MILESTONE_AGE_SECS {
+            warnings.push(format!(
+                "the latest milestone is {} seconds old, the node may be desynced", age_secs
+            ));
+        }
+    }
+
+    if let Some(network) = expected_network {
+        if bech32_hrp != network.bech32_hrp() {
+            warnings.push(format!(
+                "the node is on a network with address prefix {}, not {}",
+                bech32_hrp, network
+            ));
+        }
+    }
+
+    warnings
+}
+
+fn info_warnings(info: &InfoResponse) -> Result<Vec<String>, Error> {
+    Ok(node_warnings(
+        info.status.is_healthy,
+        info.status.latest_milestone.timestamp,
+        &info.protocol.bech32_hrp().to_string(),
+        Network::from_env()?,
+        Utc::now().timestamp()
+    ))
+}
+
+// Print the node info: its software, health, milestones and the protocol
+// parameters of its network, followed by anything wrong with it.
+pub async fn print_node_info(client: &TangleClient) -> Result<(), Error> {
+    let info: InfoResponse = client.node_info().await?;
+
+    println!("Node: {} {}", info.name, info.version);
+    println!("Healthy: {}", info.status.is_healthy);
+    println!("Network: {}", info.protocol.network_name());
+    println!("Address prefix: {}", info.protocol.bech32_hrp());
+    println!(
+        "Latest milestone: {} at {}",
+        info.status.latest_milestone.index,
+        info.status.latest_milestone.timestamp
+            .map(|timestamp| timestamp.to_string())
+            .unwrap_or_else(|| String::from("unknown"))
+    );
+    println!("Confirmed milestone: {}", info.status.confirmed_milestone.index);
+    println!("Protocol version: {}", info.protocol.protocol_version());
+    println!("Minimum PoW score: {}", info.protocol.min_pow_score());
+    println!("Below max depth: {}", info.protocol.below_max_depth());
+    println!("Token supply: {}", info.protocol.token_supply());
+
+    for warning in info_warnings(&info)? {
+        println!("Warning: {}", warning);
+    }
+
+    Ok(())
+}
+
+// Log the network of the node at the start of a run, and warn about anything
+// wrong with it. A node whose info cannot be read is only warned about, since
+// posting may still work.
+pub async fn check_node_health(client: &TangleClient) {
+    let info: InfoResponse = match client.node_info().await {
+        Ok(info) => info,
+        Err(err) => {
+            warn!("Failed to read the node info: {:#}", err);
+            return;
+        },
+    };
+
+    info!(
+        "Posting to {} {} on network {}, latest milestone {}",
+        info.name,
+        info.version,
+        info.protocol.network_name(),
+        info.status.latest_milestone.index
+    );
+
+    match info_warnings(&info) {
+        Ok(warnings) => {
+            for warning in warnings {
+                warn!("Node check: {}", warning);
+            }
+        },
+        Err(err) => warn!("Failed to check the node: {:#}", err),
+    };
+}
@@ -0,0 +1,119 @@
+// Rust module with the structured output of the board. With --json, the
+// result of every post, inspect, validate, selftest and run is printed to
+// stdout as one JSON object per line instead of prose, so the output can be
+// piped into jq, and the logs go to stderr.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::{
+    chain_verify::VerifyReport, custom_error::Error, receipt::ShipmentReceipt,
+    signing::PayloadSignature,
+};
+
+// A result printed in JSON mode. The operation that produced it is named in
+// its operation field.
+#[derive(Serialize, Debug)]
+#[serde(tag = "operation", rename_all = "camelCase")]
+pub enum JsonOutput<'a> {
+    Post(&'a PostedBlock),
+    #[serde(rename_all = "camelCase")]
+    DryRun { tag: String, digest: String, data: serde_json::Value },
+    Inspect(&'a InspectedBlock),
+    Validate(&'a PreflightReport),
+    Receipt(&'a ShipmentReceipt),
+    VerifyChain(&'a VerifyReport),
+    Selftest(&'a SelftestReport),
+    #[serde(rename_all = "camelCase")]
+    Query { tag: String, block_ids: Vec<String> },
+}
+
+impl JsonOutput<'_> {
+    // Print the result on a line of its own.
+    pub fn print(&self) -> Result<(), Error> {
+        println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+}
+
+// A block posted to the node.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PostedBlock {
+    pub block_id: String,
+    pub tag: String,
+    pub digest: String,
+    // Time spent building and posting the block, PoW included.
+    pub elapsed_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
+}
+
+// The decoded payload of an inspected block. Supply chain blocks come with
+// their block type and schema version, data that is not block data with its
+// raw UTF-8 instead.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectedBlock {
+    pub block_id: String,
+    pub tag: String,
+    pub digest: String,
+    // The binary format the data was posted in, if not JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_data: Option<String>,
+    // The metadata the block was annotated with, sorted by key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, String>>,
+    // Why the data fails validation, if it does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation_error: Option<String>,
+    pub payment_info_encrypted: bool,
+    // The encrypted payment info of the data, decrypted with ENCRYPTION_KEY.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decrypted_payment_info: Option<serde_json::Value>,
+    // The embedded signature of the payload, if it is signed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PayloadSignature>,
+}
+
+// The outcome of a preflight check: ok, FAIL or skip, and why it failed.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckOutcome {
+    pub name: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+// The outcome of the self-test: whether the block read back matched the one
+// posted, and why not otherwise. The block id and the posting time are only
+// known once the block is posted.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SelftestReport {
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    // Time spent building and posting the block, PoW included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub posting_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+// The outcomes of the preflight checks, in the order they ran.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub passed: bool,
+    pub checks: Vec<CheckOutcome>,
+}
@@ -0,0 +1,211 @@
+// Rust module with builders of the block data that carries several String
+// fields. Each field is set by name, so two of them can not be swapped the way
+// positional arguments of new() can, and build() fails naming the first
+// required field that was never set, or on a previous block that is not a
+// valid block id.
+
+use std::collections::HashMap;
+
+use crate::{
+    block_payload::{
+        BlockRef, DeliveredTransportationData, MetricData, PostedPaymentInfo, ProductInfo,
+        StartTransportationData, TransportationLeg,
+    },
+    custom_error::Error,
+};
+
+fn required<T>(value: Option<T>, field: &str) -> Result<T, Error> {
+    value.ok_or_else(|| Error::Validation(format!("{}: must be set", field)))
+}
+
+#[derive(Default)]
+pub struct StartTransportationBuilder {
+    transportation_company_info: Option<String>,
+    transportation_info: Option<ProductInfo>,
+    start_timestamp: Option<String>,
+    previous_block: Option<String>,
+    metadata: Option<HashMap<String, String>>,
+}
+
+impl StartTransportationData {
+    pub fn builder() -> StartTransportationBuilder {
+        StartTransportationBuilder::default()
+    }
+}
+
+impl StartTransportationBuilder {
+    pub fn company_info(mut self, transportation_company_info: impl Into<String>) -> Self {
+        self.transportation_company_info = Some(transportation_company_info.into());
+        self
+    }
+
+    pub fn product_info(mut self, transportation_info: ProductInfo) -> Self {
+        self.transportation_info = Some(transportation_info);
+        self
+    }
+
+    pub fn start_timestamp(mut self, start_timestamp: impl Into<String>) -> Self {
+        self.start_timestamp = Some(start_timestamp.into());
+        self
+    }
+
+    pub fn previous_block(mut self, previous_block: impl Into<String>) -> Self {
+        self.previous_block = Some(previous_block.into());
+        self
+    }
+
+    pub fn metadata(mut self, metadata: Option<HashMap<String, String>>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn build(self) -> Result<StartTransportationData, Error> {
+        let mut data: StartTransportationData = StartTransportationData::new(
+            required(self.transportation_company_info, "transportationCompanyInfo")?,
+            required(self.transportation_info, "transportationInfo")?,
+            required(self.start_timestamp, "startTimestamp")?,
+            BlockRef::new(required(self.previous_block, "previousBlock")?)?,
+        );
+        data.metadata = self.metadata;
+
+        Ok(data)
+    }
+}
+
+#[derive(Default)]
+pub struct DeliveredTransportationBuilder {
+    product_delivery_info: Option<ProductInfo>,
+    delivery_timestamp: Option<String>,
+    payment_info: Option<PostedPaymentInfo>,
+    metrics: Option<Vec<String>>,
+    resolved_metrics: Option<Vec<MetricData>>,
+    next_leg_previous_block: Option<BlockRef>,
+    legs: Option<Vec<TransportationLeg>>,
+    metadata: Option<HashMap<String, String>>,
+}
+
+impl DeliveredTransportationData {
+    pub fn builder() -> DeliveredTransportationBuilder {
+        DeliveredTransportationBuilder::default()
+    }
+}
+
+impl DeliveredTransportationBuilder {
+    pub fn product_info(mut self, product_delivery_info: ProductInfo) -> Self {
+        self.product_delivery_info = Some(product_delivery_info);
+        self
+    }
+
+    pub fn delivery_timestamp(mut self, delivery_timestamp: impl Into<String>) -> Self {
+        self.delivery_timestamp = Some(delivery_timestamp.into());
+        self
+    }
+
+    pub fn payment_info(mut self, payment_info: PostedPaymentInfo) -> Self {
+        self.payment_info = Some(payment_info);
+        self
+    }
+
+    pub fn metrics(mut self, metrics: Vec<String>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    // The optional fields take an Option, so a value that may be missing can
+    // be passed on as it is.
+    pub fn resolved_metrics(mut self, resolved_metrics: Option<Vec<MetricData>>) -> Self {
+        self.resolved_metrics = resolved_metrics;
+        self
+    }
+
+    pub fn next_leg_previous_block(mut self, next_leg_previous_block: Option<BlockRef>) -> Self {
+        self.next_leg_previous_block = next_leg_previous_block;
+        self
+    }
+
+    pub fn legs(mut self, legs: Option<Vec<TransportationLeg>>) -> Self {
+        self.legs = legs;
+        self
+    }
+
+    pub fn metadata(mut self, metadata: Option<HashMap<String, String>>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn build(self) -> Result<DeliveredTransportationData, Error> {
+        let mut data: DeliveredTransportationData = DeliveredTransportationData::new(
+            required(self.product_delivery_info, "productDeliveryInfo")?,
+            required(self.delivery_timestamp, "deliveryTimestamp")?,
+            required(self.payment_info, "paymentInfo")?,
+            required(self.metrics, "metrics")?,
+        );
+        data.resolved_metrics = self.resolved_metrics;
+        data.next_leg_previous_block = self.next_leg_previous_block;
+        data.legs = self.legs;
+        data.metadata = self.metadata;
+
+        Ok(data)
+    }
+}
+
+#[derive(Default)]
+pub struct MetricDataBuilder {
+    metric_type: Option<String>,
+    metric_value: Option<f64>,
+    measurement_unit: Option<String>,
+    timestamp: Option<String>,
+    previous_block: Option<String>,
+    metadata: Option<HashMap<String, String>>,
+}
+
+impl MetricData {
+    pub fn builder() -> MetricDataBuilder {
+        MetricDataBuilder::default()
+    }
+}
+
+impl MetricDataBuilder {
+    pub fn metric_type(mut self, metric_type: impl Into<String>) -> Self {
+        self.metric_type = Some(metric_type.into());
+        self
+    }
+
+    pub fn metric_value(mut self, metric_value: f64) -> Self {
+        self.metric_value = Some(metric_value);
+        self
+    }
+
+    pub fn measurement_unit(mut self, measurement_unit: impl Into<String>) -> Self {
+        self.measurement_unit = Some(measurement_unit.into());
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: impl Into<String>) -> Self {
+        self.timestamp = Some(timestamp.into());
+        self
+    }
+
+    pub fn previous_block(mut self, previous_block: impl Into<String>) -> Self {
+        self.previous_block = Some(previous_block.into());
+        self
+    }
+
+    pub fn metadata(mut self, metadata: Option<HashMap<String, String>>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn build(self) -> Result<MetricData, Error> {
+        let mut data: MetricData = MetricData::new(
+            required(self.metric_type, "metricType")?,
+            required(self.metric_value, "metricValue")?,
+            required(self.measurement_unit, "measurementUnit")?,
+            required(self.timestamp, "timestamp")?,
+            BlockRef::new(required(self.previous_block, "previousBlock")?)?,
+        );
+        data.metadata = self.metadata;
+
+        Ok(data)
+    }
+}
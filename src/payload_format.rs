@@ -0,0 +1,112 @@
+// Rust module with the formats block data can be posted in. JSON is verbose,
+// which inflates the size and PoW of every block of dense telemetry, so with
+// PAYLOAD_FORMAT set to cbor or msgpack the data is posted in that binary
+// format instead. A binary payload starts with a zero byte, which no JSON
+// text can start with, followed by a byte naming its format, so readers tell
+// the formats apart without any setting and decode every payload back to JSON.
+
+use serde_json::Value;
+use std::{fmt, str::FromStr};
+
+use crate::{custom_error::Error, read_env_parsed};
+
+// First byte of a binary payload.
+const BINARY_MARKER: u8 = 0x00;
+
+const CBOR_MARKER: u8 = b'c';
+const MSGPACK_MARKER: u8 = b'm';
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadFormat {
+    #[default]
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl PayloadFormat {
+    // Read the format blocks are posted in from PAYLOAD_FORMAT.
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(read_env_parsed::<PayloadFormat>("PAYLOAD_FORMAT")?.unwrap_or_default())
+    }
+
+    // Encode JSON data in the format, marked as such.
+    pub fn encode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let (marker, encoded): (u8, Vec<u8>) = match self {
+            PayloadFormat::Json => return Ok(data.to_vec()),
+            PayloadFormat::Cbor => {
+                let value: Value = serde_json::from_slice(data)?;
+                let mut encoded: Vec<u8> = Vec::new();
+                ciborium::into_writer(&value, &mut encoded).map_err(|err| {
+                    Error::Validation(format!("payload: cannot be encoded as CBOR: {}", err))
+                })?;
+                (CBOR_MARKER, encoded)
+            },
+            PayloadFormat::MessagePack => {
+                let value: Value = serde_json::from_slice(data)?;
+                let encoded: Vec<u8> = rmp_serde::to_vec(&value).map_err(|err| {
+                    Error::Validation(format!(
+                        "payload: cannot be encoded as MessagePack: {}", err
+                    ))
+                })?;
+                (MSGPACK_MARKER, encoded)
+            },
+        };
+
+        let mut payload: Vec<u8> = vec![BINARY_MARKER, marker];
+        payload.extend(encoded);
+
+        Ok(payload)
+    }
+
+    // The format of a posted payload, told by its marker.
+    pub fn detect(payload: &[u8]) -> Result<Self, Error> {
+        match payload {
+            [BINARY_MARKER, CBOR_MARKER, ..] => Ok(PayloadFormat::Cbor),
+            [BINARY_MARKER, MSGPACK_MARKER, ..] => Ok(PayloadFormat::MessagePack),
+            [BINARY_MARKER, marker, ..] => Err(Error::Validation(format!(
+                "payload: unknown binary format {:#04x}", marker
+            ))),
+            _ => Ok(PayloadFormat::Json),
+        }
+    }
+}
+
+// Decode a posted payload back to JSON. Payloads without the binary marker,
+// JSON or not, are returned unchanged.
+pub fn decode_payload(payload: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let value: Value = match PayloadFormat::detect(&payload)? {
+        PayloadFormat::Json => return Ok(payload),
+        PayloadFormat::Cbor => ciborium::from_reader(&payload[2..]).map_err(|err| {
+            Error::Validation(format!("payload: not valid CBOR: {}", err))
+        })?,
+        PayloadFormat::MessagePack => rmp_serde::from_slice(&payload[2..]).map_err(|err| {
+            Error::Validation(format!("payload: not valid MessagePack: {}", err))
+        })?,
+    };
+
+    Ok(serde_json::to_vec(&value)?)
+}
+
+impl FromStr for PayloadFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "json" => Ok(PayloadFormat::Json),
+            "cbor" => Ok(PayloadFormat::Cbor),
+            "msgpack" | "messagepack" => Ok(PayloadFormat::MessagePack),
+            _ => Err(String::from("unknown payload format, expected one of json, cbor, msgpack")),
+        }
+    }
+}
+
+impl fmt::Display for PayloadFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayloadFormat::Json => write!(f, "json"),
+            PayloadFormat::Cbor => write!(f, "cbor"),
+            PayloadFormat::MessagePack => write!(f, "msgpack"),
+        }
+    }
+}
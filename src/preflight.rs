@@ -0,0 +1,199 @@
+// Rust module to check the setup of the board before a shipment run.
+// Every step a run depends on is tried without posting anything, so a
+// misconfigured setup is caught before any PoW is spent.
+
+use iota_sdk::types::block::BlockDto;
+use std::io::{self, IsTerminal};
+
+use crate::{
+    block_id_input, check_run_tags, custom_metric_specs, extract_payment_info, humidity_spec,
+    metric_source_from_env, pressure_spec, shock_spec, temperature_spec,
+    block_payload::PaymentInfo,
+    config::AppConfig,
+    custom_error::Error,
+    encryption::read_encryption_key,
+    metric_source::BoardRng,
+    network::{
+        check_explorer_url, resolve_explorer_block_template, resolve_explorer_url,
+        resolve_node_urls,
+    },
+    output::{CheckOutcome, PreflightReport},
+    signing::read_signing_key,
+    tangle_client::TangleClient,
+    validation::validate_delivery_payment_info,
+    MetricSpec,
+};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+// The checklist printed as the checks run, unless they are only reported as
+// JSON once done. Colors are only used when stdout is a terminal, so the
+// output stays readable when piped into a file.
+struct Checklist {
+    color: bool,
+    quiet: bool,
+    report: PreflightReport,
+}
+
+impl Checklist {
+    fn new(quiet: bool) -> Self {
+        Self {
+            color: io::stdout().is_terminal(),
+            quiet,
+            report: PreflightReport { passed: true, checks: Vec::new() },
+        }
+    }
+
+    // Print the outcome of a check and return its value if it passed.
+    fn check<T>(&mut self, name: &str, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.print_line(GREEN, "ok", name, None);
+                Some(value)
+            },
+            Err(err) => {
+                self.report.passed = false;
+                self.print_line(RED, "FAIL", name, Some(format!("{:#}", err)));
+                None
+            },
+        }
+    }
+
+    // Print a check that could not run because a check it depends on failed.
+    fn skip(&mut self, name: &str) {
+        self.report.passed = false;
+        self.print_line(RED, "skip", name, None);
+    }
+
+    fn print_line(&mut self, color: &str, status: &str, name: &str, detail: Option<String>) {
+        self.report.checks.push(CheckOutcome {
+            name: name.to_owned(),
+            status: status.to_owned(),
+            detail: detail.clone(),
+        });
+
+        if self.quiet {
+            return;
+        }
+
+        let status: String = if self.color {
+            format!("{}[{}]{}", color, status, RESET)
+        } else {
+            format!("[{}]", status)
+        };
+
+        match detail {
+            Some(detail) => println!("{} {}: {}", status, name, detail),
+            None => println!("{} {}", status, name),
+        };
+    }
+}
+
+// Resolve every metric of a run along with the source of its readings.
+fn check_metrics(config: &AppConfig) -> Result<(), Error> {
+    let mut specs: Vec<MetricSpec> = vec![
+        temperature_spec()?,
+        humidity_spec()?,
+        pressure_spec()?,
+        shock_spec()?,
+    ];
+    let custom_specs: Vec<MetricSpec> = custom_metric_specs(
+        config.custom_metrics.as_deref().unwrap_or(&[]), &specs
+    )?;
+    specs.extend(custom_specs);
+    check_run_tags(&specs)?;
+
+    for spec in specs.iter() {
+        metric_source_from_env(spec, BoardRng::new(None))?;
+    }
+
+    Ok(())
+}
+
+// Run the preflight checks and print a checklist of their outcomes, unless
+// quiet. Returns the report of whether every check passed. Nothing is posted,
+// even without DRY_RUN.
+pub async fn run_preflight(
+    cli_block_id: Option<String>,
+    config: &AppConfig,
+    quiet: bool
+) -> PreflightReport {
+    let mut checklist: Checklist = Checklist::new(quiet);
+
+    checklist.check("Node URLs resolve", resolve_node_urls());
+
+    // Blocks are posted without explorer links when no explorer is set, so
+    // only a configured explorer URL is checked.
+    if let Some(Some(explorer_url)) =
+        checklist.check("Explorer URL resolves", resolve_explorer_url())
+    {
+        checklist.check("Explorer URL is well-formed", check_explorer_url(&explorer_url));
+    }
+
+    checklist.check("Explorer block template is valid", resolve_explorer_block_template());
+
+    checklist.check("Metrics and their sources resolve", check_metrics(config));
+
+    checklist.check("Encryption key, if set, is valid", read_encryption_key());
+
+    checklist.check("Signing key, if set, is valid", read_signing_key());
+
+    let block_id: Option<String> =
+        checklist.check("Initial block id is valid", block_id_input(cli_block_id));
+
+    let client: Option<TangleClient> =
+        checklist.check("IOTA client is created", TangleClient::new().await);
+
+    // Configured payment info replaces the one of the initial block, which is
+    // then never fetched.
+    let configured_payment_info: Option<PaymentInfo> = checklist
+        .check("Configured payment info, if set, is complete", config.payment_info())
+        .flatten();
+    if let Some(payment_info) = configured_payment_info {
+        checklist.skip("Initial block is fetched from the node");
+        checklist.skip("Initial block is a supply chain block with payment info");
+        checklist.check(
+            "Payment wallet address is valid",
+            validate_delivery_payment_info(&payment_info)
+        );
+        return checklist.report;
+    }
+
+    let block: Option<BlockDto> = match (&client, &block_id) {
+        (Some(client), Some(block_id)) => checklist.check(
+            "Initial block is fetched from the node",
+            client.get_block(block_id).await
+        ),
+        _ => {
+            checklist.skip("Initial block is fetched from the node");
+            None
+        },
+    };
+
+    match (block, &block_id) {
+        (Some(block), Some(block_id)) => {
+            let payment_info: Option<PaymentInfo> = checklist.check(
+                "Initial block is a supply chain block with payment info",
+                extract_payment_info(block_id, block)
+            );
+
+            match payment_info {
+                Some(payment_info) => {
+                    checklist.check(
+                        "Payment wallet address is valid",
+                        validate_delivery_payment_info(&payment_info)
+                    );
+                },
+                None => checklist.skip("Payment wallet address is valid"),
+            };
+        },
+        _ => {
+            checklist.skip("Initial block is a supply chain block with payment info");
+            checklist.skip("Payment wallet address is valid");
+        },
+    };
+
+    checklist.report
+}
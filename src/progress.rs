@@ -0,0 +1,287 @@
+// Rust module to report the progress of a shipment run while it lasts: the
+// elapsed part of the current leg, the blocks posted so far and the head of
+// every chain. On a terminal the status line is redrawn in place every second.
+// Otherwise it is printed as a plain line every PROGRESS_INTERVAL_SECS.
+// With a checkpoint set, the progress is also saved to it after every block.
+// With a block cap set, the chains stop sampling once it is reached.
+
+use iota_sdk::types::block::BlockId;
+use std::{
+    collections::BTreeMap,
+    io::{self, IsTerminal, Write},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::checkpoint::RunCheckpoint;
+
+// Characters of a chain head shown in the status line, 0x included.
+const HEAD_PREFIX_LENGTH: usize = 10;
+const BAR_WIDTH: usize = 20;
+
+struct ProgressState {
+    leg: usize,
+    leg_started: Instant,
+    // Time the leg was sampled for by the run it was resumed from.
+    resumed_elapsed: Duration,
+    blocks_posted: usize,
+    heads: BTreeMap<String, BlockId>,
+    // The sampling cycles every chain took in the leg.
+    cycles: BTreeMap<String, usize>,
+    checkpoint: Option<(String, RunCheckpoint)>,
+}
+
+impl ProgressState {
+    fn elapsed(&self) -> Duration {
+        self.resumed_elapsed + self.leg_started.elapsed()
+    }
+}
+
+// The progress of a run, shared by every chain posting blocks and the
+// reporter printing it.
+#[derive(Clone)]
+pub struct ShipmentProgress {
+    state: Arc<Mutex<ProgressState>>,
+    total_duration: Duration,
+    legs: usize,
+    max_blocks: Option<usize>,
+}
+
+impl ShipmentProgress {
+    pub fn new(total_duration: Duration, legs: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ProgressState {
+                leg: 1,
+                leg_started: Instant::now(),
+                resumed_elapsed: Duration::ZERO,
+                blocks_posted: 0,
+                heads: BTreeMap::new(),
+                cycles: BTreeMap::new(),
+                checkpoint: None,
+            })),
+            total_duration,
+            legs,
+            max_blocks: None,
+        }
+    }
+
+    // Stop sampling once the given number of chain blocks was posted.
+    pub fn with_max_blocks(mut self, max_blocks: Option<usize>) -> Self {
+        self.max_blocks = max_blocks;
+        self
+    }
+
+    // Whether the run posted as many chain blocks as it may. The chains post
+    // concurrently, so the cap can be exceeded by the blocks already in flight.
+    pub fn block_cap_reached(&self) -> bool {
+        match self.max_blocks {
+            Some(max_blocks) => self.lock_state().blocks_posted >= max_blocks,
+            None => false,
+        }
+    }
+
+    // The state is only ever updated whole, so it is still consistent if a
+    // holder of the lock panicked.
+    fn lock_state(&self) -> MutexGuard<'_, ProgressState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Start timing the given leg, counted from 1. Its chains start over from
+    // its start block, so the heads and cycles of the previous leg are dropped.
+    pub fn start_leg(&self, leg: usize) {
+        let mut state: MutexGuard<'_, ProgressState> = self.lock_state();
+        state.leg = leg;
+        state.leg_started = Instant::now();
+        state.resumed_elapsed = Duration::ZERO;
+        state.heads.clear();
+        state.cycles.clear();
+    }
+
+    // Continue timing a leg a previous run had sampled for the given time,
+    // from the chain heads and the cycles it had reached.
+    pub fn resume_leg(
+        &self,
+        leg: usize,
+        elapsed: Duration,
+        heads: BTreeMap<String, BlockId>,
+        cycles: BTreeMap<String, usize>
+    ) {
+        let mut state: MutexGuard<'_, ProgressState> = self.lock_state();
+        state.leg = leg;
+        state.leg_started = Instant::now();
+        state.resumed_elapsed = elapsed;
+        state.heads = heads;
+        state.cycles = cycles;
+    }
+
+    // The sampling cycles the given chain took in the leg.
+    pub fn cycles(&self, chain: &str) -> usize {
+        self.lock_state().cycles.get(chain).copied().unwrap_or(0)
+    }
+
+    // Count a sampling cycle the given chain took.
+    pub fn record_cycle(&self, chain: &str) {
+        let mut state: MutexGuard<'_, ProgressState> = self.lock_state();
+        *state.cycles.entry(chain.to_owned()).or_insert(0) += 1;
+        save_checkpoint(&mut state);
+    }
+
+    // Save the progress of the leg in progress to the given checkpoint, now
+    // and after every posted block.
+    pub fn checkpoint_to(&self, path: &str, checkpoint: RunCheckpoint) {
+        let mut state: MutexGuard<'_, ProgressState> = self.lock_state();
+        state.checkpoint = Some((path.to_owned(), checkpoint));
+        save_checkpoint(&mut state);
+    }
+
+    // Count a posted block and advance the head of its chain.
+    pub fn record_block(&self, chain: &str, block_id: BlockId) {
+        let mut state: MutexGuard<'_, ProgressState> = self.lock_state();
+        state.blocks_posted += 1;
+        state.heads.insert(chain.to_owned(), block_id);
+        if self.max_blocks == Some(state.blocks_posted) {
+            warn!(
+                "Posted MAX_BLOCKS={} chain blocks, sampling stops and the shipment is delivered",
+                state.blocks_posted
+            );
+        }
+        save_checkpoint(&mut state);
+    }
+
+    pub fn status_line(&self) -> String {
+        let state: MutexGuard<'_, ProgressState> = self.lock_state();
+
+        format_status(
+            state.elapsed(),
+            self.total_duration,
+            state.leg,
+            self.legs,
+            state.blocks_posted,
+            &state.heads
+        )
+    }
+
+    // Report the progress in the background until the reporter is stopped.
+    pub fn spawn_reporter(&self, report_interval: Duration) -> ProgressReporter {
+        let progress: ShipmentProgress = self.clone();
+        let interactive: bool = io::stdout().is_terminal();
+        let tick: Duration = if interactive { Duration::from_secs(1) } else { report_interval };
+
+        let handle: JoinHandle<()> = tokio::spawn(async move {
+            let mut ticker: tokio::time::Interval = tokio::time::interval(tick);
+            // The first tick completes immediately, before anything is posted.
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                let line: String = progress.status_line();
+
+                if interactive {
+                    print!("\r\x1b[2K{}", line);
+                    let _ = io::stdout().flush();
+                } else {
+                    println!("Progress: {}", line);
+                }
+            }
+        });
+
+        ProgressReporter { handle, interactive }
+    }
+}
+
+// Bring the checkpoint up to date with the chain heads, cycles and elapsed
+// time of the leg and save it. A failed save only costs the ability to resume, so the run
+// goes on.
+fn save_checkpoint(state: &mut ProgressState) {
+    let elapsed: Duration = state.elapsed();
+    let heads: BTreeMap<String, String> = state.heads
+        .iter()
+        .map(|(chain, head)| (chain.clone(), head.to_string()))
+        .collect();
+
+    let cycles: BTreeMap<String, usize> = state.cycles.clone();
+
+    if let Some((path, checkpoint)) = state.checkpoint.as_mut() {
+        checkpoint.heads = heads;
+        checkpoint.cycles = cycles;
+        checkpoint.elapsed_secs = elapsed.as_secs();
+
+        if let Err(err) = checkpoint.save(path) {
+            warn!("Failed to save the checkpoint: {:#}", err);
+        }
+    }
+}
+
+// The background task printing the progress of a run.
+pub struct ProgressReporter {
+    handle: JoinHandle<()>,
+    interactive: bool,
+}
+
+impl ProgressReporter {
+    // Stop reporting, clearing the status line from the terminal.
+    pub fn stop(self) {
+        self.handle.abort();
+
+        if self.interactive {
+            print!("\r\x1b[2K");
+            let _ = io::stdout().flush();
+        }
+    }
+}
+
+// Format a duration as e.g. 1h02m05s, 2m05s or 45s.
+pub fn format_duration(duration: Duration) -> String {
+    let secs: u64 = duration.as_secs();
+    let (hours, minutes, seconds): (u64, u64, u64) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{}h{:02}m{:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m{:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+// The status line of a run: a bar of the elapsed part of the leg, the elapsed
+// and total duration, the leg when there are several, the blocks posted so far
+// and the start of every chain head.
+pub fn format_status(
+    elapsed: Duration,
+    total: Duration,
+    leg: usize,
+    legs: usize,
+    blocks_posted: usize,
+    heads: &BTreeMap<String, BlockId>
+) -> String {
+    let elapsed: Duration = elapsed.min(total);
+    let filled: usize = if total.is_zero() {
+        BAR_WIDTH
+    } else {
+        (elapsed.as_secs_f64() / total.as_secs_f64() * BAR_WIDTH as f64) as usize
+    };
+
+    let mut status: String = format!(
+        "[{}{}] {}/{}",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH - filled),
+        format_duration(elapsed),
+        format_duration(total)
+    );
+
+    if legs > 1 {
+        status.push_str(&format!(" | leg {}/{}", leg, legs));
+    }
+
+    status.push_str(&format!(" | {} blocks posted", blocks_posted));
+
+    for (chain, head) in heads.iter() {
+        let head: String = head.to_string();
+        status.push_str(&format!(" | {} {}", chain, &head[..HEAD_PREFIX_LENGTH]));
+    }
+
+    status
+}
@@ -0,0 +1,133 @@
+// Rust module to find the blocks of a shipment by their tag, for analysis
+// beyond following a single chain. Tagged data blocks are not indexed by
+// Stardust nodes, and a block cannot be asked for the blocks that reference
+// it, so the search walks the shipment back from one of its blocks, usually
+// the delivery: through the chain heads and legs of a delivery and the
+// previous_block links of every chain, down to the start transportation
+// blocks. Alerts and stuck sensor notes are referenced by no block of the
+// shipment, so they are not found.
+
+use iota_sdk::types::block::{BlockDto, BlockId};
+use std::collections::HashSet;
+use tracing::warn;
+
+use crate::{
+    block_payload::{decode_block_data, BlockData},
+    block_tag::BlockTag,
+    custom_error::Error,
+    extract_tag_and_data,
+    inspect::inspect_block,
+    output::JsonOutput,
+    tangle_client::TangleClient,
+};
+
+// The blocks of the shipment a block links back to. Blocks of the supply chain,
+// such as the initial block of the shipment, link to none, so the walk ends
+// there.
+pub fn shipment_links(data: &BlockData) -> Vec<String> {
+    match data {
+        BlockData::StartTransportationData(data) => vec![data.previous_block.to_string()],
+        BlockData::DeliveredTransportationData(data) => {
+            let mut links: Vec<String> = data.metrics.clone();
+            links.extend(data.next_leg_previous_block.iter().map(|block| block.to_string()));
+            for leg in data.legs.iter().flatten() {
+                links.push(leg.start_block.to_string());
+                links.extend(leg.delivery_block.iter().map(|block| block.to_string()));
+            }
+            links
+        },
+        BlockData::MetricData(data) => vec![data.previous_block.to_string()],
+        BlockData::AlertData(data) => vec![data.previous_block.to_string()],
+        BlockData::LocationMetricData(data) => vec![data.previous_block.to_string()],
+        BlockData::BatchMetricData(data) => vec![data.previous_block.to_string()],
+        BlockData::StuckSensorData(data) => vec![data.previous_block.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+// Find the blocks with the given tag, by its built-in name or as a custom tag,
+// among the blocks of the shipment the given block links back to, itself
+// included. A pruned block ends its branch of the walk; only a pruned first
+// block is an error.
+pub async fn find_blocks_by_tag(
+    client: &TangleClient,
+    head_block_id: &str,
+    tag: &str
+) -> Result<Vec<BlockId>, Error> {
+    let tag: BlockTag = BlockTag::from_name(tag);
+
+    let mut seen: HashSet<String> = HashSet::from([head_block_id.to_owned()]);
+    let mut pending: Vec<String> = vec![head_block_id.to_owned()];
+    let mut block_ids: Vec<BlockId> = Vec::new();
+
+    while let Some(block_id) = pending.pop() {
+        let block: BlockDto = match client.get_block(&block_id).await {
+            Ok(block) => block,
+            Err(Error::BlockNotFound(pruned)) if pruned != head_block_id => {
+                warn!("Query of {} skips pruned block {}", head_block_id, pruned);
+                continue;
+            },
+            Err(err) => return Err(err),
+        };
+
+        let (block_tag, data): (Vec<u8>, Vec<u8>) = extract_tag_and_data(block)
+            .map_err(|err| err.with_block_context(&block_id, None, None))?;
+
+        if block_tag == tag.as_bytes() {
+            block_ids.push(block_id.parse::<BlockId>()?);
+        }
+
+        // Data that is not block data links to nothing.
+        let data: BlockData = match decode_block_data(&data) {
+            Ok(data) => data,
+            Err(_err) => continue,
+        };
+        for link in shipment_links(&data) {
+            if seen.insert(link.clone()) {
+                pending.push(link);
+            }
+        }
+    }
+
+    Ok(block_ids)
+}
+
+// Print the id of every block found with the tag, or its decoded payload when
+// requested, the way inspect prints it. As JSON, the ids are printed as one
+// object, and the payloads as one inspect object per block.
+pub async fn print_blocks_by_tag(
+    client: &TangleClient,
+    head_block_id: &str,
+    tag: &str,
+    payloads: bool,
+    json: bool
+) -> Result<(), Error> {
+    let block_ids: Vec<String> = find_blocks_by_tag(client, head_block_id, tag)
+        .await?
+        .iter()
+        .map(|block_id| block_id.to_string())
+        .collect();
+
+    if payloads {
+        for block_id in block_ids.iter() {
+            inspect_block(client, block_id, json).await?;
+            if !json {
+                println!();
+            }
+        }
+        return Ok(());
+    }
+
+    if json {
+        return JsonOutput::Query { tag: tag.to_owned(), block_ids }.print();
+    }
+
+    if block_ids.is_empty() {
+        println!("No blocks found with tag {} from block {}", tag, head_block_id);
+    }
+    for block_id in block_ids.iter() {
+        println!("{}", block_id);
+    }
+
+    Ok(())
+}
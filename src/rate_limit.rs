@@ -0,0 +1,93 @@
+// Rust module to keep the posting rate within the quota of the node. Public
+// nodes reject clients posting too often, so with MAX_POSTS_PER_MINUTE set
+// every post first takes a token of a bucket holding a minute worth of posts,
+// refilled continuously. Every concurrent chain posts through the same
+// bucket, so the quota holds for the run as a whole.
+
+use std::{
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+use tracing::debug;
+
+use crate::{custom_error::Error, read_env_parsed};
+
+// A bucket of posts, holding at most the posts of a minute.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    // Tokens added per second.
+    refill_rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    // A full bucket allowing the given number of posts per minute.
+    pub fn new(posts_per_minute: u32, now: Instant) -> Self {
+        let capacity: f64 = posts_per_minute as f64;
+
+        Self { capacity, refill_rate: capacity / 60.0, tokens: capacity, last_refill: now }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed: Duration = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.refill_rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    // Take a token for a post at the given time. Returns how long to wait for
+    // the next token if the bucket is empty.
+    pub fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / self.refill_rate))
+    }
+}
+
+// The bucket shared by every post of a client.
+pub struct PostRateLimiter {
+    bucket: Mutex<TokenBucket>,
+}
+
+impl PostRateLimiter {
+    pub fn new(posts_per_minute: u32) -> Self {
+        Self { bucket: Mutex::new(TokenBucket::new(posts_per_minute, Instant::now())) }
+    }
+
+    // Read MAX_POSTS_PER_MINUTE. Posts are not limited when it is unset.
+    pub fn from_env() -> Result<Option<Self>, Error> {
+        match read_env_parsed::<u32>("MAX_POSTS_PER_MINUTE")? {
+            Some(0) => Err(Error::Validation(String::from(
+                "MAX_POSTS_PER_MINUTE: must be positive"
+            ))),
+            Some(posts_per_minute) => Ok(Some(Self::new(posts_per_minute))),
+            None => Ok(None),
+        }
+    }
+
+    // A token is only ever taken whole, so the bucket is still consistent if
+    // a holder of the lock panicked.
+    fn lock_bucket(&self) -> MutexGuard<'_, TokenBucket> {
+        self.bucket.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Wait until a post is allowed. The lock is released while waiting, so the
+    // waiting posts race for the next token.
+    pub async fn acquire(&self) {
+        loop {
+            let wait: Duration = match self.lock_bucket().try_take(Instant::now()) {
+                Ok(()) => return,
+                Err(wait) => wait,
+            };
+
+            debug!(?wait, "Post rate limit reached, waiting");
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// Rust module with the receipt of a recorded shipment, the single artifact
+// listing the blocks a run posted. A resumed run may post to another network
+// than the run it resumes, so every transportation block keeps the network it
+// was posted to, and the explorer link of that network.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    block_payload::{BlockRef, TransportationLeg}, custom_error::Error, humanize::humanize_smr,
+    network::Network, output::JsonOutput, stats::BreachSummary,
+};
+
+// A transportation block of the shipment, with the network it was posted to
+// and its link on the explorer of that network. The network is None for
+// blocks posted to nodes given without a network preset.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptBlock {
+    pub name: String,
+    pub block_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<Network>,
+    // Left out when no explorer is configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipmentReceipt {
+    pub initial_block_id: String,
+    pub start_transportation_block_id: String,
+    // Number of metric blocks posted, keyed by metric type.
+    pub metric_blocks: BTreeMap<String, usize>,
+    pub delivery_block_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery_explorer_url: Option<String>,
+    // The SMR cost paid for the delivery. Left out of receipts saved before
+    // it was added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smr_cost: Option<f64>,
+    // The legs of a shipment handed over between carriers. Empty for a
+    // single-leg shipment.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub legs: Vec<TransportationLeg>,
+    // The start and delivery blocks of every leg, in the order they were
+    // posted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<ReceiptBlock>,
+    // The breaches of the OK range of every metric type that has one, keyed
+    // by metric type: the cold chain compliance of the shipment.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub breaches: BTreeMap<String, BreachSummary>,
+}
+
+impl ShipmentReceipt {
+    // Print the receipt, as JSON if requested, for machine consumption.
+    pub fn print(&self, json: bool) -> Result<(), Error> {
+        if json {
+            return JsonOutput::Receipt(self).print();
+        }
+
+        print!("--------------------------------------------------\n");
+        println!("Shipment receipt");
+        println!("Initial block: {}", self.initial_block_id);
+        println!("Start transportation block: {}", self.start_transportation_block_id);
+        for (metric_type, count) in self.metric_blocks.iter() {
+            println!("{} blocks: {}", metric_type, count);
+        }
+        for (metric_type, summary) in self.breaches.iter() {
+            match &summary.worst {
+                Some(worst) => println!(
+                    "{} breached its range {} of {} times, worst {:.2} {} (beyond {}) at {}",
+                    metric_type,
+                    summary.breaches,
+                    summary.readings,
+                    worst.value,
+                    summary.unit,
+                    worst.threshold,
+                    worst.timestamp
+                ),
+                None => println!(
+                    "{} stayed within its range for all {} readings",
+                    metric_type, summary.readings
+                ),
+            };
+        }
+        for (index, leg) in self.legs.iter().enumerate() {
+            let delivery_block: &str = leg.delivery_block
+                .as_ref()
+                .map_or(self.delivery_block_id.as_str(), BlockRef::as_str);
+            println!(
+                "Leg {} ({}): {} -> {}",
+                index + 1,
+                leg.carrier,
+                leg.start_block,
+                delivery_block
+            );
+        }
+        println!("Delivery block: {}", self.delivery_block_id);
+        if let Some(delivery_explorer_url) = &self.delivery_explorer_url {
+            println!("Delivery on explorer: {}", delivery_explorer_url);
+        }
+        if let Some(smr_cost) = self.smr_cost {
+            println!("Delivery cost: {}", humanize_smr(smr_cost));
+        }
+        if self.blocks.iter().any(|block| block.explorer_url.is_some()) {
+            println!("Blocks on explorer:");
+            for block in self.blocks.iter() {
+                let explorer_url: &str = match &block.explorer_url {
+                    Some(explorer_url) => explorer_url,
+                    None => continue,
+                };
+                match block.network {
+                    Some(network) => {
+                        println!("  {} ({}): {}", block.name, network, explorer_url)
+                    },
+                    None => println!("  {}: {}", block.name, explorer_url),
+                };
+            }
+        }
+        print!("--------------------------------------------------\n");
+
+        Ok(())
+    }
+}
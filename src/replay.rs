@@ -0,0 +1,124 @@
+// Rust module to record the blocks of a shipment and replay them later.
+// A live run started with --record saves every block it posts, in posting
+// order, and replaying the record posts the same data again, e.g. to a fresh
+// Tangle, so an experiment can be reproduced exactly.
+
+use iota_sdk::types::block::BlockId;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs};
+use tracing::info;
+
+use crate::{
+    block_payload::{BlockData, BlockRef},
+    block_store::{post_block, BlockStore},
+    custom_error::Error,
+};
+
+// A block posted by a run: the id it was posted with, its tag and its data.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedBlock {
+    pub block_id: String,
+    pub tag: String,
+    pub data: BlockData,
+}
+
+// The blocks of a recorded shipment in posting order: the start block, the
+// metric blocks and the delivery block of every leg.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipmentRecord {
+    pub blocks: Vec<RecordedBlock>,
+}
+
+impl ShipmentRecord {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let content: String = fs::read_to_string(path)?;
+
+        serde_json::from_str::<ShipmentRecord>(&content).map_err(|err| {
+            Error::Validation(format!("shipment record {}: {}", path, err))
+        })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+// Point a reference to a recorded block to the block it was replayed as.
+// References to blocks outside the record, such as the initial supply chain
+// block, are kept as they are.
+fn remap(block_id: &mut String, replayed: &HashMap<String, String>) {
+    if let Some(replayed_block_id) = replayed.get(block_id.as_str()) {
+        *block_id = replayed_block_id.to_owned();
+    }
+}
+
+// Like remap, for a previous block. The replayed ids are those of posted
+// blocks, so they are always valid.
+fn remap_ref(block_ref: &mut BlockRef, replayed: &HashMap<String, String>) {
+    if let Some(replayed_block_id) = replayed.get(block_ref.as_str()) {
+        if let Ok(replayed_block_ref) = BlockRef::new(replayed_block_id.as_str()) {
+            *block_ref = replayed_block_ref;
+        }
+    }
+}
+
+// Point every block reference of the data to the replayed blocks.
+pub fn remap_block_references(data: &mut BlockData, replayed: &HashMap<String, String>) {
+    match data {
+        BlockData::StartTransportationData(data) => remap_ref(&mut data.previous_block, replayed),
+        BlockData::DeliveredTransportationData(data) => {
+            for block_id in data.metrics.iter_mut() {
+                remap(block_id, replayed);
+            }
+            if let Some(block_ref) = data.next_leg_previous_block.as_mut() {
+                remap_ref(block_ref, replayed);
+            }
+            for leg in data.legs.iter_mut().flatten() {
+                remap_ref(&mut leg.start_block, replayed);
+                if let Some(block_ref) = leg.delivery_block.as_mut() {
+                    remap_ref(block_ref, replayed);
+                }
+            }
+        },
+        BlockData::MetricData(data) => remap_ref(&mut data.previous_block, replayed),
+        BlockData::AlertData(data) => remap_ref(&mut data.previous_block, replayed),
+        BlockData::LocationMetricData(data) => remap_ref(&mut data.previous_block, replayed),
+        BlockData::BatchMetricData(data) => {
+            remap_ref(&mut data.previous_block, replayed);
+            for metric in data.metrics.iter_mut() {
+                remap_ref(&mut metric.previous_block, replayed);
+            }
+        },
+        BlockData::StuckSensorData(data) => remap_ref(&mut data.previous_block, replayed),
+        _ => {},
+    };
+}
+
+// Post the blocks of a recorded shipment again, in their recorded order. The
+// recorded timestamps are kept, and every reference to a recorded block is
+// rechained to the id the block was replayed as. Returns the id of the last
+// replayed block, the delivery of the shipment.
+pub async fn replay_shipment(client: &dyn BlockStore, path: &str) -> Result<BlockId, Error> {
+    let record: ShipmentRecord = ShipmentRecord::load(path)?;
+
+    let mut replayed: HashMap<String, String> = HashMap::new();
+    let mut last_block_id: Option<BlockId> = None;
+
+    for mut block in record.blocks {
+        remap_block_references(&mut block.data, &replayed);
+
+        let data: Vec<u8> = serde_json::to_string(&block.data)?.as_bytes().to_vec();
+        let block_id: BlockId = post_block(client, block.tag.as_bytes().to_vec(), data).await?;
+
+        info!("Replayed block {} as {}", block.block_id, block_id);
+        replayed.insert(block.block_id, block_id.to_string());
+        last_block_id = Some(block_id);
+    }
+
+    last_block_id.ok_or_else(|| Error::Anyhow(anyhow::Error::msg(format!(
+        "Shipment record {} holds no blocks", path
+    ))))
+}
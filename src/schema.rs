@@ -0,0 +1,37 @@
+// Rust module to generate the JSON Schema of the block payloads.
+// The schemas follow the serde attributes of the payload types, so they match
+// the camelCase JSON consumed by the React.js frontend and Tauri.
+
+use schemars::{schema::RootSchema, schema_for};
+use serde_json::{Map, Value};
+
+use crate::{block_payload::*, custom_error::Error};
+
+// Generate the schema of every payload type, keyed by the type name.
+pub fn block_payload_schemas() -> Result<Value, Error> {
+    let schemas: Vec<(&str, RootSchema)> = vec![
+        ("BlockPayload", schema_for!(BlockPayload)),
+        ("TaggedDataPayload", schema_for!(TaggedDataPayload)),
+        ("BlockData", schema_for!(BlockData)),
+        ("RawMaterialsProducerBlockData", schema_for!(RawMaterialsProducerBlockData)),
+        ("SupplierBlockData", schema_for!(SupplierBlockData)),
+        ("ManufacturerBlockData", schema_for!(ManufacturerBlockData)),
+        ("DistributorBlockData", schema_for!(DistributorBlockData)),
+        ("RetailerBlockData", schema_for!(RetailerBlockData)),
+        ("ConsumerBlockData", schema_for!(ConsumerBlockData)),
+        ("StartTransportationData", schema_for!(StartTransportationData)),
+        ("DeliveredTransportationData", schema_for!(DeliveredTransportationData)),
+        ("MetricData", schema_for!(MetricData)),
+        ("AlertData", schema_for!(AlertData)),
+        ("LocationMetricData", schema_for!(LocationMetricData)),
+        ("BatchMetricData", schema_for!(BatchMetricData)),
+        ("StuckSensorData", schema_for!(StuckSensorData)),
+    ];
+
+    let mut schema_map: Map<String, Value> = Map::new();
+    for (name, schema) in schemas {
+        schema_map.insert(name.to_owned(), serde_json::to_value(schema)?);
+    }
+
+    Ok(Value::Object(schema_map))
+}
@@ -0,0 +1,104 @@
+// Rust module with the self-test of the board. A small block is posted,
+// fetched back from the node and decoded, so a single command confirms that
+// the node, the serialization and the decoding work end to end before a real
+// run relies on them.
+
+use iota_sdk::types::block::{BlockDto, BlockId};
+use std::time::Duration;
+
+use crate::{
+    block_payload::{decode_block_data, BlockData},
+    block_store::post_block,
+    custom_error::Error,
+    extract_tag_and_data,
+    humanize::humanize_duration,
+    output::SelftestReport,
+    tangle_client::TangleClient,
+    timestamp::{now_timestamp, TimestampFormat},
+};
+
+pub const SELFTEST_TAG: &str = "Board Selftest Tag";
+
+// The data of the self-test block, a plain BasicBlockData naming the time it
+// was posted at.
+pub fn selftest_data(timestamp: &str) -> BlockData {
+    BlockData::BasicBlockData(format!("Board self-test at {}", timestamp))
+}
+
+// Check that the tag and the decoded data read back from the node are the
+// ones posted.
+pub fn check_round_trip(
+    tag: &[u8],
+    data: &BlockData,
+    fetched_tag: &[u8],
+    fetched_data: &[u8]
+) -> Result<(), Error> {
+    if fetched_tag != tag {
+        return Err(Error::Anyhow(anyhow::Error::msg(format!(
+            "tag read back as '{}', posted as '{}'",
+            String::from_utf8_lossy(fetched_tag), String::from_utf8_lossy(tag)
+        ))));
+    }
+
+    let fetched: BlockData = decode_block_data(fetched_data)?;
+    if serde_json::to_value(&fetched)? != serde_json::to_value(data)? {
+        return Err(Error::Anyhow(anyhow::Error::msg(format!(
+            "data read back as {}, posted as {}",
+            serde_json::to_string(&fetched)?, serde_json::to_string(data)?
+        ))));
+    }
+
+    Ok(())
+}
+
+// Post the self-test block, fetch it back and compare it with the posted one,
+// filling in the block id and the posting time as they become known.
+async fn round_trip(client: &TangleClient, report: &mut SelftestReport) -> Result<(), Error> {
+    if client.dry_run() {
+        return Err(Error::Anyhow(anyhow::Error::msg(
+            "DRY_RUN is set, but the self-test needs to post a block"
+        )));
+    }
+
+    let tag: Vec<u8> = SELFTEST_TAG.as_bytes().to_vec();
+    let data: BlockData = selftest_data(&now_timestamp(TimestampFormat::from_env()?)?);
+
+    let block_id: BlockId = post_block(client, tag.clone(), serde_json::to_vec(&data)?).await?;
+    report.block_id = Some(block_id.to_string());
+    report.posting_ms = Some(client.posting_stats().total.as_millis() as u64);
+
+    let block: BlockDto = client.get_block(&block_id.to_string()).await?;
+    let (fetched_tag, fetched_data): (Vec<u8>, Vec<u8>) = extract_tag_and_data(block)?;
+
+    check_round_trip(&tag, &data, &fetched_tag, &fetched_data)
+}
+
+// Run the self-test. A failing step fails the test rather than the command,
+// so the report still names the block and the posting time reached so far.
+pub async fn run_selftest(client: &TangleClient) -> SelftestReport {
+    let mut report: SelftestReport = SelftestReport::default();
+
+    match round_trip(client, &mut report).await {
+        Ok(()) => report.passed = true,
+        Err(err) => report.failure = Some(format!("{:#}", err)),
+    };
+
+    report
+}
+
+pub fn print_selftest_report(report: &SelftestReport) {
+    if let Some(block_id) = &report.block_id {
+        println!("Self-test block: {}", block_id);
+    }
+    if let Some(posting_ms) = report.posting_ms {
+        println!(
+            "Posting time, PoW included: {}",
+            humanize_duration(Duration::from_millis(posting_ms))
+        );
+    }
+
+    match &report.failure {
+        Some(failure) => println!("Self-test failed: {}", failure),
+        None => println!("Self-test passed: the block read back matches the one posted"),
+    };
+}
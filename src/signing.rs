@@ -0,0 +1,153 @@
+// Rust module to sign the posted payloads, proving which producer posted them.
+// With SIGNING_KEY set, an Ed25519 signature of the payload is embedded in it
+// as a base64 signature and publicKey, next to the fields of the block data,
+// which readers ignore. The signature covers the payload without these two
+// fields in canonical form: compact JSON with its object keys sorted, which
+// reads back identically, so it can be checked on any block read later.
+// Without the key, payloads are posted unsigned.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{custom_error::Error, read_env_var};
+
+// Ed25519 secret keys are 32 bytes long, signatures 64 bytes long.
+const KEY_LENGTH: usize = 32;
+
+const SIGNATURE_FIELD: &str = "signature";
+const PUBLIC_KEY_FIELD: &str = "publicKey";
+
+fn decode_base64(value: &str, field: &str) -> Result<Vec<u8>, Error> {
+    STANDARD.decode(value.trim()).map_err(|err| Error::Validation(format!(
+        "{}: not valid base64: {}", field, err
+    )))
+}
+
+// Parse a base64 encoded 32 byte Ed25519 secret key.
+pub fn parse_signing_key(value: &str) -> Result<SigningKey, Error> {
+    let key: Vec<u8> = decode_base64(value, "SIGNING_KEY")?;
+
+    let key: [u8; KEY_LENGTH] = key.as_slice().try_into().map_err(|_err| {
+        Error::Validation(format!(
+            "SIGNING_KEY: must be {} bytes long, got {}", KEY_LENGTH, key.len()
+        ))
+    })?;
+
+    Ok(SigningKey::from_bytes(&key))
+}
+
+// Read the key from SIGNING_KEY. Returns None when it is not set.
+pub fn read_signing_key() -> Result<Option<SigningKey>, Error> {
+    match read_env_var("SIGNING_KEY".to_string()) {
+        Ok(value) => Ok(Some(parse_signing_key(&value)?)),
+        Err(_err) => Ok(None),
+    }
+}
+
+// The payload as a JSON object. Only objects can carry a signature.
+fn payload_object(data: &[u8]) -> Result<Map<String, Value>, Error> {
+    match serde_json::from_slice::<Value>(data)? {
+        Value::Object(object) => Ok(object),
+        _ => Err(Error::Validation(String::from(
+            "payload: only JSON objects can be signed"
+        ))),
+    }
+}
+
+// A JSON value written with the keys of every object sorted.
+struct Canonical<'a>(&'a Value);
+
+impl Serialize for Canonical<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Object(object) => {
+                let mut entries: Vec<(&String, &Value)> = object.iter().collect();
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, &Canonical(value))?;
+                }
+                map.end()
+            },
+            Value::Array(values) => serializer.collect_seq(values.iter().map(Canonical)),
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+// The bytes a signature covers. They do not depend on the order the fields
+// were written in.
+fn canonical_bytes(object: &Map<String, Value>) -> Result<Vec<u8>, Error> {
+    Ok(serde_json::to_vec(&Canonical(&Value::Object(object.clone())))?)
+}
+
+// Sign the payload and embed the signature and the public key in it.
+pub fn sign_payload(data: &[u8], key: &SigningKey) -> Result<Vec<u8>, Error> {
+    let mut object: Map<String, Value> = payload_object(data)?;
+    let signature: Signature = key.sign(&canonical_bytes(&object)?);
+
+    object.insert(
+        String::from(SIGNATURE_FIELD),
+        Value::String(STANDARD.encode(signature.to_bytes()))
+    );
+    object.insert(
+        String::from(PUBLIC_KEY_FIELD),
+        Value::String(STANDARD.encode(key.verifying_key().to_bytes()))
+    );
+
+    Ok(serde_json::to_vec(&object)?)
+}
+
+// The signature embedded in a payload, and whether it matches the payload.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadSignature {
+    pub public_key: String,
+    pub valid: bool,
+}
+
+// Check the signature embedded in a payload against its public key. Returns
+// None for unsigned payloads, including payloads that are not JSON objects. A
+// malformed signature or public key is an error.
+pub fn verify_payload(data: &[u8]) -> Result<Option<PayloadSignature>, Error> {
+    let mut object: Map<String, Value> = match payload_object(data) {
+        Ok(object) => object,
+        Err(_err) => return Ok(None),
+    };
+
+    let (signature, public_key): (String, String) = match (
+        object.remove(SIGNATURE_FIELD),
+        object.remove(PUBLIC_KEY_FIELD)
+    ) {
+        (Some(Value::String(signature)), Some(Value::String(public_key))) => {
+            (signature, public_key)
+        },
+        (None, None) => return Ok(None),
+        _ => return Err(Error::Validation(String::from(
+            "signature: must be given as base64 strings along with the publicKey"
+        ))),
+    };
+
+    let signature_bytes: Vec<u8> = decode_base64(&signature, SIGNATURE_FIELD)?;
+    let signature: Signature = Signature::from_slice(&signature_bytes).map_err(|err| {
+        Error::Validation(format!("signature: {}", err))
+    })?;
+
+    let public_key_bytes: [u8; KEY_LENGTH] = decode_base64(&public_key, PUBLIC_KEY_FIELD)?
+        .as_slice()
+        .try_into()
+        .map_err(|_err| Error::Validation(format!(
+            "publicKey: must be {} bytes long", KEY_LENGTH
+        )))?;
+    let verifying_key: VerifyingKey = VerifyingKey::from_bytes(&public_key_bytes)
+        .map_err(|err| Error::Validation(format!("publicKey: {}", err)))?;
+
+    let valid: bool = verifying_key
+        .verify(&canonical_bytes(&object)?, &signature)
+        .is_ok();
+
+    Ok(Some(PayloadSignature { public_key, valid }))
+}
@@ -0,0 +1,193 @@
+// Rust module to compute aggregate statistics of the metrics of a shipment and
+// of the time spent posting its blocks.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use crate::{
+    alert::AlertRange, block_payload::MetricData, custom_error::Error,
+    humanize::humanize_duration,
+};
+
+// Aggregate statistics of the readings of a single metric type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSummary {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+// Summarize the given metrics, grouped by metric type. Non-finite readings are
+// ignored, and metric types without any finite reading are left out.
+pub fn summarize_metrics(metrics: &[MetricData]) -> HashMap<String, MetricSummary> {
+    let mut sums: HashMap<String, (MetricSummary, f64)> = HashMap::new();
+
+    for metric in metrics.iter().filter(|metric| metric.metric_value.is_finite()) {
+        let value: f64 = metric.metric_value;
+        let (summary, sum) = sums
+            .entry(metric.metric_type.to_owned())
+            .or_insert((
+                MetricSummary { count: 0, min: value, max: value, mean: 0.0 },
+                0.0
+            ));
+
+        summary.count += 1;
+        summary.min = summary.min.min(value);
+        summary.max = summary.max.max(value);
+        *sum += value;
+    }
+
+    sums
+        .into_iter()
+        .map(|(metric_type, (mut summary, sum))| {
+            summary.mean = sum / summary.count as f64;
+            (metric_type, summary)
+        })
+        .collect()
+}
+
+// A reading that left the OK range of its metric, and the bound it crossed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Breach {
+    pub value: f64,
+    pub threshold: f64,
+    pub timestamp: String,
+}
+
+// How many readings of a metric type left its OK range, and the breach
+// furthest beyond it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BreachSummary {
+    pub readings: usize,
+    pub breaches: usize,
+    pub unit: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worst: Option<Breach>,
+}
+
+impl BreachSummary {
+    fn record(&mut self, metric: &MetricData, threshold: Option<f64>) {
+        self.readings += 1;
+
+        let threshold: f64 = match threshold {
+            Some(threshold) => threshold,
+            None => return,
+        };
+        self.breaches += 1;
+
+        let distance: f64 = (metric.metric_value - threshold).abs();
+        let worse: bool = match &self.worst {
+            Some(worst) => distance > (worst.value - worst.threshold).abs(),
+            None => true,
+        };
+        if worse {
+            self.worst = Some(Breach {
+                value: metric.metric_value,
+                threshold,
+                timestamp: metric.timestamp.clone(),
+            });
+        }
+    }
+}
+
+// Count the readings of every metric type outside the OK range the given
+// lookup returns for it, e.g. AlertRange::from_env. Metric types without any
+// bound configured cannot breach and are left out.
+pub fn summarize_breaches<F>(
+    metrics: &[MetricData],
+    range_of: F
+) -> Result<BTreeMap<String, BreachSummary>, Error>
+where
+    F: Fn(&str) -> Result<AlertRange, Error>,
+{
+    let mut ranges: HashMap<String, AlertRange> = HashMap::new();
+    let mut summaries: BTreeMap<String, BreachSummary> = BTreeMap::new();
+
+    for metric in metrics.iter().filter(|metric| metric.metric_value.is_finite()) {
+        if !ranges.contains_key(&metric.metric_type) {
+            ranges.insert(metric.metric_type.clone(), range_of(&metric.metric_type)?);
+        }
+        let range: &AlertRange = &ranges[&metric.metric_type];
+        if range.min.is_none() && range.max.is_none() {
+            continue;
+        }
+
+        summaries
+            .entry(metric.metric_type.clone())
+            .or_insert_with(|| BreachSummary {
+                readings: 0,
+                breaches: 0,
+                unit: metric.measurement_unit.clone(),
+                worst: None,
+            })
+            .record(metric, range.violated_threshold(metric.metric_value));
+    }
+
+    Ok(summaries)
+}
+
+// Print the summary of every metric type, sorted by metric type.
+pub fn print_metric_summaries(summaries: &HashMap<String, MetricSummary>) {
+    let mut metric_types: Vec<&String> = summaries.keys().collect();
+    metric_types.sort();
+
+    print!("--------------------------------------------------\n");
+    println!("Metric summary");
+    for metric_type in metric_types {
+        let summary: &MetricSummary = &summaries[metric_type];
+        println!(
+            "{}: count {}, min {:.2}, max {:.2}, mean {:.2}",
+            metric_type, summary.count, summary.min, summary.max, summary.mean
+        );
+    }
+    print!("--------------------------------------------------\n");
+}
+
+// Running tally of the time spent posting blocks, PoW included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PostingStats {
+    pub blocks_posted: usize,
+    pub total: Duration,
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+}
+
+impl PostingStats {
+    pub fn record(&mut self, elapsed: Duration) {
+        self.blocks_posted += 1;
+        self.total += elapsed;
+        self.min = Some(self.min.map_or(elapsed, |min| min.min(elapsed)));
+        self.max = Some(self.max.map_or(elapsed, |max| max.max(elapsed)));
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        let blocks_posted: u32 = u32::try_from(self.blocks_posted).ok()?;
+
+        match blocks_posted {
+            0 => None,
+            blocks_posted => Some(self.total / blocks_posted),
+        }
+    }
+}
+
+pub fn print_posting_stats(stats: &PostingStats) {
+    print!("--------------------------------------------------\n");
+    println!("Posting summary");
+    println!("Blocks posted: {}", stats.blocks_posted);
+    println!("Total posting time: {}", humanize_duration(stats.total));
+    if let (Some(average), Some(min), Some(max)) = (stats.average(), stats.min, stats.max) {
+        println!(
+            "Per block: average {}, min {}, max {}",
+            humanize_duration(average),
+            humanize_duration(min),
+            humanize_duration(max)
+        );
+    }
+    print!("--------------------------------------------------\n");
+}
@@ -0,0 +1,442 @@
+// Rust module with the client the board talks to the Tangle through.
+// It wraps the IOTA client along with the explorer, dry run and digest log
+// settings, which are resolved once when the client is created.
+
+use iota_sdk::{
+    client::{
+        core::Client,
+        node_api::{error::Error as NodeApiError, indexer::query_parameters::QueryParameter},
+        Error as IotaClientError,
+    },
+    types::{
+        api::core::response::{BlockMetadataResponse, InfoResponse},
+        block::{
+            address::Bech32Address,
+            output::{OutputId, OutputWithMetadata},
+            Block, BlockDto, BlockId,
+        },
+    },
+};
+use ed25519_dalek::SigningKey;
+use std::{
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+use tracing::{debug, info, instrument, warn};
+
+use crate::{
+    block_payload::{decode_block_data, BlockData},
+    collector_metrics::CollectorMetrics,
+    config::DEFAULT_POST_TIMEOUT_SECS,
+    confirmation::{read_confirmation_timeout, wait_until_referenced, CONFIRMATION_POLL_INTERVAL},
+    create_iota_client,
+    custom_error::Error,
+    digest::{payload_digest, record_digest},
+    humanize::humanize_duration,
+    network::{
+        explorer_block_link, read_explorer_url, resolve_explorer_block_template, Network,
+    },
+    output::{JsonOutput, PostedBlock},
+    payload_format::PayloadFormat,
+    receipt::ReceiptBlock,
+    rate_limit::PostRateLimiter,
+    read_env_flag, read_env_parsed, read_env_var,
+    replay::{RecordedBlock, ShipmentRecord},
+    signing::{read_signing_key, sign_payload},
+    stats::PostingStats,
+};
+
+pub struct TangleClient {
+    client: Client,
+    // The network preset the client posts to, if any.
+    network: Option<Network>,
+    // Base URL of the explorer, if block links are printed at all.
+    explorer_url: Option<String>,
+    explorer_block_template: String,
+    dry_run: bool,
+    digest_log_path: Option<String>,
+    // Longest time a block may take to build and post.
+    post_timeout: Duration,
+    // Longest time a posted block is waited on to be referenced, if posts
+    // wait for it at all.
+    confirmation_timeout: Option<Duration>,
+    // Shared by the concurrent chains, so their posts stay within the quota
+    // of the node together.
+    rate_limiter: Option<PostRateLimiter>,
+    // Key every posted payload is signed with, if any.
+    signing_key: Option<SigningKey>,
+    // Format the data is posted in.
+    payload_format: PayloadFormat,
+    // Blocks are posted concurrently through a shared client, so the tally
+    // sits behind a lock.
+    posting_stats: Mutex<PostingStats>,
+    collector_metrics: CollectorMetrics,
+    // The blocks posted so far, kept when the run is recorded.
+    recorded_blocks: Option<Mutex<Vec<RecordedBlock>>>,
+    // Whether every post is reported on stdout as a JSON object.
+    json_output: bool,
+}
+
+impl TangleClient {
+    // Create the IOTA client of the configured nodes. DRY_RUN, the explorer
+    // and its block link template, DIGEST_LOG_PATH, POST_TIMEOUT_SECS,
+    // WAIT_CONFIRMATION, MAX_POSTS_PER_MINUTE, SIGNING_KEY and PAYLOAD_FORMAT
+    // are read once here.
+    // An explorer block template without {block_id} is rejected.
+    pub async fn new() -> Result<Self, Error> {
+        Ok(Self {
+            client: create_iota_client().await?,
+            network: Network::from_env()?,
+            explorer_url: read_explorer_url()?,
+            explorer_block_template: resolve_explorer_block_template()?,
+            dry_run: read_env_flag("DRY_RUN"),
+            digest_log_path: read_env_var("DIGEST_LOG_PATH".to_string()).ok(),
+            post_timeout: read_post_timeout()?,
+            confirmation_timeout: read_confirmation_timeout()?,
+            rate_limiter: PostRateLimiter::from_env()?,
+            signing_key: read_signing_key()?,
+            payload_format: PayloadFormat::from_env()?,
+            posting_stats: Mutex::new(PostingStats::default()),
+            collector_metrics: CollectorMetrics::new()?,
+            recorded_blocks: None,
+            json_output: false,
+        })
+    }
+
+    // The wrapped IOTA client, for node calls the wrapper does not cover.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    // A duration is only ever recorded whole, so the tally is still
+    // consistent if a holder of the lock panicked.
+    fn lock_posting_stats(&self) -> MutexGuard<'_, PostingStats> {
+        self.posting_stats.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // The time spent posting the blocks posted so far, PoW included.
+    pub fn posting_stats(&self) -> PostingStats {
+        self.lock_posting_stats().clone()
+    }
+
+    // Keep every block posted from now on, to be saved as a shipment record.
+    pub fn enable_recording(&mut self) {
+        self.recorded_blocks = Some(Mutex::new(Vec::new()));
+    }
+
+    // Post the payloads unsigned from now on, e.g. for data that is not a JSON
+    // object and so cannot carry a signature.
+    pub fn disable_signing(&mut self) {
+        self.signing_key = None;
+    }
+
+    // Report every block posted, or printed in dry run mode, from now on as a
+    // JSON object on stdout.
+    pub fn enable_json_output(&mut self) {
+        self.json_output = true;
+    }
+
+    // Take the blocks recorded so far, in posting order.
+    pub fn take_shipment_record(&self) -> ShipmentRecord {
+        let blocks: Vec<RecordedBlock> = match &self.recorded_blocks {
+            Some(recorded_blocks) => std::mem::take(
+                &mut *recorded_blocks.lock().unwrap_or_else(|err| err.into_inner())
+            ),
+            None => Vec::new(),
+        };
+
+        ShipmentRecord { blocks }
+    }
+
+    // The operational metrics of the posted blocks, for the HTTP server.
+    pub fn collector_metrics(&self) -> CollectorMetrics {
+        self.collector_metrics.clone()
+    }
+
+    // Link of a block on the explorer of the selected network, if an explorer
+    // is configured.
+    pub fn explorer_link(&self, block_id: &str) -> Option<String> {
+        self.explorer_url.as_deref().map(|explorer_url| {
+            explorer_block_link(&self.explorer_block_template, explorer_url, block_id)
+        })
+    }
+
+    pub fn network(&self) -> Option<Network> {
+        self.network
+    }
+
+    // A block just posted by the client, named for the receipt, with the
+    // network it went to and its explorer link.
+    pub fn receipt_block(&self, name: String, block_id: &BlockId) -> ReceiptBlock {
+        ReceiptBlock {
+            name,
+            block_id: block_id.to_string(),
+            network: self.network,
+            explorer_url: self.explorer_link(&block_id.to_string()),
+        }
+    }
+
+    // The info of the first healthy node: its health, milestones and the
+    // protocol parameters of its network.
+    pub async fn node_info(&self) -> Result<InfoResponse, Error> {
+        Ok(self.client.get_info().await?)
+    }
+
+    // The balance of an address in glow: the amounts of its unspent basic
+    // outputs that it can spend right away, without a timelock, expiration or
+    // storage deposit return attached.
+    pub async fn address_balance(&self, address: &Bech32Address) -> Result<u64, Error> {
+        let output_ids: Vec<OutputId> = self.client
+            .basic_output_ids(vec![
+                QueryParameter::Address(address.clone()),
+                QueryParameter::HasExpiration(false),
+                QueryParameter::HasTimelock(false),
+                QueryParameter::HasStorageDepositReturn(false),
+            ])
+            .await?
+            .items;
+
+        let outputs: Vec<OutputWithMetadata> = self.client.get_outputs(&output_ids).await?;
+
+        Ok(outputs.iter().map(|output| output.output().amount()).sum())
+    }
+
+    pub async fn get_block(&self, block_id: &String) -> Result<BlockDto, Error> {
+        let block_context = |err: Error| err.with_block_context(block_id, None, None);
+
+        let parsed_block_id: BlockId = block_id
+            .parse::<BlockId>()
+            .map_err(|err| block_context(err.into()))?;
+
+        let block: Block = self.client
+            .get_block(&parsed_block_id)
+            .await
+            .map_err(|err| if is_not_found(&err) {
+                Error::BlockNotFound(block_id.to_owned())
+            } else {
+                block_context(err.into())
+            })?;
+
+        let block_dto: BlockDto = BlockDto::from(&block);
+
+        Ok(block_dto)
+    }
+
+    // Post a tagged data block and return its id. The SHA-256 digest of the
+    // data is logged with the block id, and appended to the digest log if one
+    // is configured. With a signing key, the data is signed first, so the
+    // digest and the dry run output cover the signed payload. The data is then
+    // encoded in the payload format, which the digest covers as well, while
+    // the dry run output stays JSON. In dry run mode, the block is only
+    // printed and a null block id is returned instead, without spending PoW or
+    // touching the node.
+    #[instrument(skip_all, fields(tag = %String::from_utf8_lossy(&tag)))]
+    pub async fn post(&self, tag: Vec<u8>, data: Vec<u8>) -> Result<BlockId, Error> {
+        let data: Vec<u8> = match &self.signing_key {
+            Some(key) => sign_payload(&data, key)?,
+            None => data,
+        };
+        let payload: Vec<u8> = self.payload_format.encode(&data)?;
+        let digest: String = payload_digest(&payload);
+
+        if self.dry_run {
+            info!(%digest, "Dry run, block not posted");
+            if self.json_output {
+                JsonOutput::DryRun {
+                    tag: String::from_utf8_lossy(&tag).into_owned(),
+                    digest,
+                    data: serde_json::from_slice::<serde_json::Value>(&data).unwrap_or_else(
+                        |_err| serde_json::Value::String(String::from_utf8_lossy(&data).into())
+                    ),
+                }.print()?;
+            } else {
+                print_dry_run_block(&tag, &data)?;
+            }
+            return Ok(BlockId::null());
+        }
+
+        debug!("Posting block...");
+        let tag_label: String = String::from_utf8_lossy(&tag).into_owned();
+
+        // The data is decoded before it is handed over to the block builder.
+        let recorded_data: Option<BlockData> = match &self.recorded_blocks {
+            Some(_recorded_blocks) => match decode_block_data(&data) {
+                Ok(block_data) => Some(block_data),
+                Err(err) => {
+                    warn!("Block data cannot be recorded: {}", err);
+                    None
+                },
+            },
+            None => None,
+        };
+
+        // The wait for the rate limit is not counted as posting time.
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+
+        let start: Instant = Instant::now();
+
+        let posted: Result<BlockId, Error> = self.build_and_post(tag, payload).await;
+
+        let block_id: BlockId = match posted {
+            Ok(block_id) => block_id,
+            Err(err) => {
+                self.collector_metrics.record_posting_error(&tag_label);
+                return Err(err);
+            },
+        };
+
+        let elapsed: Duration = start.elapsed();
+        self.lock_posting_stats().record(elapsed);
+        self.collector_metrics.record_posted(&tag_label, elapsed);
+
+        if let (Some(recorded_blocks), Some(data)) = (&self.recorded_blocks, recorded_data) {
+            recorded_blocks.lock().unwrap_or_else(|err| err.into_inner()).push(RecordedBlock {
+                block_id: block_id.to_string(),
+                tag: tag_label.clone(),
+                data,
+            });
+        }
+
+        info!(elapsed = %humanize_duration(elapsed), %block_id, %digest, "Block posted");
+        if let Some(explorer_link) = self.explorer_link(&block_id.to_string()) {
+            info!("Block posted on: {}", explorer_link);
+        }
+
+        // The block is already posted, so a block that is not referenced in
+        // time is still chained off rather than posted again.
+        if let Some(confirmation_timeout) = self.confirmation_timeout {
+            if let Err(err) = self.wait_for_confirmation(&block_id, confirmation_timeout).await {
+                warn!("Block {} not confirmed, chaining off it anyway: {:#}", block_id, err);
+            }
+        }
+
+        if self.json_output {
+            let posted_block: PostedBlock = PostedBlock {
+                block_id: block_id.to_string(),
+                tag: tag_label,
+                digest: digest.clone(),
+                elapsed_ms: elapsed.as_millis() as u64,
+                explorer_url: self.explorer_link(&block_id.to_string()),
+            };
+            if let Err(err) = JsonOutput::Post(&posted_block).print() {
+                warn!("Failed to report block {}: {:#}", block_id, err);
+            }
+        }
+
+        // The block is already posted, so a failing digest log must not make
+        // the caller lose its id.
+        if let Some(path) = &self.digest_log_path {
+            if let Err(err) = record_digest(path, &block_id.to_string(), &digest) {
+                warn!("Failed to record the digest of block {}: {:#}", block_id, err);
+            }
+        }
+
+        Ok(block_id)
+    }
+
+    // Wait for a posted block to be referenced by a milestone.
+    async fn wait_for_confirmation(
+        &self,
+        block_id: &BlockId,
+        timeout: Duration
+    ) -> Result<(), Error> {
+        let start: Instant = Instant::now();
+
+        wait_until_referenced(
+            || async {
+                let metadata: BlockMetadataResponse =
+                    self.client.get_block_metadata(block_id).await?;
+                Ok::<bool, Error>(metadata.referenced_by_milestone_index.is_some())
+            },
+            timeout,
+            CONFIRMATION_POLL_INTERVAL
+        ).await?;
+
+        debug!(elapsed = ?start.elapsed(), %block_id, "Block referenced");
+
+        Ok(())
+    }
+
+    // Build the block, doing its PoW, and post it to the node. A hung
+    // connection to the node must not block the run forever, so both steps
+    // together get the post timeout. The id of a block is known once it is
+    // built, so a post that failed or timed out after the node received the
+    // block is adopted instead of failing: the caller would otherwise post
+    // the next block off the same parent, forking the chain.
+    async fn build_and_post(&self, tag: Vec<u8>, data: Vec<u8>) -> Result<BlockId, Error> {
+        let deadline: tokio::time::Instant = tokio::time::Instant::now() + self.post_timeout;
+
+        let block: Block = match tokio::time::timeout_at(
+            deadline,
+            self.client.build_block().with_tag(tag).with_data(data).finish()
+        ).await {
+            Ok(block) => block?,
+            Err(_elapsed) => return Err(Error::Timeout(self.post_timeout)),
+        };
+        let block_id: BlockId = block.id();
+
+        let err: Error = match tokio::time::timeout_at(
+            deadline,
+            self.client.post_block(&block)
+        ).await {
+            Ok(Ok(posted_block_id)) => return Ok(posted_block_id),
+            Ok(Err(err)) => Error::from(err),
+            Err(_elapsed) => Error::Timeout(self.post_timeout),
+        };
+
+        match tokio::time::timeout(
+            self.post_timeout,
+            self.client.get_block_metadata(&block_id)
+        ).await {
+            Ok(Ok(_metadata)) => {
+                warn!("Posting block {} failed, but the node holds it: {:#}", block_id, err);
+                Ok(block_id)
+            },
+            _ => Err(err),
+        }
+    }
+}
+
+// Whether the node answered that it does not hold the requested data, as for a
+// block that was pruned or never posted.
+fn is_not_found(err: &IotaClientError) -> bool {
+    matches!(
+        err,
+        IotaClientError::Node(NodeApiError::NotFound(_))
+            | IotaClientError::Node(NodeApiError::ResponseError { code: 404, .. })
+    )
+}
+
+// Read POST_TIMEOUT_SECS, the longest time a block may take to build, PoW
+// included, and post.
+fn read_post_timeout() -> Result<Duration, Error> {
+    let timeout_secs: u64 = read_env_parsed("POST_TIMEOUT_SECS")?
+        .unwrap_or(DEFAULT_POST_TIMEOUT_SECS);
+
+    if timeout_secs == 0 {
+        return Err(Error::Validation(String::from("POST_TIMEOUT_SECS: must be positive")));
+    }
+
+    Ok(Duration::from_secs(timeout_secs))
+}
+
+// Print the block that would be posted in dry-run mode: the tag and the data,
+// pretty-printed if it is JSON.
+fn print_dry_run_block(tag: &[u8], data: &[u8]) -> Result<(), Error> {
+    let data: String = String::from_utf8(data.to_vec())?;
+    let pretty_data: String = match serde_json::from_str::<serde_json::Value>(&data) {
+        Ok(value) => serde_json::to_string_pretty(&value)?,
+        Err(_err) => data,
+    };
+
+    println!("Tag: {}", String::from_utf8_lossy(tag));
+    println!("Data:\n{}", pretty_data);
+
+    Ok(())
+}
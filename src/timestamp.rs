@@ -0,0 +1,156 @@
+// Rust module to format the timestamps stored in the posted blocks.
+// The timestamps are read from the clock of the run: the wall clock, or with
+// SIM_TIME_SCALE set, a simulated clock running that many times as fast, so a
+// run of minutes can produce the telemetry of days.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::{str::FromStr, sync::OnceLock, time::{Duration, Instant}};
+
+use crate::{custom_error::Error, read_env_parsed};
+
+// Format of the timestamps stored in the posted blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampFormat {
+    // RFC 3339 in UTC, e.g. 2023-08-01T12:30:00.123Z.
+    #[default]
+    Rfc3339,
+    // Milliseconds since the Unix epoch.
+    UnixMillis,
+    // Seconds since the Unix epoch.
+    UnixSecs,
+}
+
+impl TimestampFormat {
+    // Read the timestamp format from TIMESTAMP_FORMAT, defaulting to RFC 3339.
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(read_env_parsed::<TimestampFormat>("TIMESTAMP_FORMAT")?.unwrap_or_default())
+    }
+}
+
+impl FromStr for TimestampFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "rfc3339" => Ok(TimestampFormat::Rfc3339),
+            "unix_millis" => Ok(TimestampFormat::UnixMillis),
+            "unix_secs" => Ok(TimestampFormat::UnixSecs),
+            _ => Err(String::from(
+                "unknown timestamp format, expected one of rfc3339, unix_millis, unix_secs"
+            )),
+        }
+    }
+}
+
+// A source of the current time for the timestamps of the posted blocks.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+// The wall clock.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// A clock starting at the given time and running scale times as fast as the
+// wall clock from then on. A reading taken an interval after the previous one
+// is interval * scale later.
+pub struct ScaledClock {
+    origin: DateTime<Utc>,
+    started: Instant,
+    scale: f64,
+}
+
+impl ScaledClock {
+    pub fn new(origin: DateTime<Utc>, started: Instant, scale: f64) -> Self {
+        Self { origin, started, scale }
+    }
+
+    // The simulated time at the given instant of the wall clock. A time past
+    // the range of the clock is clamped to its end rather than overflowing.
+    pub fn at(&self, instant: Instant) -> DateTime<Utc> {
+        let elapsed: Duration = Duration::try_from_secs_f64(
+            instant.saturating_duration_since(self.started).as_secs_f64() * self.scale
+        ).unwrap_or(Duration::MAX);
+
+        chrono::Duration::from_std(elapsed)
+            .ok()
+            .and_then(|elapsed| self.origin.checked_add_signed(elapsed))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+impl Clock for ScaledClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.at(Instant::now())
+    }
+}
+
+// Fastest the simulated clock may run, at which a second of the run is
+// stamped as about 11.5 days.
+pub const MAX_SIM_TIME_SCALE: f64 = 1_000_000.0;
+
+// Check the scale of the simulated clock: a positive number no larger than
+// MAX_SIM_TIME_SCALE.
+pub fn check_sim_time_scale(scale: f64) -> Result<(), Error> {
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err(Error::Validation(String::from(
+            "SIM_TIME_SCALE: must be a positive number"
+        )));
+    }
+
+    if scale > MAX_SIM_TIME_SCALE {
+        return Err(Error::Validation(format!(
+            "SIM_TIME_SCALE: must not exceed {}", MAX_SIM_TIME_SCALE
+        )));
+    }
+
+    Ok(())
+}
+
+// The clock of the run, created the first time a timestamp is taken.
+static CLOCK: OnceLock<Result<Box<dyn Clock>, String>> = OnceLock::new();
+
+// Create the clock from SIM_TIME_SCALE. A scale of 1, the default, is the wall
+// clock.
+fn clock_from_env() -> Result<Box<dyn Clock>, String> {
+    let scale: f64 = read_env_parsed::<f64>("SIM_TIME_SCALE")
+        .map_err(|err| err.to_string())?
+        .unwrap_or(1.0);
+
+    check_sim_time_scale(scale).map_err(|err| err.to_string())?;
+
+    if scale == 1.0 {
+        return Ok(Box::new(SystemClock));
+    }
+
+    Ok(Box::new(ScaledClock::new(Utc::now(), Instant::now(), scale)))
+}
+
+// The clock of the run. An invalid SIM_TIME_SCALE fails every timestamp, so
+// the first block of a run is never built.
+pub fn clock() -> Result<&'static dyn Clock, Error> {
+    CLOCK
+        .get_or_init(clock_from_env)
+        .as_ref()
+        .map(|clock| clock.as_ref())
+        .map_err(|err| Error::Anyhow(anyhow::Error::msg(err.to_owned())))
+}
+
+// The current time of the clock of the run, in the given format.
+pub fn now_timestamp(fmt: TimestampFormat) -> Result<String, Error> {
+    Ok(format_timestamp(clock()?.now(), fmt))
+}
+
+// A time in the given format.
+pub fn format_timestamp(now: DateTime<Utc>, fmt: TimestampFormat) -> String {
+    match fmt {
+        TimestampFormat::Rfc3339 => now.to_rfc3339_opts(SecondsFormat::Millis, true),
+        TimestampFormat::UnixMillis => now.timestamp_millis().to_string(),
+        TimestampFormat::UnixSecs => now.timestamp().to_string(),
+    }
+}
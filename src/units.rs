@@ -0,0 +1,80 @@
+// Rust module to convert metric values between measurement units.
+// Units are keyed on the measurement_unit strings of the posted metrics.
+
+use crate::custom_error::Error;
+
+const KELVIN_OFFSET: f64 = 273.15;
+
+pub fn celsius_to_fahrenheit(value: f64) -> f64 {
+    value * 9.0 / 5.0 + 32.0
+}
+
+pub fn fahrenheit_to_celsius(value: f64) -> f64 {
+    (value - 32.0) * 5.0 / 9.0
+}
+
+pub fn celsius_to_kelvin(value: f64) -> f64 {
+    value + KELVIN_OFFSET
+}
+
+pub fn kelvin_to_celsius(value: f64) -> f64 {
+    value - KELVIN_OFFSET
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+fn temperature_unit(unit: &str) -> Option<TemperatureUnit> {
+    match unit.trim().to_lowercase().as_str() {
+        "celsius" | "c" | "°c" => Some(TemperatureUnit::Celsius),
+        "fahrenheit" | "f" | "°f" => Some(TemperatureUnit::Fahrenheit),
+        "kelvin" | "k" => Some(TemperatureUnit::Kelvin),
+        _ => None,
+    }
+}
+
+// Name of a unit as recorded in measurement_unit, e.g. Fahrenheit for f.
+// Units without a known name are kept as given.
+pub fn canonical_unit(unit: &str) -> String {
+    match temperature_unit(unit) {
+        Some(TemperatureUnit::Celsius) => String::from("Celsius"),
+        Some(TemperatureUnit::Fahrenheit) => String::from("Fahrenheit"),
+        Some(TemperatureUnit::Kelvin) => String::from("Kelvin"),
+        None => unit.to_owned(),
+    }
+}
+
+// Convert a value from one measurement unit to another. A value is returned
+// as is when both units are the same, and units that cannot be converted into
+// each other are an error.
+pub fn convert(value: f64, from: &str, to: &str) -> Result<f64, Error> {
+    if from.trim().eq_ignore_ascii_case(to.trim()) {
+        return Ok(value);
+    }
+
+    let (from_unit, to_unit): (TemperatureUnit, TemperatureUnit) =
+        match (temperature_unit(from), temperature_unit(to)) {
+            (Some(from_unit), Some(to_unit)) => (from_unit, to_unit),
+            _ => return Err(Error::Anyhow(anyhow::Error::msg(format!(
+                "Cannot convert from {} to {}", from, to
+            )))),
+        };
+
+    let celsius: f64 = match from_unit {
+        TemperatureUnit::Celsius => value,
+        TemperatureUnit::Fahrenheit => fahrenheit_to_celsius(value),
+        TemperatureUnit::Kelvin => kelvin_to_celsius(value),
+    };
+
+    let converted: f64 = match to_unit {
+        TemperatureUnit::Celsius => celsius,
+        TemperatureUnit::Fahrenheit => celsius_to_fahrenheit(celsius),
+        TemperatureUnit::Kelvin => celsius_to_kelvin(celsius),
+    };
+
+    Ok(converted)
+}
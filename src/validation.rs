@@ -0,0 +1,451 @@
+// Rust module to validate the data of supply chain blocks.
+// Deserializing a block only guarantees its shape, so the invariants of each
+// block type are checked here before the data is used.
+
+use iota_sdk::types::block::address::Bech32Address;
+
+use crate::{
+    block_payload::{
+        BlockData, ExportLocation, PaymentInfo, PostedPaymentInfo, ProductInfo, Resource,
+        Resources,
+    },
+    cid::validate_cid,
+    custom_error::Error,
+    network::Network,
+    read_env_var, validate_block_id,
+};
+
+// Human-readable parts of the IOTA and Shimmer mainnet and testnet addresses.
+const KNOWN_BECH32_HRPS: [&str; 4] = ["iota", "atoi", "smr", "rms"];
+
+fn invalid_field(field: &str, reason: &str) -> Error {
+    Error::Validation(format!("{}: {}", field, reason))
+}
+
+// Prefix the field of a validation error with the field it is nested in.
+fn nested_field(err: Error, field: &str) -> Error {
+    match err {
+        Error::Validation(reason) => Error::Validation(format!("{}.{}", field, reason)),
+        other => invalid_field(field, &other.to_string()),
+    }
+}
+
+// Encrypted payment info can only be checked for its shape here. Its content
+// is checked once it is decrypted, before a delivery references it.
+fn validate_payment_info(payment_info: &PostedPaymentInfo, field: &str) -> Result<(), Error> {
+    let payment_info: &PaymentInfo = match payment_info {
+        PostedPaymentInfo::Clear(payment_info) => payment_info,
+        PostedPaymentInfo::Encrypted(encrypted) => {
+            if encrypted.ciphertext.trim().is_empty() {
+                return Err(invalid_field(
+                    &format!("{}.ciphertext", field), "must not be empty"
+                ));
+            }
+            if encrypted.nonce.trim().is_empty() {
+                return Err(invalid_field(&format!("{}.nonce", field), "must not be empty"));
+            }
+            return Ok(());
+        },
+    };
+
+    if payment_info.wallet_address.trim().is_empty() {
+        return Err(invalid_field(
+            &format!("{}.walletAddress", field), "must not be empty"
+        ));
+    }
+
+    if !payment_info.smr_cost.is_finite() || payment_info.smr_cost < 0.0 {
+        return Err(invalid_field(
+            &format!("{}.smrCost", field), "must be a non-negative number"
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_product_info(product_info: &ProductInfo, field: &str) -> Result<(), Error> {
+    if product_info.info.trim().is_empty() {
+        return Err(invalid_field(&format!("{}.info", field), "must not be empty"));
+    }
+
+    if let Some(file_cid) = &product_info.file_cid {
+        validate_cid(file_cid).map_err(|err| nested_field(err, field))?;
+    }
+
+    // A quantity only means something along with its unit.
+    if let Some(quantity) = product_info.quantity {
+        if !quantity.is_finite() || quantity < 0.0 {
+            return Err(invalid_field(
+                &format!("{}.quantity", field), "must be a non-negative number"
+            ));
+        }
+
+        if product_info.unit.as_deref().map_or(true, |unit| unit.trim().is_empty()) {
+            return Err(invalid_field(
+                &format!("{}.unit", field), "must be given along with the quantity"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_resources(resources: &Resources, field: &str) -> Result<(), Error> {
+    if resources.previous_blocks.len() != resources.transaction_receipts.len() {
+        return Err(invalid_field(
+            field,
+            &format!(
+                "has {} previous blocks but {} transaction receipts",
+                resources.previous_blocks.len(),
+                resources.transaction_receipts.len()
+            )
+        ));
+    }
+
+    Ok(())
+}
+
+// The resource of a consumer block, the block that ends the chain of a
+// shipment, must name the delivery it consumes along with its digest.
+fn validate_resource(resource: &Resource, field: &str) -> Result<(), Error> {
+    validate_block_id(resource.previous_block.as_str()).map_err(|err| {
+        invalid_field(&format!("{}.previousBlock", field), &err.to_string())
+    })?;
+
+    if resource.transaction_receipt.trim().is_empty() {
+        return Err(invalid_field(
+            &format!("{}.transactionReceipt", field), "must not be empty"
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_export_location(location: &ExportLocation, field: &str) -> Result<(), Error> {
+    location.validate().map_err(|err| nested_field(err, field))
+}
+
+// Check the invariants of a block's data. The returned error names the
+// offending field.
+pub fn validate_block_data(data: &BlockData) -> Result<(), Error> {
+    use BlockData::*;
+
+    match data {
+        RawMaterialsProducerBlockData(data) => {
+            validate_product_info(&data.material_info, "materialInfo")?;
+            validate_export_location(&data.export_location, "exportLocation")?;
+            validate_payment_info(&data.payment_info, "paymentInfo")?;
+        },
+        SupplierBlockData(data) => {
+            validate_product_info(
+                &data.processed_material_info, "processedMaterialInfo"
+            )?;
+            validate_resources(&data.resources, "resources")?;
+            validate_payment_info(&data.payment_info, "paymentInfo")?;
+        },
+        ManufacturerBlockData(data) => {
+            validate_product_info(&data.product_info, "productInfo")?;
+            validate_resources(&data.resources, "resources")?;
+            validate_payment_info(&data.payment_info, "paymentInfo")?;
+        },
+        DistributorBlockData(data) => {
+            validate_product_info(
+                &data.product_distribution_info, "productDistributionInfo"
+            )?;
+            validate_payment_info(&data.payment_info, "paymentInfo")?;
+        },
+        RetailerBlockData(data) => {
+            validate_product_info(&data.product_retail_info, "productRetailInfo")?;
+            validate_payment_info(&data.payment_info, "paymentInfo")?;
+        },
+        ConsumerBlockData(data) => {
+            validate_resource(&data.resource, "resource")?;
+        },
+        StartTransportationData(data) => {
+            validate_product_info(&data.transportation_info, "transportationInfo")?;
+        },
+        DeliveredTransportationData(data) => {
+            validate_product_info(
+                &data.product_delivery_info, "productDeliveryInfo"
+            )?;
+            validate_payment_info(&data.payment_info, "paymentInfo")?;
+        },
+        LocationMetricData(data) => {
+            data.validate()?;
+        },
+        _ => {},
+    };
+
+    Ok(())
+}
+
+// Check that the payment info of a delivery is payable: the wallet address
+// must be a valid bech32 address of the selected network (or of any IOTA or
+// Shimmer network when none is selected) and the cost must be positive.
+pub fn validate_delivery_payment_info(payment_info: &PaymentInfo) -> Result<(), Error> {
+    validate_wallet_address(&payment_info.wallet_address, "paymentInfo.walletAddress")?;
+
+    if !payment_info.smr_cost.is_finite() || payment_info.smr_cost <= 0.0 {
+        return Err(invalid_field("paymentInfo.smrCost", "must be positive"));
+    }
+
+    Ok(())
+}
+
+// Check that a wallet address is a valid bech32 address of the selected
+// network, or of any IOTA or Shimmer network when none is selected.
+fn validate_wallet_address(wallet_address: &str, field: &str) -> Result<(), Error> {
+    let address: Bech32Address = wallet_address.trim()
+        .parse::<Bech32Address>()
+        .map_err(|err| invalid_field(
+            field,
+            &format!("'{}' is not a valid bech32 address: {}", wallet_address, err)
+        ))?;
+
+    let hrp: String = address.hrp().to_string();
+    let valid_hrp: bool = match Network::from_env()? {
+        Some(network) => hrp == network.bech32_hrp(),
+        None => KNOWN_BECH32_HRPS.contains(&hrp.as_str()),
+    };
+
+    if !valid_hrp {
+        return Err(invalid_field(field, &format!("unexpected address prefix '{}'", hrp)));
+    }
+
+    Ok(())
+}
+
+// A check of block data run before the block is posted. Validators are
+// registered with a ValidatorRegistry and can be disabled by their name.
+pub trait PayloadValidator: Send + Sync {
+    // The name the validator is listed by in DISABLED_VALIDATORS.
+    fn name(&self) -> &'static str;
+
+    fn validate(&self, data: &BlockData) -> Result<(), Error>;
+}
+
+// The product infos of a block, along with the field each is named by.
+fn product_infos(data: &BlockData) -> Vec<(&ProductInfo, &'static str)> {
+    use BlockData::*;
+
+    match data {
+        RawMaterialsProducerBlockData(data) => vec![(&data.material_info, "materialInfo")],
+        SupplierBlockData(data) => {
+            vec![(&data.processed_material_info, "processedMaterialInfo")]
+        },
+        ManufacturerBlockData(data) => vec![(&data.product_info, "productInfo")],
+        DistributorBlockData(data) => {
+            vec![(&data.product_distribution_info, "productDistributionInfo")]
+        },
+        RetailerBlockData(data) => vec![(&data.product_retail_info, "productRetailInfo")],
+        StartTransportationData(data) => {
+            vec![(&data.transportation_info, "transportationInfo")]
+        },
+        DeliveredTransportationData(data) => {
+            vec![(&data.product_delivery_info, "productDeliveryInfo")]
+        },
+        _ => Vec::new(),
+    }
+}
+
+// The payment info of a block, if its type carries one.
+fn payment_info(data: &BlockData) -> Option<&PostedPaymentInfo> {
+    use BlockData::*;
+
+    match data {
+        RawMaterialsProducerBlockData(data) => Some(&data.payment_info),
+        SupplierBlockData(data) => Some(&data.payment_info),
+        ManufacturerBlockData(data) => Some(&data.payment_info),
+        DistributorBlockData(data) => Some(&data.payment_info),
+        RetailerBlockData(data) => Some(&data.payment_info),
+        DeliveredTransportationData(data) => Some(&data.payment_info),
+        _ => None,
+    }
+}
+
+fn validate_finite(value: f64, field: &str) -> Result<(), Error> {
+    if !value.is_finite() {
+        return Err(invalid_field(field, "must be a finite number"));
+    }
+
+    Ok(())
+}
+
+// Quantities and costs must be non-negative and readings finite.
+pub struct RangeValidator;
+
+impl PayloadValidator for RangeValidator {
+    fn name(&self) -> &'static str {
+        "ranges"
+    }
+
+    fn validate(&self, data: &BlockData) -> Result<(), Error> {
+        for (product_info, field) in product_infos(data) {
+            if let Some(quantity) = product_info.quantity {
+                if !quantity.is_finite() || quantity < 0.0 {
+                    return Err(invalid_field(
+                        &format!("{}.quantity", field), "must be a non-negative number"
+                    ));
+                }
+            }
+        }
+
+        if let Some(PostedPaymentInfo::Clear(payment_info)) = payment_info(data) {
+            if !payment_info.smr_cost.is_finite() || payment_info.smr_cost < 0.0 {
+                return Err(invalid_field(
+                    "paymentInfo.smrCost", "must be a non-negative number"
+                ));
+            }
+        }
+
+        match data {
+            BlockData::MetricData(data) => validate_finite(data.metric_value, "metricValue")?,
+            BlockData::BatchMetricData(data) => {
+                for (index, metric) in data.metrics.iter().enumerate() {
+                    validate_finite(
+                        metric.metric_value, &format!("metrics[{}].metricValue", index)
+                    )?;
+                }
+            },
+            BlockData::AlertData(data) => {
+                validate_finite(data.value, "value")?;
+                validate_finite(data.threshold_violated, "thresholdViolated")?;
+            },
+            BlockData::StuckSensorData(data) => validate_finite(data.value, "value")?,
+            _ => {},
+        };
+
+        Ok(())
+    }
+}
+
+// Wallet addresses in clear payment info must be bech32 addresses of the
+// selected network. Encrypted payment info cannot be checked before posting.
+pub struct AddressValidator;
+
+impl PayloadValidator for AddressValidator {
+    fn name(&self) -> &'static str {
+        "addresses"
+    }
+
+    fn validate(&self, data: &BlockData) -> Result<(), Error> {
+        match payment_info(data) {
+            Some(PostedPaymentInfo::Clear(payment_info)) => validate_wallet_address(
+                &payment_info.wallet_address, "paymentInfo.walletAddress"
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Export and sensor locations must be valid coordinates.
+pub struct CoordinateValidator;
+
+impl PayloadValidator for CoordinateValidator {
+    fn name(&self) -> &'static str {
+        "coordinates"
+    }
+
+    fn validate(&self, data: &BlockData) -> Result<(), Error> {
+        match data {
+            BlockData::RawMaterialsProducerBlockData(data) => {
+                validate_export_location(&data.export_location, "exportLocation")
+            },
+            BlockData::LocationMetricData(data) => data.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+// File CIDs attached to product infos must be valid IPFS CIDs.
+pub struct CidValidator;
+
+impl PayloadValidator for CidValidator {
+    fn name(&self) -> &'static str {
+        "cids"
+    }
+
+    fn validate(&self, data: &BlockData) -> Result<(), Error> {
+        for (product_info, field) in product_infos(data) {
+            if let Some(file_cid) = &product_info.file_cid {
+                validate_cid(file_cid).map_err(|err| nested_field(err, field))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The validators run on block data before it is posted, in the order they
+// are registered.
+pub struct ValidatorRegistry {
+    validators: Vec<Box<dyn PayloadValidator>>,
+}
+
+impl ValidatorRegistry {
+    // A registry without validators.
+    pub fn empty() -> Self {
+        Self { validators: Vec::new() }
+    }
+
+    // A registry with every built-in validator.
+    pub fn with_builtin() -> Self {
+        let mut registry: Self = Self::empty();
+        registry.register(Box::new(RangeValidator));
+        registry.register(Box::new(AddressValidator));
+        registry.register(Box::new(CoordinateValidator));
+        registry.register(Box::new(CidValidator));
+
+        registry
+    }
+
+    // The built-in validators without the ones listed in the comma-separated
+    // DISABLED_VALIDATORS.
+    pub fn from_env() -> Result<Self, Error> {
+        let mut registry: Self = Self::with_builtin();
+
+        if let Ok(value) = read_env_var("DISABLED_VALIDATORS".to_string()) {
+            for name in value.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                registry.disable(name).map_err(|err| {
+                    Error::Validation(format!("DISABLED_VALIDATORS: {}", err))
+                })?;
+            }
+        }
+
+        Ok(registry)
+    }
+
+    pub fn register(&mut self, validator: Box<dyn PayloadValidator>) {
+        self.validators.push(validator);
+    }
+
+    // Remove the validator with the given name. Naming a validator that is not
+    // registered is an error, so a typo does not leave it enabled unnoticed.
+    pub fn disable(&mut self, name: &str) -> Result<(), Error> {
+        let count: usize = self.validators.len();
+        self.validators.retain(|validator| validator.name() != name);
+
+        if self.validators.len() == count {
+            return Err(Error::Anyhow(anyhow::Error::msg(format!(
+                "unknown validator '{}', expected one of: {}",
+                name,
+                Self::with_builtin().names().join(", ")
+            ))));
+        }
+
+        Ok(())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.validators.iter().map(|validator| validator.name()).collect()
+    }
+
+    // Run every validator, failing with the error of the first one that fails.
+    pub fn validate(&self, data: &BlockData) -> Result<(), Error> {
+        for validator in &self.validators {
+            validator.validate(data)?;
+        }
+
+        Ok(())
+    }
+}
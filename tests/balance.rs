@@ -0,0 +1,28 @@
+// Checking the sender wallet balance against the cost of a delivery.
+
+use metrics_board_demo::balance::{balance_shortfall, smr_to_glow, BalanceCheck, Smr};
+
+#[test]
+fn costs_are_rounded_up_to_the_next_glow() {
+    assert_eq!(smr_to_glow(1.5), 1_500_000);
+    assert_eq!(smr_to_glow(0.0000001), 1);
+}
+
+#[test]
+fn a_balance_below_the_cost_falls_short() {
+    assert_eq!(balance_shortfall(1_000_000, 1.5), Some(500_000));
+    assert_eq!(balance_shortfall(1_500_000, 1.5), None);
+    assert_eq!(balance_shortfall(2_000_000, 1.5), None);
+}
+
+#[test]
+fn glow_amounts_are_shown_in_smr() {
+    assert_eq!(Smr(1_500_000).to_string(), "1.500000 SMR");
+}
+
+#[test]
+fn balance_checks_parse_case_insensitively() {
+    assert_eq!("warn".parse::<BalanceCheck>(), Ok(BalanceCheck::Warn));
+    assert_eq!("ERROR".parse::<BalanceCheck>(), Ok(BalanceCheck::Error));
+    assert!("fail".parse::<BalanceCheck>().is_err());
+}
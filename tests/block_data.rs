@@ -0,0 +1,165 @@
+// Wire format tests of the block payloads. BlockData is an untagged enum, so a
+// change to one of its structs can silently make a payload deserialize as
+// another variant. Every fixture must deserialize into its own variant and
+// survive a serialize, deserialize, serialize round-trip unchanged.
+
+use metrics_board_demo::block_payload::{
+    block_data_kind, check_schema_version, BlockData, TaggedDataPayload,
+    TransportationLeg, CURRENT_SCHEMA_VERSION,
+};
+use serde_json::Value;
+
+// Every fixture along with the kind of block data it must deserialize into.
+const FIXTURES: [(&str, &str); 14] = [
+    ("BasicBlockData", include_str!("fixtures/block_data/BasicBlockData.json")),
+    (
+        "RawMaterialsProducerBlockData",
+        include_str!("fixtures/block_data/RawMaterialsProducerBlockData.json")
+    ),
+    ("SupplierBlockData", include_str!("fixtures/block_data/SupplierBlockData.json")),
+    ("ManufacturerBlockData", include_str!("fixtures/block_data/ManufacturerBlockData.json")),
+    ("DistributorBlockData", include_str!("fixtures/block_data/DistributorBlockData.json")),
+    ("RetailerBlockData", include_str!("fixtures/block_data/RetailerBlockData.json")),
+    ("ConsumerBlockData", include_str!("fixtures/block_data/ConsumerBlockData.json")),
+    (
+        "StartTransportationData",
+        include_str!("fixtures/block_data/StartTransportationData.json")
+    ),
+    (
+        "DeliveredTransportationData",
+        include_str!("fixtures/block_data/DeliveredTransportationData.json")
+    ),
+    ("MetricData", include_str!("fixtures/block_data/MetricData.json")),
+    ("AlertData", include_str!("fixtures/block_data/AlertData.json")),
+    ("LocationMetricData", include_str!("fixtures/block_data/LocationMetricData.json")),
+    ("BatchMetricData", include_str!("fixtures/block_data/BatchMetricData.json")),
+    ("StuckSensorData", include_str!("fixtures/block_data/StuckSensorData.json")),
+];
+
+// Serialize block data and read it back as a JSON value, so floats are
+// compared the way they are written on the wire.
+fn to_wire_value(data: &BlockData) -> Value {
+    let json: String = serde_json::to_string(data).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn fixtures_deserialize_into_their_variant() {
+    for (kind, fixture) in FIXTURES {
+        let data: BlockData = serde_json::from_str(fixture)
+            .unwrap_or_else(|err| panic!("{} fixture does not deserialize: {}", kind, err));
+
+        assert_eq!(block_data_kind(&data), kind);
+    }
+}
+
+#[test]
+fn fixtures_round_trip_unchanged() {
+    for (kind, fixture) in FIXTURES {
+        let fixture_value: Value = serde_json::from_str(fixture).unwrap();
+
+        let data: BlockData = serde_json::from_str(fixture).unwrap();
+        let first: Value = to_wire_value(&data);
+        assert_eq!(first, fixture_value, "{} fixture changed when serialized", kind);
+
+        let data: BlockData = serde_json::from_value(first.clone()).unwrap();
+        assert_eq!(block_data_kind(&data), kind);
+        assert_eq!(to_wire_value(&data), first, "{} round-trip is not stable", kind);
+    }
+}
+
+#[test]
+fn json_looking_basic_block_data_stays_basic() {
+    // A string holding another variant's JSON is still plain basic block data.
+    let metric_json: &str = include_str!("fixtures/block_data/MetricData.json");
+    let data: BlockData = serde_json::from_value(Value::String(metric_json.to_owned())).unwrap();
+
+    match data {
+        BlockData::BasicBlockData(text) => assert_eq!(text, metric_json),
+        other => panic!("expected BasicBlockData, got {}", block_data_kind(&other)),
+    }
+}
+
+#[test]
+fn unknown_object_does_not_deserialize() {
+    let result: Result<BlockData, serde_json::Error> =
+        serde_json::from_str(r#"{ "unknownField": "value" }"#);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn unversioned_payload_reads_as_version_zero() {
+    let payload: TaggedDataPayload = serde_json::from_str(
+        r#"{ "blockType": "Basic", "data": "Basic block data" }"#
+    ).unwrap();
+
+    assert_eq!(payload.schema_version, 0);
+    assert!(check_schema_version(payload.schema_version).is_ok());
+}
+
+#[test]
+fn new_payload_is_stamped_with_the_current_version() {
+    let payload: TaggedDataPayload = TaggedDataPayload::new(
+        String::from("Basic"),
+        BlockData::BasicBlockData(String::from("Basic block data"))
+    );
+    let value: Value = serde_json::to_value(&payload).unwrap();
+
+    assert_eq!(value["schemaVersion"], CURRENT_SCHEMA_VERSION);
+}
+
+#[test]
+fn newer_schema_version_is_rejected() {
+    assert!(check_schema_version(CURRENT_SCHEMA_VERSION).is_ok());
+    assert!(check_schema_version(CURRENT_SCHEMA_VERSION + 1).is_err());
+}
+
+#[test]
+fn multi_leg_delivery_keeps_its_legs() {
+    let fixture: &str =
+        include_str!("fixtures/block_data/MultiLegDeliveredTransportationData.json");
+    let data: BlockData = serde_json::from_str(fixture).unwrap();
+
+    match &data {
+        BlockData::DeliveredTransportationData(delivery) => {
+            let legs: &Vec<TransportationLeg> = delivery.legs.as_ref().unwrap();
+            assert_eq!(legs.len(), 2);
+            assert_eq!(legs[1].delivery_block, None);
+        },
+        other => panic!("expected DeliveredTransportationData, got {}", block_data_kind(other)),
+    }
+
+    assert_eq!(to_wire_value(&data), serde_json::from_str::<Value>(fixture).unwrap());
+}
+
+#[test]
+fn kind_names_the_variant() {
+    for (kind, fixture) in FIXTURES {
+        let data: BlockData = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(data.kind(), kind);
+        assert!(data.to_string().starts_with(kind), "{}", data);
+    }
+}
+
+#[test]
+fn display_summarizes_the_identifying_field() {
+    let metric: BlockData =
+        serde_json::from_str(include_str!("fixtures/block_data/MetricData.json")).unwrap();
+    assert_eq!(
+        metric.to_string(),
+        "MetricData Temperature 21.5 Celsius at 2024-01-01T00:00:00.000Z"
+    );
+
+    let supplier: BlockData =
+        serde_json::from_str(include_str!("fixtures/block_data/SupplierBlockData.json")).unwrap();
+    assert_eq!(supplier.to_string(), "SupplierBlockData from Supplier");
+}
+
+#[test]
+fn display_shortens_long_basic_block_text() {
+    let data: BlockData = BlockData::BasicBlockData("a".repeat(100));
+
+    assert_eq!(data.to_string(), format!("BasicBlockData \"{}...\"", "a".repeat(40)));
+}
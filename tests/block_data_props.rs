@@ -0,0 +1,286 @@
+// Property tests of the untagged BlockData wire format. Serde picks the first
+// variant whose required fields are all present, so every generated payload
+// must deserialize back into the variant it was serialized from, and survive
+// the round-trip unchanged.
+
+use metrics_board_demo::block_payload::{
+    AlertData, BatchMetricData, BlockData, BlockRef, ConsumerBlockData, DeliveredTransportationData,
+    DistributorBlockData, EncryptedPaymentInfo, ExportLocation, LocationMetricData,
+    ManufacturerBlockData, MetricData, PaymentInfo, PostedPaymentInfo, ProductInfo,
+    RawMaterialsProducerBlockData, Resource, Resources, RetailerBlockData,
+    StartTransportationData, StuckSensorData, SupplierBlockData, TransportationLeg,
+};
+use proptest::{collection::vec, option, prelude::*};
+use serde_json::{json, Value};
+
+fn text() -> impl Strategy<Value = String> {
+    ".{0,16}"
+}
+
+const PREVIOUS_BLOCK: &str = "0xcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd";
+
+fn block_ref() -> impl Strategy<Value = BlockRef> {
+    "0x[0-9a-f]{64}".prop_map(|block_id| BlockRef::new(block_id).unwrap())
+}
+
+// Floats with two decimals, which are written and read back exactly. NaN and
+// the infinities are written as null and never posted.
+fn amount() -> impl Strategy<Value = f64> {
+    (-100_000_000i64..100_000_000).prop_map(|cents| cents as f64 / 100.0)
+}
+
+prop_compose! {
+    fn product_info()(
+        info in text(),
+        file_cid in option::of(text()),
+        quantity in option::of(amount()),
+        unit in option::of(text())
+    ) -> ProductInfo {
+        ProductInfo { info, file_cid, quantity, unit }
+    }
+}
+
+prop_compose! {
+    fn resource()(previous_block in block_ref(), transaction_receipt in text()) -> Resource {
+        Resource { previous_block, transaction_receipt }
+    }
+}
+
+prop_compose! {
+    fn resources()(
+        previous_blocks in vec(block_ref(), 0..3),
+        transaction_receipts in vec(text(), 0..3)
+    ) -> Resources {
+        Resources { previous_blocks, transaction_receipts }
+    }
+}
+
+fn payment_info() -> impl Strategy<Value = PostedPaymentInfo> {
+    prop_oneof![
+        (text(), amount()).prop_map(|(wallet_address, smr_cost)| {
+            PostedPaymentInfo::Clear(PaymentInfo { wallet_address, smr_cost })
+        }),
+        (text(), text()).prop_map(|(ciphertext, nonce)| {
+            PostedPaymentInfo::Encrypted(EncryptedPaymentInfo { ciphertext, nonce })
+        }),
+    ]
+}
+
+prop_compose! {
+    fn export_location()(longitude in -18_000i32..18_000, latitude in -9_000i32..9_000)
+        -> ExportLocation {
+        ExportLocation { longitude: longitude as f32 / 100.0, latitude: latitude as f32 / 100.0 }
+    }
+}
+
+prop_compose! {
+    fn metric_data()(
+        metric_type in text(),
+        metric_value in amount(),
+        measurement_unit in text(),
+        timestamp in text(),
+        previous_block in block_ref()
+    ) -> MetricData {
+        MetricData::new(metric_type, metric_value, measurement_unit, timestamp, previous_block)
+    }
+}
+
+prop_compose! {
+    fn transportation_leg()(
+        carrier in text(),
+        start_block in block_ref(),
+        delivery_block in option::of(block_ref())
+    ) -> TransportationLeg {
+        TransportationLeg { carrier, start_block, delivery_block }
+    }
+}
+
+prop_compose! {
+    fn distributor_block_data()(
+        distributor_info in text(),
+        product_distribution_info in product_info(),
+        resource in resource(),
+        payment_info in payment_info()
+    ) -> DistributorBlockData {
+        DistributorBlockData {
+            distributor_info, product_distribution_info, resource, payment_info
+        }
+    }
+}
+
+prop_compose! {
+    fn retailer_block_data()(
+        retailer_info in text(),
+        product_retail_info in product_info(),
+        payment_info in payment_info(),
+        resource in resource()
+    ) -> RetailerBlockData {
+        RetailerBlockData { retailer_info, product_retail_info, payment_info, resource }
+    }
+}
+
+prop_compose! {
+    fn delivered_transportation_data()(
+        product_delivery_info in product_info(),
+        delivery_timestamp in text(),
+        payment_info in payment_info(),
+        metrics in vec(text(), 0..4),
+        resolved_metrics in option::of(vec(metric_data(), 0..3)),
+        next_leg_previous_block in option::of(block_ref()),
+        legs in option::of(vec(transportation_leg(), 1..3))
+    ) -> DeliveredTransportationData {
+        let mut data: DeliveredTransportationData = DeliveredTransportationData::new(
+            product_delivery_info, delivery_timestamp, payment_info, metrics
+        );
+        data.resolved_metrics = resolved_metrics;
+        data.next_leg_previous_block = next_leg_previous_block;
+        data.legs = legs;
+        data
+    }
+}
+
+// Block data of every variant.
+fn block_data() -> impl Strategy<Value = BlockData> {
+    prop_oneof![
+        text().prop_map(BlockData::BasicBlockData),
+        (text(), product_info(), text(), export_location(), payment_info()).prop_map(
+            |(provider_info, material_info, export_timestamp, export_location, payment_info)| {
+                BlockData::RawMaterialsProducerBlockData(RawMaterialsProducerBlockData {
+                    provider_info, material_info, export_timestamp, export_location, payment_info
+                })
+            }
+        ),
+        (text(), product_info(), resources(), payment_info()).prop_map(
+            |(supplier_info, processed_material_info, resources, payment_info)| {
+                BlockData::SupplierBlockData(SupplierBlockData {
+                    supplier_info, processed_material_info, resources, payment_info
+                })
+            }
+        ),
+        (text(), product_info(), resources(), payment_info()).prop_map(
+            |(manufacturer_info, product_info, resources, payment_info)| {
+                BlockData::ManufacturerBlockData(ManufacturerBlockData {
+                    manufacturer_info, product_info, resources, payment_info
+                })
+            }
+        ),
+        distributor_block_data().prop_map(BlockData::DistributorBlockData),
+        retailer_block_data().prop_map(BlockData::RetailerBlockData),
+        (text(), resource()).prop_map(|(consumer_info, resource)| {
+            BlockData::ConsumerBlockData(ConsumerBlockData { consumer_info, resource })
+        }),
+        (text(), product_info(), text(), block_ref()).prop_map(
+            |(company_info, transportation_info, start_timestamp, previous_block)| {
+                BlockData::StartTransportationData(StartTransportationData::new(
+                    company_info, transportation_info, start_timestamp, previous_block
+                ))
+            }
+        ),
+        delivered_transportation_data().prop_map(BlockData::DeliveredTransportationData),
+        metric_data().prop_map(BlockData::MetricData),
+        (text(), amount(), amount(), text(), block_ref()).prop_map(
+            |(metric_type, value, threshold_violated, timestamp, previous_block)| {
+                BlockData::AlertData(AlertData::new(
+                    metric_type, value, threshold_violated, timestamp, previous_block
+                ))
+            }
+        ),
+        (export_location(), text(), block_ref()).prop_map(
+            |(location, timestamp, previous_block)| {
+                BlockData::LocationMetricData(LocationMetricData::new(
+                    location.longitude, location.latitude, timestamp, previous_block
+                ))
+            }
+        ),
+        (vec(metric_data(), 0..4), block_ref()).prop_map(|(metrics, previous_block)| {
+            BlockData::BatchMetricData(BatchMetricData::new(metrics, previous_block))
+        }),
+        (text(), amount(), any::<usize>(), text(), block_ref()).prop_map(
+            |(metric_type, value, consecutive_readings, timestamp, previous_block)| {
+                BlockData::StuckSensorData(StuckSensorData::new(
+                    metric_type, value, consecutive_readings, timestamp, previous_block
+                ))
+            }
+        ),
+    ]
+}
+
+// Serialize block data and read it back as a JSON value, so floats are
+// compared the way they are written on the wire.
+fn to_wire_value(data: &BlockData) -> Value {
+    let json: String = serde_json::to_string(data).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+// Serialize the data and deserialize it back. Returns the wire values written
+// before and after, along with the kind the data was read back as.
+fn round_trip(data: &BlockData) -> (Value, Value, &'static str) {
+    let json: String = serde_json::to_string(data).unwrap();
+    let read_back: BlockData = serde_json::from_str(&json).unwrap();
+
+    (to_wire_value(data), to_wire_value(&read_back), read_back.kind())
+}
+
+proptest! {
+    #[test]
+    fn every_variant_deserializes_into_itself(data in block_data()) {
+        let (written, read_back, kind): (Value, Value, &str) = round_trip(&data);
+
+        prop_assert_eq!(kind, data.kind());
+        prop_assert_eq!(read_back, written);
+    }
+
+    // Distributor and retailer data share their structure and differ only by
+    // field names, so they are generated from the same values.
+    #[test]
+    fn distributor_and_retailer_data_are_told_apart(
+        info in text(),
+        product in product_info(),
+        resource in resource(),
+        payment_info in payment_info()
+    ) {
+        let distributor: BlockData = BlockData::DistributorBlockData(DistributorBlockData {
+            distributor_info: info.clone(),
+            product_distribution_info: ProductInfo {
+                info: product.info.clone(),
+                file_cid: product.file_cid.clone(),
+                quantity: product.quantity,
+                unit: product.unit.clone(),
+            },
+            resource: Resource {
+                previous_block: resource.previous_block.clone(),
+                transaction_receipt: resource.transaction_receipt.clone(),
+            },
+            payment_info: payment_info.clone(),
+        });
+        let retailer: BlockData = BlockData::RetailerBlockData(RetailerBlockData {
+            retailer_info: info,
+            product_retail_info: product,
+            payment_info,
+            resource,
+        });
+
+        prop_assert_eq!(round_trip(&distributor).2, "DistributorBlockData");
+        prop_assert_eq!(round_trip(&retailer).2, "RetailerBlockData");
+    }
+}
+
+// Unknown fields are ignored, so an object carrying the fields of two
+// variants is read as the one declared first. This is the one ambiguity of
+// the format: such payloads are never written by the board, but a merged or
+// hand-edited payload is classified by declaration order.
+#[test]
+fn an_object_with_the_fields_of_two_variants_reads_as_the_first() {
+    let merged: Value = json!({
+        "distributorInfo": "Distributor",
+        "productDistributionInfo": { "info": "Pallets", "fileCid": null },
+        "retailerInfo": "Retailer",
+        "productRetailInfo": { "info": "Boxes", "fileCid": null },
+        "resource": { "previousBlock": PREVIOUS_BLOCK, "transactionReceipt": "0x02" },
+        "paymentInfo": { "walletAddress": "smr1", "smrCost": 1.0 },
+    });
+
+    let data: BlockData = serde_json::from_value(merged).unwrap();
+
+    assert_eq!(data.kind(), "DistributorBlockData");
+}
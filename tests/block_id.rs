@@ -0,0 +1,57 @@
+// Validation and normalization of the block ids given by users.
+
+use metrics_board_demo::{custom_error::Error, normalize_block_id, validate_block_id};
+
+const BLOCK_ID: &str = "0xabababababababababababababababababababababababababababababababab";
+
+#[test]
+fn trailing_newline_is_trimmed() {
+    let input: String = format!("{}\n", BLOCK_ID);
+
+    assert_eq!(normalize_block_id(&input).unwrap(), BLOCK_ID);
+    assert_eq!(normalize_block_id(&format!("  {}\r\n", BLOCK_ID)).unwrap(), BLOCK_ID);
+}
+
+#[test]
+fn hex_is_lowercased() {
+    let input: String = BLOCK_ID.to_uppercase();
+
+    assert_eq!(normalize_block_id(&input).unwrap(), BLOCK_ID);
+}
+
+#[test]
+fn embedded_whitespace_is_rejected() {
+    let input: String = format!("{} {}", &BLOCK_ID[..30], &BLOCK_ID[30..]);
+    let err: String = normalize_block_id(&input).unwrap_err().to_string();
+
+    assert!(err.contains("whitespace"), "{}", err);
+}
+
+#[test]
+fn empty_input_is_rejected() {
+    assert!(normalize_block_id(" \n").is_err());
+}
+
+#[test]
+fn each_failure_mode_has_its_own_error() {
+    let no_prefix: String = validate_block_id(&BLOCK_ID[2..]).unwrap_err().to_string();
+    assert!(no_prefix.contains("0x"), "{}", no_prefix);
+
+    let short: String = validate_block_id(&BLOCK_ID[..64]).unwrap_err().to_string();
+    assert!(short.contains("66 characters"), "{}", short);
+
+    let not_hex: String = format!("{}g", &BLOCK_ID[..65]);
+    let not_hex: String = validate_block_id(&not_hex).unwrap_err().to_string();
+    assert!(not_hex.contains("'g' at position 65"), "{}", not_hex);
+}
+
+#[test]
+fn valid_block_id_passes() {
+    assert!(validate_block_id(BLOCK_ID).is_ok());
+}
+
+#[test]
+fn malformed_block_id_is_a_typed_error() {
+    assert!(matches!(validate_block_id("abc"), Err(Error::InvalidBlockId(_))));
+    assert!(matches!(normalize_block_id(""), Err(Error::InvalidBlockId(_))));
+}
@@ -0,0 +1,163 @@
+// Chaining of the transportation and metric blocks, posted to an in-memory
+// block store instead of the Tangle.
+
+use iota_sdk::types::block::BlockId;
+use metrics_board_demo::{
+    block_payload::{
+        decode_block_data, BlockData, MetricData, TaggedDataPayload, CURRENT_SCHEMA_VERSION,
+    },
+    block_store::{BlockStore, MockStore},
+    collect_metric_chain,
+    custom_error::Error,
+    metric_source::{BoardRng, RandomSource},
+    post_metric, resolve_metric_chains, start_transportation, temperature_spec,
+    verify_continued_head, MetricSpec,
+};
+
+const INITIAL_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+
+#[tokio::test]
+async fn posted_blocks_read_back_unchanged() {
+    let store: MockStore = MockStore::new();
+
+    let first: String = store.post(b"Tag".to_vec(), b"data".to_vec()).await.unwrap();
+    let second: String = store.post(b"Tag".to_vec(), b"data".to_vec()).await.unwrap();
+
+    assert_ne!(first, second);
+    assert!(first.parse::<BlockId>().is_ok());
+    assert_eq!(store.get(&first).await.unwrap(), b"data".to_vec());
+    assert!(store.get(INITIAL_BLOCK).await.is_err());
+}
+
+#[tokio::test]
+async fn posted_block_data_is_stamped_with_the_current_schema_version() {
+    let store: MockStore = MockStore::new();
+
+    let start_block_id: BlockId =
+        start_transportation(&store, &INITIAL_BLOCK.to_owned(), "Carrier").await.unwrap();
+
+    let data: Vec<u8> = store.get(&start_block_id.to_string()).await.unwrap();
+    let payload: TaggedDataPayload = serde_json::from_slice(&data).unwrap();
+
+    assert_eq!(payload.schema_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(payload.block_type, "StartTransportationData");
+    assert!(matches!(payload.data, BlockData::StartTransportationData(_)));
+}
+
+#[tokio::test]
+async fn metric_chain_leads_back_to_the_start_block() {
+    let store: MockStore = MockStore::new();
+
+    let start_block_id: BlockId =
+        start_transportation(&store, &INITIAL_BLOCK.to_owned(), "Carrier").await.unwrap();
+
+    let start_data: BlockData =
+        decode_block_data(&store.get(&start_block_id.to_string()).await.unwrap()).unwrap();
+    match start_data {
+        BlockData::StartTransportationData(start) => {
+            assert_eq!(start.previous_block, INITIAL_BLOCK);
+            assert_eq!(start.transportation_company_info, "Carrier");
+        },
+        _ => panic!("expected StartTransportationData"),
+    }
+
+    let spec: MetricSpec = temperature_spec().unwrap();
+    let mut source: RandomSource = RandomSource::new(spec.min, spec.max, BoardRng::new(Some(1)));
+
+    let mut previous_block: BlockId = start_block_id;
+    let mut posted: Vec<MetricData> = Vec::new();
+    for _ in 0..3 {
+        let (block_id, metric_data): (BlockId, MetricData) =
+            post_metric(&store, &spec, &mut source, &previous_block.to_string()).await.unwrap();
+        previous_block = block_id;
+        posted.push(metric_data);
+    }
+
+    let chain: Vec<MetricData> =
+        collect_metric_chain(&store, &previous_block.to_string()).await.unwrap();
+
+    let values: Vec<f64> = chain.iter().map(|metric| metric.metric_value).collect();
+    let posted_values: Vec<f64> = posted.iter().map(|metric| metric.metric_value).collect();
+    assert_eq!(values, posted_values);
+    assert_eq!(chain[0].previous_block, start_block_id.to_string());
+    assert_eq!(store.blocks().len(), 4);
+}
+
+#[tokio::test]
+async fn a_pruned_block_cuts_a_metric_chain_short() {
+    let store: MockStore = MockStore::new();
+    let spec: MetricSpec = temperature_spec().unwrap();
+    let mut source: RandomSource = RandomSource::new(spec.min, spec.max, BoardRng::new(Some(1)));
+
+    // The chain starts from a block the store does not hold, as if pruned.
+    let (head, reading): (BlockId, MetricData) =
+        post_metric(&store, &spec, &mut source, &INITIAL_BLOCK.to_owned()).await.unwrap();
+
+    let chain: Vec<MetricData> = collect_metric_chain(&store, &head.to_string()).await.unwrap();
+    assert_eq!(chain.len(), 1);
+    assert_eq!(chain[0].metric_value, reading.metric_value);
+
+    let err: Error = collect_metric_chain(&store, INITIAL_BLOCK).await.unwrap_err();
+    assert!(matches!(err, Error::BlockNotFound(block_id) if block_id == INITIAL_BLOCK));
+}
+
+#[tokio::test]
+async fn metric_chains_resolve_in_the_order_of_their_heads() {
+    let store: MockStore = MockStore::new();
+    let spec: MetricSpec = temperature_spec().unwrap();
+    let mut source: RandomSource = RandomSource::new(spec.min, spec.max, BoardRng::new(Some(2)));
+
+    let start_block_id: String = start_transportation(&store, &INITIAL_BLOCK.to_owned(), "Carrier")
+        .await
+        .unwrap()
+        .to_string();
+
+    let mut heads: Vec<String> = Vec::new();
+    let mut posted: Vec<f64> = Vec::new();
+    for _ in 0..3 {
+        let (head, metric_data): (BlockId, MetricData) =
+            post_metric(&store, &spec, &mut source, &start_block_id).await.unwrap();
+        heads.push(head.to_string());
+        posted.push(metric_data.metric_value);
+    }
+
+    let readings: Vec<MetricData> = resolve_metric_chains(&store, &heads).await.unwrap();
+    let values: Vec<f64> = readings.iter().map(|metric| metric.metric_value).collect();
+    assert_eq!(values, posted);
+}
+
+#[tokio::test]
+async fn a_second_run_continues_the_metric_chain_of_the_first() {
+    let store: MockStore = MockStore::new();
+    let spec: MetricSpec = temperature_spec().unwrap();
+    let mut source: RandomSource = RandomSource::new(spec.min, spec.max, BoardRng::new(Some(1)));
+
+    let start_block_id: BlockId =
+        start_transportation(&store, &INITIAL_BLOCK.to_owned(), "Carrier").await.unwrap();
+    let (first_head, _reading): (BlockId, MetricData) =
+        post_metric(&store, &spec, &mut source, &start_block_id.to_string()).await.unwrap();
+
+    // Only a reading of the metric itself can be continued.
+    let err: String = verify_continued_head(
+        &store, "Temperature", &start_block_id.to_string(), "CONTINUE_TEMPERATURE_HEAD"
+    ).await.unwrap_err().to_string();
+    assert!(err.contains("is not a Temperature reading"), "{}", err);
+
+    let head: BlockId = verify_continued_head(
+        &store, "Temperature", &first_head.to_string(), "CONTINUE_TEMPERATURE_HEAD"
+    ).await.unwrap();
+    assert_eq!(head, first_head);
+
+    let err: String = verify_continued_head(
+        &store, "Humidity", &first_head.to_string(), "CONTINUE_HUMIDITY_HEAD"
+    ).await.unwrap_err().to_string();
+    assert!(err.contains("CONTINUE_HUMIDITY_HEAD"), "{}", err);
+
+    let (second_head, _reading): (BlockId, MetricData) =
+        post_metric(&store, &spec, &mut source, &head.to_string()).await.unwrap();
+    let chain: Vec<MetricData> =
+        collect_metric_chain(&store, &second_head.to_string()).await.unwrap();
+
+    assert_eq!(chain.len(), 2);
+    assert_eq!(chain[0].previous_block, start_block_id.to_string());
+}
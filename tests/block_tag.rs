@@ -0,0 +1,57 @@
+// Checks of the tags a run posts its blocks with, and their lookup by name.
+
+use metrics_board_demo::block_tag::{check_tags, max_tag_length, BlockTag};
+
+#[test]
+fn built_in_tags_are_valid() {
+    assert!(check_tags(&BlockTag::ALL).is_ok());
+}
+
+#[test]
+fn tag_longer_than_the_protocol_allows_is_rejected() {
+    let longest: BlockTag = BlockTag::Custom("t".repeat(max_tag_length()));
+    let too_long: BlockTag = BlockTag::Custom("t".repeat(max_tag_length() + 1));
+
+    assert!(check_tags(&[longest]).is_ok());
+
+    let err: String = check_tags(&[too_long]).unwrap_err().to_string();
+    assert!(err.contains("longer than"), "{}", err);
+}
+
+#[test]
+fn colliding_tags_are_rejected() {
+    let tags: [BlockTag; 2] = [
+        BlockTag::TemperatureMetric,
+        BlockTag::Custom(String::from("Temperature Metric Tag")),
+    ];
+
+    let err: String = check_tags(&tags).unwrap_err().to_string();
+    assert!(err.contains("used by both"), "{}", err);
+}
+
+#[test]
+fn empty_tag_is_rejected() {
+    assert!(check_tags(&[BlockTag::Custom(String::new())]).is_err());
+}
+
+#[test]
+fn tags_are_looked_up_by_name() {
+    assert_eq!(BlockTag::from_name("temperature metric tag"), BlockTag::TemperatureMetric);
+    assert_eq!(
+        BlockTag::from_name("Vibration Metric Tag"),
+        BlockTag::Custom(String::from("Vibration Metric Tag"))
+    );
+}
+
+#[test]
+fn every_built_in_tag_round_trips_through_its_bytes() {
+    for tag in BlockTag::ALL {
+        assert_eq!(BlockTag::from_bytes(&tag.as_bytes()), Some(tag));
+    }
+}
+
+#[test]
+fn unknown_tag_bytes_are_rejected() {
+    assert_eq!(BlockTag::from_bytes(b"Vibration Metric Tag"), None);
+    assert_eq!(BlockTag::from_bytes(b""), None);
+}
@@ -0,0 +1,88 @@
+// Breaches of the OK range of every metric type, summarized for the receipt.
+
+use metrics_board_demo::{
+    alert::AlertRange,
+    block_payload::{BlockRef, MetricData},
+    custom_error::Error,
+    stats::{summarize_breaches, Breach, BreachSummary},
+};
+use std::collections::BTreeMap;
+
+const PREVIOUS_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+
+fn reading(metric_type: &str, value: f64, timestamp: &str) -> MetricData {
+    MetricData::new(
+        String::from(metric_type),
+        value,
+        String::from(if metric_type == "Temperature" { "Celsius" } else { "Percent" }),
+        String::from(timestamp),
+        BlockRef::new(PREVIOUS_BLOCK).unwrap()
+    )
+}
+
+fn test_range(metric_type: &str) -> Result<AlertRange, Error> {
+    match metric_type {
+        "Temperature" => Ok(AlertRange { min: Some(2.0), max: Some(8.0) }),
+        _ => Ok(AlertRange { min: None, max: None }),
+    }
+}
+
+#[test]
+fn breaches_are_counted_with_the_one_furthest_beyond_its_bound() {
+    let readings: Vec<MetricData> = vec![
+        reading("Temperature", 5.0, "2024-01-01T00:00:00Z"),
+        reading("Temperature", 9.5, "2024-01-01T00:01:00Z"),
+        reading("Temperature", -1.0, "2024-01-01T00:02:00Z"),
+        reading("Temperature", 10.0, "2024-01-01T00:03:00Z"),
+    ];
+
+    let breaches: BTreeMap<String, BreachSummary> =
+        summarize_breaches(&readings, test_range).unwrap();
+
+    assert_eq!(
+        breaches["Temperature"],
+        BreachSummary {
+            readings: 4,
+            breaches: 3,
+            unit: String::from("Celsius"),
+            worst: Some(Breach {
+                value: -1.0,
+                threshold: 2.0,
+                timestamp: String::from("2024-01-01T00:02:00Z"),
+            }),
+        }
+    );
+}
+
+#[test]
+fn metrics_within_range_have_no_worst_breach() {
+    let readings: Vec<MetricData> = vec![reading("Temperature", 4.0, "2024-01-01T00:00:00Z")];
+
+    let breaches: BTreeMap<String, BreachSummary> =
+        summarize_breaches(&readings, test_range).unwrap();
+
+    assert_eq!(breaches["Temperature"].breaches, 0);
+    assert_eq!(breaches["Temperature"].worst, None);
+}
+
+#[test]
+fn metrics_without_a_range_are_left_out() {
+    let readings: Vec<MetricData> = vec![reading("Humidity", 99.0, "2024-01-01T00:00:00Z")];
+
+    let breaches: BTreeMap<String, BreachSummary> =
+        summarize_breaches(&readings, test_range).unwrap();
+
+    assert!(breaches.is_empty());
+}
+
+#[test]
+fn an_invalid_range_fails_the_summary() {
+    let readings: Vec<MetricData> = vec![reading("Temperature", 4.0, "2024-01-01T00:00:00Z")];
+
+    let result: Result<BTreeMap<String, BreachSummary>, Error> =
+        summarize_breaches(&readings, |_| {
+            Err(Error::Validation(String::from("TEMPERATURE_MAX_OK: not a number")))
+        });
+
+    assert!(result.is_err());
+}
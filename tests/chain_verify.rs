@@ -0,0 +1,100 @@
+// Walking the posted chains back to their start block.
+
+use iota_sdk::types::block::BlockId;
+use metrics_board_demo::{
+    block_payload::{BlockRef, LocationMetricData, MetricData},
+    block_store::{BlockStore, MockStore},
+    chain_verify::{verify_chain, verify_chains, VerifyReport},
+    start_transportation,
+};
+
+const INITIAL_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+
+async fn post_reading(store: &MockStore, metric_type: &str, previous_block: &str) -> String {
+    let metric_data: MetricData = MetricData::new(
+        metric_type.to_owned(),
+        21.5,
+        String::from("Celsius"),
+        String::from("2024-01-01T00:00:00Z"),
+        BlockRef::new(previous_block).unwrap()
+    );
+
+    store.post(b"Metric".to_vec(), serde_json::to_vec(&metric_data).unwrap()).await.unwrap()
+}
+
+async fn start_block(store: &MockStore) -> String {
+    let start_block_id: BlockId =
+        start_transportation(store, &INITIAL_BLOCK.to_owned(), "Carrier").await.unwrap();
+
+    start_block_id.to_string()
+}
+
+#[tokio::test]
+async fn an_intact_chain_leads_back_to_its_start_block() {
+    let store: MockStore = MockStore::new();
+    let start: String = start_block(&store).await;
+
+    let first: String = post_reading(&store, "Temperature", &start).await;
+    let head: String = post_reading(&store, "Temperature", &first).await;
+
+    let report: VerifyReport = verify_chain(&store, &head).await.unwrap();
+
+    assert!(report.is_intact());
+    assert_eq!(report.chain.as_deref(), Some("Temperature"));
+    assert_eq!(report.blocks_checked, 2);
+    assert_eq!(report.start_block, Some(start));
+}
+
+#[tokio::test]
+async fn a_link_to_a_missing_block_is_broken() {
+    let store: MockStore = MockStore::new();
+
+    let head: String = post_reading(&store, "Temperature", INITIAL_BLOCK).await;
+
+    let report: VerifyReport = verify_chain(&store, &head).await.unwrap();
+
+    assert!(!report.is_intact());
+    assert_eq!(report.broken_links.len(), 1);
+    assert_eq!(report.broken_links[0].block_id, head);
+    assert_eq!(report.broken_links[0].previous_block, INITIAL_BLOCK);
+    assert_eq!(
+        report.broken_links[0].reason,
+        "is not on the node, it was pruned or never posted"
+    );
+}
+
+#[tokio::test]
+async fn a_link_into_another_chain_is_broken() {
+    let store: MockStore = MockStore::new();
+    let start: String = start_block(&store).await;
+
+    let humidity: String = post_reading(&store, "Humidity", &start).await;
+    let head: String = post_reading(&store, "Temperature", &humidity).await;
+
+    let report: VerifyReport = verify_chain(&store, &head).await.unwrap();
+
+    assert_eq!(report.broken_links.len(), 1);
+    assert_eq!(report.broken_links[0].reason, "links to a block of the Humidity chain");
+}
+
+#[tokio::test]
+async fn every_chain_is_reported_in_order() {
+    let store: MockStore = MockStore::new();
+    let start: String = start_block(&store).await;
+
+    let temperature: String = post_reading(&store, "Temperature", &start).await;
+    let location: LocationMetricData = LocationMetricData::new(
+        23.7, 37.9, String::from("2024-01-01T00:00:00Z"), BlockRef::new(start.clone()).unwrap()
+    );
+    let location_head: String = store
+        .post(b"Location".to_vec(), serde_json::to_vec(&location).unwrap())
+        .await
+        .unwrap();
+
+    let reports: Vec<VerifyReport> =
+        verify_chains(&store, &[temperature, location_head, start]).await.unwrap();
+
+    assert!(reports.iter().all(|report| report.is_intact()));
+    assert_eq!(reports[1].chain.as_deref(), Some("Location"));
+    assert_eq!(reports[2].blocks_checked, 0);
+}
@@ -0,0 +1,95 @@
+// Saving, loading and removing the checkpoint a run is resumed from.
+
+use iota_sdk::types::block::BlockId;
+use metrics_board_demo::{checkpoint::RunCheckpoint, network::Network, receipt::ReceiptBlock};
+use std::{collections::BTreeMap, env, time::Duration};
+
+const START_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+const HEAD_BLOCK: &str = "0xcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd";
+
+fn checkpoint_path(name: &str) -> String {
+    env::temp_dir()
+        .join(format!("board-checkpoint-{}-{}.json", name, std::process::id()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn sample_checkpoint() -> RunCheckpoint {
+    let mut heads: BTreeMap<String, String> = BTreeMap::new();
+    heads.insert(String::from("Temperature"), String::from(HEAD_BLOCK));
+
+    RunCheckpoint {
+        initial_block_id: String::from(START_BLOCK),
+        leg_index: 0,
+        leg_previous_block: String::from(START_BLOCK),
+        start_block: String::from(START_BLOCK),
+        heads,
+        elapsed_secs: 42,
+        cycles: BTreeMap::from([(String::from("Temperature"), 3)]),
+        legs: Vec::new(),
+        reading_heads: Vec::new(),
+        blocks: vec![ReceiptBlock {
+            name: String::from("Start transportation, leg 1"),
+            block_id: String::from(START_BLOCK),
+            network: Some(Network::ShimmerTestnet),
+            explorer_url: Some(format!(
+                "https://explorer.shimmer.network/testnet/block/{}", START_BLOCK
+            )),
+        }],
+    }
+}
+
+#[test]
+fn a_saved_checkpoint_loads_back_unchanged() {
+    let path: String = checkpoint_path("round-trip");
+    let checkpoint: RunCheckpoint = sample_checkpoint();
+
+    checkpoint.save(&path).unwrap();
+    let loaded: RunCheckpoint = RunCheckpoint::load(&path).unwrap();
+    RunCheckpoint::remove(&path).unwrap();
+
+    assert_eq!(loaded, checkpoint);
+    assert_eq!(loaded.elapsed(), Duration::from_secs(42));
+    assert_eq!(loaded.cycles("Temperature"), 3);
+    assert_eq!(loaded.cycles("Location"), 0);
+}
+
+#[test]
+fn chains_without_a_head_start_from_the_start_block() {
+    let checkpoint: RunCheckpoint = sample_checkpoint();
+
+    assert_eq!(
+        checkpoint.head("Temperature").unwrap(),
+        HEAD_BLOCK.parse::<BlockId>().unwrap()
+    );
+    assert_eq!(
+        checkpoint.head("Location").unwrap(),
+        START_BLOCK.parse::<BlockId>().unwrap()
+    );
+}
+
+#[test]
+fn a_missing_checkpoint_cannot_be_resumed_but_is_removed() {
+    let path: String = checkpoint_path("missing");
+
+    assert!(RunCheckpoint::load(&path).is_err());
+    assert!(RunCheckpoint::remove(&path).is_ok());
+}
+
+#[test]
+fn blocks_keep_the_network_they_were_posted_to() {
+    let json: String = serde_json::to_string(&sample_checkpoint()).unwrap();
+    assert!(json.contains(r#""network":"shimmer-testnet""#), "{}", json);
+
+    let loaded: RunCheckpoint = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.blocks[0].network, Some(Network::ShimmerTestnet));
+}
+
+#[test]
+fn checkpoints_without_blocks_still_load() {
+    let mut checkpoint: serde_json::Value = serde_json::to_value(sample_checkpoint()).unwrap();
+    checkpoint.as_object_mut().unwrap().remove("blocks");
+
+    let loaded: RunCheckpoint = serde_json::from_value(checkpoint).unwrap();
+    assert!(loaded.blocks.is_empty());
+}
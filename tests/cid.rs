@@ -0,0 +1,59 @@
+// Syntax checks of the IPFS CIDs attached to product info.
+
+use metrics_board_demo::{
+    block_payload::{BlockData, ProductInfo},
+    cid::validate_cid,
+    validation::validate_block_data,
+};
+use serde_json::Value;
+
+#[test]
+fn valid_cids_of_every_supported_encoding_pass() {
+    // The same CIDv1 in base32, base58btc and base16.
+    for cid in [
+        "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+        "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+        "zdj7WWeQ43G6JJvLWQWZpyHuAMq6uYWRjkBXFad11vE2LHhQ7",
+        "f01701220c3c4733ec8affd06cf9e9ff50ffc6bcd2ec85a6170004bb709669c31de94391a",
+    ] {
+        assert!(validate_cid(cid).is_ok(), "{}", cid);
+    }
+}
+
+#[test]
+fn malformed_cids_are_rejected() {
+    for cid in [
+        "",
+        "QmCid",
+        "Qm0wAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+        "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbz",
+        "xafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+        "f0170122",
+    ] {
+        let err: String = validate_cid(cid).unwrap_err().to_string();
+        assert!(err.contains("fileCid"), "{}: {}", cid, err);
+    }
+}
+
+#[test]
+fn product_info_is_only_built_with_a_valid_cid() {
+    assert!(ProductInfo::new(String::from("Pallets"), Some(String::from("QmCid"))).is_err());
+
+    let product_info: ProductInfo = ProductInfo::new(
+        String::from("Pallets"),
+        Some(String::from("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"))
+    ).unwrap();
+    assert!(product_info.file_cid.is_some());
+}
+
+#[test]
+fn block_data_with_a_malformed_cid_fails_validation() {
+    let mut manufacturer: Value = serde_json::from_str(
+        include_str!("fixtures/block_data/ManufacturerBlockData.json")
+    ).unwrap();
+    manufacturer["productInfo"]["fileCid"] = Value::from("not-a-cid");
+    let manufacturer: BlockData = serde_json::from_value(manufacturer).unwrap();
+
+    let err: String = validate_block_data(&manufacturer).unwrap_err().to_string();
+    assert!(err.contains("productInfo.fileCid"), "{}", err);
+}
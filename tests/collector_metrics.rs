@@ -0,0 +1,33 @@
+// Rendering of the operational metrics in the Prometheus text format.
+
+use metrics_board_demo::collector_metrics::CollectorMetrics;
+use std::time::Duration;
+
+#[test]
+fn posted_and_failed_blocks_are_counted_per_tag() {
+    let metrics: CollectorMetrics = CollectorMetrics::new().unwrap();
+
+    metrics.record_posted("Temperature Metric Tag", Duration::from_millis(1500));
+    metrics.record_posted("Temperature Metric Tag", Duration::from_millis(500));
+    metrics.record_posting_error("Location Metric Tag");
+
+    let (content_type, body): (String, String) = metrics.render().unwrap();
+
+    assert!(content_type.starts_with("text/plain"), "{}", content_type);
+    assert!(body.contains(r#"board_blocks_posted_total{tag="Temperature Metric Tag"} 2"#), "{}", body);
+    assert!(body.contains(r#"board_posting_errors_total{tag="Location Metric Tag"} 1"#), "{}", body);
+    assert!(body.contains("board_block_posting_seconds_sum 2"), "{}", body);
+    assert!(body.contains("board_block_posting_seconds_count 2"), "{}", body);
+    assert!(body.contains("board_uptime_seconds"), "{}", body);
+}
+
+#[test]
+fn clones_share_their_counters() {
+    let metrics: CollectorMetrics = CollectorMetrics::new().unwrap();
+    let clone: CollectorMetrics = metrics.clone();
+
+    clone.record_posting_error("Stuck Sensor Tag");
+
+    let (_content_type, body): (String, String) = metrics.render().unwrap();
+    assert!(body.contains(r#"board_posting_errors_total{tag="Stuck Sensor Tag"} 1"#), "{}", body);
+}
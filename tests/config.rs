@@ -0,0 +1,46 @@
+// Settings resolved from the configuration into the values a run uses.
+
+use metrics_board_demo::{
+    block_payload::PaymentInfo,
+    config::{check_metric_count, AppConfig},
+    custom_error::Error,
+};
+
+#[test]
+fn configured_payment_info_needs_both_settings() {
+    let config: AppConfig = AppConfig {
+        payment_wallet_address: Some(String::from("smr1qexample")),
+        payment_smr_cost: Some(1.5),
+        ..Default::default()
+    };
+    let payment_info: PaymentInfo = config.payment_info().unwrap().unwrap();
+    assert_eq!(payment_info.wallet_address, "smr1qexample");
+    assert_eq!(payment_info.smr_cost, 1.5);
+
+    assert!(AppConfig::default().payment_info().unwrap().is_none());
+
+    let config: AppConfig = AppConfig {
+        payment_wallet_address: Some(String::from("smr1qexample")),
+        ..Default::default()
+    };
+    let err: String = config.payment_info().unwrap_err().to_string();
+    assert!(err.contains("PAYMENT_SMR_COST"), "{}", err);
+}
+
+#[test]
+fn a_block_cap_of_zero_turns_it_off() {
+    assert_eq!(AppConfig::with_defaults().max_blocks(), Some(10_000));
+
+    let config: AppConfig = AppConfig { max_blocks: Some(0), ..Default::default() };
+    assert_eq!(config.max_blocks(), None);
+}
+
+#[test]
+fn a_metric_count_replaces_the_total_duration() {
+    assert!(check_metric_count(Some(5), None).is_ok());
+    assert!(check_metric_count(None, Some(60)).is_ok());
+
+    let err: Error = check_metric_count(Some(5), Some(60)).unwrap_err();
+    assert!(err.to_string().contains("TOTAL_DURATION_SECS"), "{}", err);
+    assert!(matches!(check_metric_count(Some(0), None), Err(Error::Validation(_))));
+}
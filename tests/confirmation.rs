@@ -0,0 +1,44 @@
+// Waiting on a posted block until it is referenced.
+
+use metrics_board_demo::{confirmation::wait_until_referenced, custom_error::Error};
+use std::{cell::Cell, time::Duration};
+
+#[tokio::test]
+async fn the_wait_ends_once_the_block_is_referenced() {
+    let checks: Cell<u32> = Cell::new(0);
+
+    let result: Result<(), Error> = wait_until_referenced(
+        || {
+            checks.set(checks.get() + 1);
+            let referenced: bool = checks.get() >= 3;
+            async move { Ok::<bool, Error>(referenced) }
+        },
+        Duration::from_secs(5),
+        Duration::from_millis(1)
+    ).await;
+
+    assert!(result.is_ok());
+    assert_eq!(checks.get(), 3);
+}
+
+#[tokio::test]
+async fn a_block_never_referenced_times_out() {
+    let result: Result<(), Error> = wait_until_referenced(
+        || async { Ok::<bool, Error>(false) },
+        Duration::from_millis(20),
+        Duration::from_millis(5)
+    ).await;
+
+    assert!(matches!(result, Err(Error::Timeout(timeout)) if timeout == Duration::from_millis(20)));
+}
+
+#[tokio::test]
+async fn a_failing_check_fails_the_wait() {
+    let result: Result<(), Error> = wait_until_referenced(
+        || async { Err::<bool, Error>(Error::BlockNotFound(String::from("0x00"))) },
+        Duration::from_secs(5),
+        Duration::from_millis(1)
+    ).await;
+
+    assert!(matches!(result, Err(Error::BlockNotFound(_))));
+}
@@ -0,0 +1,77 @@
+// Posting the consumer block that closes the supply chain of a shipment.
+
+use iota_sdk::types::block::BlockId;
+use metrics_board_demo::{
+    block_payload::{decode_block_data, BlockData, ConsumerBlockData},
+    block_store::{BlockStore, MockStore},
+    custom_error::Error,
+    digest::payload_digest,
+    payload_format::PayloadFormat,
+    post_consumer_block,
+};
+
+const DELIVERY: &str = include_str!("fixtures/block_data/DeliveredTransportationData.json");
+
+#[tokio::test]
+async fn the_consumer_block_points_at_the_delivery_and_its_digest() {
+    let store: MockStore = MockStore::new();
+    let delivery: String = store
+        .post(b"Delivered Transportation Tag".to_vec(), DELIVERY.as_bytes().to_vec())
+        .await
+        .unwrap();
+
+    let block_id: BlockId = post_consumer_block(&store, "Consumer", &delivery).await.unwrap();
+
+    let data: Vec<u8> = store.get(&block_id.to_string()).await.unwrap();
+    let consumer: ConsumerBlockData = match decode_block_data(&data).unwrap() {
+        BlockData::ConsumerBlockData(consumer) => consumer,
+        other => panic!("expected ConsumerBlockData, got {}", other.kind()),
+    };
+
+    assert_eq!(consumer.consumer_info, "Consumer");
+    assert_eq!(consumer.resource.previous_block, delivery);
+    assert_eq!(consumer.resource.transaction_receipt, payload_digest(DELIVERY.as_bytes()));
+}
+
+#[tokio::test]
+async fn the_receipt_of_a_binary_delivery_is_the_digest_of_its_payload() {
+    let store: MockStore = MockStore::new();
+    let payload: Vec<u8> = PayloadFormat::Cbor.encode(DELIVERY.as_bytes()).unwrap();
+    let delivery: String =
+        store.post(b"Delivered Transportation Tag".to_vec(), payload.clone()).await.unwrap();
+
+    let block_id: BlockId = post_consumer_block(&store, "Consumer", &delivery).await.unwrap();
+
+    let data: Vec<u8> = store.get(&block_id.to_string()).await.unwrap();
+    let consumer: ConsumerBlockData = match decode_block_data(&data).unwrap() {
+        BlockData::ConsumerBlockData(consumer) => consumer,
+        other => panic!("expected ConsumerBlockData, got {}", other.kind()),
+    };
+
+    assert_eq!(consumer.resource.transaction_receipt, payload_digest(&payload));
+}
+
+#[tokio::test]
+async fn only_a_delivery_can_be_consumed() {
+    let store: MockStore = MockStore::new();
+    let consumer: String = store
+        .post(
+            b"Consumer Tag".to_vec(),
+            include_str!("fixtures/block_data/ConsumerBlockData.json").as_bytes().to_vec()
+        )
+        .await
+        .unwrap();
+
+    let err: Error = post_consumer_block(&store, "Consumer", &consumer).await.unwrap_err();
+    assert!(err.to_string().contains("is a ConsumerBlockData"), "{}", err);
+}
+
+#[tokio::test]
+async fn a_missing_delivery_is_not_found() {
+    let store: MockStore = MockStore::new();
+    let missing: String =
+        String::from("0xabababababababababababababababababababababababababababababababab");
+
+    let err: Error = post_consumer_block(&store, "Consumer", &missing).await.unwrap_err();
+    assert!(matches!(err, Error::BlockNotFound(_)), "{}", err);
+}
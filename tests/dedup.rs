@@ -0,0 +1,34 @@
+// Stuck sensor detection of the dedup module.
+
+use metrics_board_demo::dedup::{DuplicateAction, DuplicateTracker};
+
+#[test]
+fn repeated_readings_post_one_note_then_skip() {
+    let mut tracker: DuplicateTracker = DuplicateTracker::new(0.0, 3);
+
+    assert_eq!(tracker.observe(20.0), DuplicateAction::Post);
+    assert_eq!(tracker.observe(20.0), DuplicateAction::Post);
+    assert_eq!(tracker.observe(20.0), DuplicateAction::PostStuckSensorNote);
+    assert_eq!(tracker.consecutive_readings(), 3);
+    assert_eq!(tracker.observe(20.0), DuplicateAction::Skip);
+    assert_eq!(tracker.observe(20.0), DuplicateAction::Skip);
+}
+
+#[test]
+fn changed_reading_resets_the_repeats() {
+    let mut tracker: DuplicateTracker = DuplicateTracker::new(0.0, 2);
+
+    assert_eq!(tracker.observe(20.0), DuplicateAction::Post);
+    assert_eq!(tracker.observe(20.0), DuplicateAction::PostStuckSensorNote);
+    assert_eq!(tracker.observe(21.0), DuplicateAction::Post);
+    assert_eq!(tracker.consecutive_readings(), 1);
+}
+
+#[test]
+fn readings_within_epsilon_count_as_repeats() {
+    let mut tracker: DuplicateTracker = DuplicateTracker::new(0.1, 2);
+
+    assert_eq!(tracker.observe(20.0), DuplicateAction::Post);
+    assert_eq!(tracker.observe(20.05), DuplicateAction::PostStuckSensorNote);
+    assert_eq!(tracker.observe(20.5), DuplicateAction::Post);
+}
@@ -0,0 +1,15 @@
+// Payload digests of the digest module.
+
+use metrics_board_demo::digest::payload_digest;
+
+#[test]
+fn payload_digest_is_hex_sha256() {
+    assert_eq!(
+        payload_digest(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        payload_digest(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+}
@@ -0,0 +1,99 @@
+// Encryption of the payment info of posted payloads.
+
+use metrics_board_demo::{
+    block_payload::{BlockData, EncryptedPaymentInfo, PaymentInfo, PostedPaymentInfo},
+    encryption::{
+        decrypt_payment_info, encrypt_payment_info, open_payment_info, parse_encryption_key,
+        seal_payment_info,
+    },
+    validation::validate_block_data,
+};
+
+const KEY: [u8; 32] = [7; 32];
+const PREVIOUS_BLOCK: &str = "0xcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd";
+
+fn payment_info() -> PaymentInfo {
+    PaymentInfo {
+        wallet_address: String::from("rms1qqexampleaddress"),
+        smr_cost: 12.5,
+    }
+}
+
+#[test]
+fn encrypted_payment_info_decrypts_with_the_same_key() {
+    let encrypted: EncryptedPaymentInfo = encrypt_payment_info(&payment_info(), &KEY).unwrap();
+
+    assert!(!encrypted.ciphertext.contains("rms1qqexampleaddress"));
+
+    let decrypted: PaymentInfo = decrypt_payment_info(&encrypted, &KEY).unwrap();
+    assert_eq!(decrypted.wallet_address, "rms1qqexampleaddress");
+    assert_eq!(decrypted.smr_cost, 12.5);
+}
+
+#[test]
+fn every_encryption_uses_a_fresh_nonce() {
+    let first: EncryptedPaymentInfo = encrypt_payment_info(&payment_info(), &KEY).unwrap();
+    let second: EncryptedPaymentInfo = encrypt_payment_info(&payment_info(), &KEY).unwrap();
+
+    assert_ne!(first.nonce, second.nonce);
+    assert_ne!(first.ciphertext, second.ciphertext);
+}
+
+#[test]
+fn wrong_key_fails_to_decrypt() {
+    let encrypted: EncryptedPaymentInfo = encrypt_payment_info(&payment_info(), &KEY).unwrap();
+
+    assert!(decrypt_payment_info(&encrypted, &[8; 32]).is_err());
+}
+
+#[test]
+fn tampered_ciphertext_fails_to_decrypt() {
+    let mut encrypted: EncryptedPaymentInfo = encrypt_payment_info(&payment_info(), &KEY).unwrap();
+    encrypted.ciphertext = encrypted.ciphertext.chars().rev().collect();
+
+    assert!(decrypt_payment_info(&encrypted, &KEY).is_err());
+}
+
+#[test]
+fn payment_info_stays_in_cleartext_without_a_key() {
+    let posted: PostedPaymentInfo = seal_payment_info(payment_info(), None).unwrap();
+
+    assert!(matches!(posted, PostedPaymentInfo::Clear(_)));
+    assert_eq!(open_payment_info(posted, None).unwrap().smr_cost, 12.5);
+}
+
+#[test]
+fn encrypted_payment_info_needs_the_key_to_open() {
+    let posted: PostedPaymentInfo = seal_payment_info(payment_info(), Some(&KEY)).unwrap();
+
+    assert!(open_payment_info(posted.clone(), None).is_err());
+    assert_eq!(open_payment_info(posted, Some(&KEY)).unwrap().smr_cost, 12.5);
+}
+
+#[test]
+fn encrypted_payment_info_round_trips_through_a_payload() {
+    let posted: PostedPaymentInfo = seal_payment_info(payment_info(), Some(&KEY)).unwrap();
+    let json: String = format!(
+        r#"{{"retailerInfo":"Retailer","productRetailInfo":{{"info":"Retail","fileCid":null}},
+        "paymentInfo":{},"resource":{{"previousBlock":"{}","transactionReceipt":"0x00"}}}}"#,
+        serde_json::to_string(&posted).unwrap(),
+        PREVIOUS_BLOCK
+    );
+
+    let data: BlockData = serde_json::from_str(&json).unwrap();
+    assert!(validate_block_data(&data).is_ok());
+
+    match data {
+        BlockData::RetailerBlockData(data) => {
+            assert!(matches!(data.payment_info, PostedPaymentInfo::Encrypted(_)));
+        },
+        _ => panic!("expected a retailer block"),
+    };
+}
+
+#[test]
+fn key_must_be_base64_of_32_bytes() {
+    assert!(parse_encryption_key("BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=").is_ok());
+    assert!(parse_encryption_key("BwcHBw==").is_err());
+    assert!(parse_encryption_key("not base64!").is_err());
+}
@@ -0,0 +1,85 @@
+// Reporting the settings a command needs at startup.
+
+use metrics_board_demo::{
+    cli::{CliArgs, Command},
+    env_check::{command_settings, EnvReport, EnvSetting, EnvStatus},
+};
+use std::collections::HashMap;
+
+fn report(cli_args: &CliArgs, interactive: bool, vars: &[(&str, &str)]) -> EnvReport {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    EnvReport::from_lookup(command_settings(cli_args, interactive), |name| {
+        vars.get(name).cloned()
+    })
+}
+
+fn status<'a>(report: &'a EnvReport, label: &str) -> &'a EnvStatus {
+    report.entries
+        .iter()
+        .find(|(setting, _status)| setting.label() == label)
+        .map(|(_setting, status)| status)
+        .unwrap()
+}
+
+#[test]
+fn a_network_preset_provides_the_node_and_the_explorer() {
+    let report: EnvReport = report(
+        &CliArgs::default(),
+        false,
+        &[("NETWORK", "shimmer-testnet"), ("INITIAL_BLOCK_ID", "0xab")]
+    );
+
+    assert!(report.missing().is_empty());
+    assert_eq!(status(&report, "NETWORK or NODE_URLS or NODE_URL"), &EnvStatus::Set("NETWORK"));
+    assert_eq!(
+        status(&report, "MODE"),
+        &EnvStatus::Default(String::from("uniform"))
+    );
+    assert_eq!(status(&report, "SIGNING_KEY"), &EnvStatus::Unset);
+}
+
+#[test]
+fn a_node_without_an_explorer_is_enough() {
+    let report: EnvReport = report(
+        &CliArgs { command: Command::Info, ..CliArgs::default() },
+        false,
+        &[("NODE_URL", "https://api.testnet.shimmer.network")]
+    );
+
+    assert_eq!(
+        status(&report, "NETWORK or NODE_URLS or NODE_URL"),
+        &EnvStatus::Set("NODE_URL")
+    );
+    assert_eq!(status(&report, "EXPLORER_URL"), &EnvStatus::Unset);
+    assert!(report.missing().is_empty());
+}
+
+#[test]
+fn the_initial_block_is_only_required_when_nothing_else_provides_it() {
+    let vars: [(&str, &str); 1] = [("NETWORK", "shimmer")];
+
+    let unattended: EnvReport = report(&CliArgs::default(), false, &vars);
+    assert_eq!(unattended.missing(), vec![String::from("INITIAL_BLOCK_ID")]);
+
+    let interactive: EnvReport = report(&CliArgs::default(), true, &vars);
+    assert!(interactive.missing().is_empty());
+
+    let given: CliArgs = CliArgs { block_id: Some(String::from("0xab")), ..CliArgs::default() };
+    assert!(report(&given, false, &vars).missing().is_empty());
+
+    let resumed: CliArgs = CliArgs { resume: true, ..CliArgs::default() };
+    assert!(report(&resumed, false, &vars).missing().is_empty());
+}
+
+#[test]
+fn commands_without_the_node_need_no_settings() {
+    let schema: CliArgs =
+        CliArgs { command: Command::Schema { output: None }, ..CliArgs::default() };
+    let settings: Vec<EnvSetting> = command_settings(&schema, false);
+
+    assert!(settings.is_empty());
+}
@@ -0,0 +1,53 @@
+// Explorer block links built from EXPLORER_BLOCK_TEMPLATE, and the explorer
+// URLs they are built on.
+
+use metrics_board_demo::{
+    config::DEFAULT_EXPLORER_BLOCK_TEMPLATE,
+    custom_error::Error,
+    network::{check_explorer_url, explorer_block_link, parse_explorer_block_template},
+};
+
+const EXPLORER_URL: &str = "https://explorer.shimmer.network/shimmer";
+
+#[test]
+fn the_default_template_links_to_the_block_path() {
+    assert_eq!(
+        explorer_block_link(DEFAULT_EXPLORER_BLOCK_TEMPLATE, EXPLORER_URL, "0xab"),
+        "https://explorer.shimmer.network/shimmer/block/0xab"
+    );
+}
+
+#[test]
+fn query_string_templates_are_filled_in() {
+    assert_eq!(
+        explorer_block_link("{base}?block={block_id}", "https://explorer.example/", "0xab"),
+        "https://explorer.example?block=0xab"
+    );
+}
+
+#[test]
+fn templates_without_a_base_are_used_as_is() {
+    assert_eq!(
+        explorer_block_link("https://other.example/b/{block_id}", EXPLORER_URL, "0xab"),
+        "https://other.example/b/0xab"
+    );
+}
+
+#[test]
+fn templates_without_the_block_id_placeholder_are_rejected() {
+    assert!(matches!(
+        parse_explorer_block_template("{base}/block/"),
+        Err(Error::Validation(_))
+    ));
+    assert!(parse_explorer_block_template("{base}/block/{block_id}").is_ok());
+}
+
+#[test]
+fn explorer_urls_must_be_http_urls_with_a_host() {
+    assert!(check_explorer_url(EXPLORER_URL).is_ok());
+    assert!(check_explorer_url("http://localhost:8082").is_ok());
+
+    let err: String = check_explorer_url("explorer.example").unwrap_err().to_string();
+    assert!(err.contains("not an http(s) URL"), "{}", err);
+    assert!(check_explorer_url("https:///block").is_err());
+}
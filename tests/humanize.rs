@@ -0,0 +1,24 @@
+// Durations and costs formatted for the operator.
+
+use metrics_board_demo::humanize::{humanize_duration, humanize_smr};
+use std::time::Duration;
+
+#[test]
+fn durations_are_shown_to_a_tenth_of_a_second() {
+    assert_eq!(humanize_duration(Duration::from_millis(350)), "350ms");
+    assert_eq!(humanize_duration(Duration::from_millis(4_230)), "4.2s");
+    assert_eq!(humanize_duration(Duration::from_millis(83_400)), "1m 23.4s");
+    assert_eq!(humanize_duration(Duration::from_millis(3_725_000)), "1h 2m 5.0s");
+}
+
+#[test]
+fn a_duration_rounding_up_to_a_minute_carries_over() {
+    assert_eq!(humanize_duration(Duration::from_millis(59_960)), "1m 0.0s");
+}
+
+#[test]
+fn costs_carry_the_token_symbol_without_trailing_zeros() {
+    assert_eq!(humanize_smr(1.5), "1.5 SMR");
+    assert_eq!(humanize_smr(12.0), "12 SMR");
+    assert_eq!(humanize_smr(0.000125), "0.000125 SMR");
+}
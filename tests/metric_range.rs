@@ -0,0 +1,27 @@
+// Parsing of the min:max ranges of the generated metric readings.
+
+use metrics_board_demo::parse_metric_range;
+
+#[test]
+fn range_is_parsed() {
+    assert_eq!(parse_metric_range("0:100").unwrap(), (0.0, 100.0));
+    assert_eq!(parse_metric_range(" 20.5 : 45 ").unwrap(), (20.5, 45.0));
+}
+
+#[test]
+fn negative_range_is_parsed() {
+    assert_eq!(parse_metric_range("-25:-15").unwrap(), (-25.0, -15.0));
+}
+
+#[test]
+fn min_must_be_below_max() {
+    assert!(parse_metric_range("30:-5").is_err());
+    assert!(parse_metric_range("10:10").is_err());
+}
+
+#[test]
+fn malformed_range_is_rejected() {
+    assert!(parse_metric_range("0-100").is_err());
+    assert!(parse_metric_range("low:100").is_err());
+    assert!(parse_metric_range("0:inf").is_err());
+}
@@ -0,0 +1,62 @@
+// Fanning the posted readings out to the local sinks.
+
+use metrics_board_demo::{
+    block_payload::{BlockRef, MetricData},
+    custom_error::Error,
+    metric_sink::{MetricSink, MetricSinks},
+    metric_store::MetricStore,
+};
+
+const PREVIOUS_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+
+fn reading(metric_type: &str, value: f64) -> MetricData {
+    MetricData::new(
+        String::from(metric_type),
+        value,
+        String::from("Celsius"),
+        String::from("2024-01-01T00:00:00Z"),
+        BlockRef::new(PREVIOUS_BLOCK).unwrap()
+    )
+}
+
+struct FailingSink;
+
+impl MetricSink for FailingSink {
+    fn name(&self) -> &str {
+        "failing sink"
+    }
+
+    fn post(&self, _metric_data: &MetricData) -> Result<(), Error> {
+        Err(Error::Anyhow(anyhow::Error::msg("disk full")))
+    }
+}
+
+#[test]
+fn a_failing_sink_does_not_keep_readings_from_the_others() {
+    let store: MetricStore = MetricStore::new();
+    let sinks: MetricSinks = MetricSinks::new(vec![Box::new(FailingSink), Box::new(store.clone())]);
+
+    sinks.post(&reading("Temperature", 4.0));
+    sinks.post_all(&[reading("Temperature", 5.0), reading("Humidity", 60.0)]);
+
+    let values: Vec<f64> = store.all().iter().map(|metric| metric.metric_value).collect();
+    assert_eq!(values, vec![4.0, 5.0, 60.0]);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_readings_are_queried_by_metric_type() {
+    use metrics_board_demo::metric_sink::SqliteSink;
+
+    let sink: SqliteSink = SqliteSink::open(":memory:").unwrap();
+    sink.post(&reading("Temperature", 4.0)).unwrap();
+    sink.post(&reading("Humidity", 60.0)).unwrap();
+    sink.post(&reading("Temperature", 5.5)).unwrap();
+
+    let temperatures: Vec<MetricData> = sink.readings(Some("Temperature")).unwrap();
+    let values: Vec<f64> = temperatures.iter().map(|metric| metric.metric_value).collect();
+    assert_eq!(values, vec![4.0, 5.5]);
+    assert_eq!(temperatures[0].previous_block, PREVIOUS_BLOCK);
+
+    assert_eq!(sink.readings(None).unwrap().len(), 3);
+}
@@ -0,0 +1,124 @@
+// Random readings: drawn within their range at the configured precision.
+// Random-walk readings: every step stays within the range and moves from the
+// previous reading by at most the configured delta. Pushed readings: the
+// latest one is read until it gets too old.
+
+use metrics_board_demo::metric_source::{
+    gen_random_number_with, next_walk_value, parse_reading_payload, BoardRng, LatestReading,
+    MetricSource, WalkSource,
+};
+use std::time::{Duration, Instant};
+
+#[test]
+fn walk_steps_are_bounded() {
+    let mut rng: BoardRng = BoardRng::new(Some(7));
+    let mut value: f64 = 20.0;
+
+    for _ in 0..1000 {
+        let next: f64 = next_walk_value(value, 0.5, 0.0, 40.0, 2, &mut rng);
+
+        assert!((0.0..=40.0).contains(&next), "{} left the range", next);
+        // Rounding to two decimals can add up to half a hundredth.
+        assert!((next - value).abs() <= 0.5 + 0.005, "{} -> {} stepped too far", value, next);
+        value = next;
+    }
+}
+
+#[test]
+fn walk_is_clamped_at_the_bounds() {
+    let mut rng: BoardRng = BoardRng::new(Some(7));
+
+    for _ in 0..100 {
+        let at_min: f64 = next_walk_value(0.0, 5.0, 0.0, 1.0, 2, &mut rng);
+        let at_max: f64 = next_walk_value(1.0, 5.0, 0.0, 1.0, 2, &mut rng);
+
+        assert!((0.0..=1.0).contains(&at_min));
+        assert!((0.0..=1.0).contains(&at_max));
+    }
+}
+
+#[test]
+fn walk_source_continues_from_its_previous_reading() {
+    let mut source: WalkSource = WalkSource::new(0.0, 100.0, 1.0, BoardRng::new(Some(42)));
+    let mut previous: f64 = source.read("Temperature").unwrap();
+
+    for _ in 0..100 {
+        let value: f64 = source.read("Temperature").unwrap();
+
+        assert!((value - previous).abs() <= 1.0 + 0.005);
+        previous = value;
+    }
+}
+
+#[test]
+fn latest_reading_is_the_last_one_pushed() {
+    let latest: LatestReading = LatestReading::new();
+    assert!(latest.read("Temperature", None).is_err());
+
+    latest.update(21.5);
+    latest.update(22.0);
+
+    assert_eq!(latest.read("Temperature", None).unwrap(), 22.0);
+}
+
+#[test]
+fn stale_reading_is_rejected_only_with_a_max_age() {
+    let latest: LatestReading = LatestReading::new();
+    let received: Instant = Instant::now();
+    latest.update_at(21.5, received);
+
+    let later: Instant = received + Duration::from_secs(120);
+    assert_eq!(latest.read_at("Temperature", None, later).unwrap(), 21.5);
+    assert!(latest.read_at("Temperature", Some(Duration::from_secs(60)), later).is_err());
+    assert!(latest.read_at("Temperature", Some(Duration::from_secs(180)), later).is_ok());
+}
+
+#[test]
+fn reading_payload_is_a_number() {
+    assert_eq!(parse_reading_payload(b" 21.5\n").unwrap(), 21.5);
+    assert!(parse_reading_payload(b"warm").is_err());
+    assert!(parse_reading_payload(b"NaN").is_err());
+}
+
+#[test]
+fn random_numbers_stay_within_the_range_after_rounding() {
+    let mut rng: BoardRng = BoardRng::new(Some(3));
+
+    for _ in 0..1000 {
+        let value: f64 = gen_random_number_with(&mut rng, 0.001, 0.019, 2).unwrap();
+        assert!((0.001..=0.019).contains(&value), "{} left the range", value);
+
+        let value: f64 = gen_random_number_with(&mut rng, -5.0, 5.0, 1).unwrap();
+        assert!((-5.0..=5.0).contains(&value), "{} left the range", value);
+        assert_eq!((value * 10.0).round() / 10.0, value);
+    }
+}
+
+#[test]
+fn an_empty_range_yields_its_bound() {
+    let mut rng: BoardRng = BoardRng::new(Some(3));
+
+    assert_eq!(gen_random_number_with(&mut rng, 21.5, 21.5, 2).unwrap(), 21.5);
+}
+
+#[test]
+fn inverted_bounds_are_rejected() {
+    let mut rng: BoardRng = BoardRng::new(Some(3));
+
+    let err: String = gen_random_number_with(&mut rng, 10.0, 0.0, 2).unwrap_err().to_string();
+    assert!(err.contains("min must not exceed max"), "{}", err);
+    assert!(gen_random_number_with(&mut rng, f64::NAN, 1.0, 2).is_err());
+}
+
+#[test]
+fn equally_seeded_rngs_draw_the_same_numbers() {
+    let mut first: BoardRng = BoardRng::new(Some(11));
+    let mut second: BoardRng = BoardRng::new(Some(11));
+
+    for _ in 0..100 {
+        assert_eq!(
+            gen_random_number_with(&mut first, -5.0, 30.0, 2).unwrap(),
+            gen_random_number_with(&mut second, -5.0, 30.0, 2).unwrap()
+        );
+    }
+}
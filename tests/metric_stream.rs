@@ -0,0 +1,65 @@
+// Collecting the metric readings as a stream of posted blocks.
+
+use futures::{pin_mut, StreamExt};
+use iota_sdk::types::block::BlockId;
+use metrics_board_demo::{
+    block_payload::MetricData,
+    block_store::MockStore,
+    config::AppConfig,
+    custom_error::Error,
+    metric_stream::metric_stream,
+    start_transportation,
+};
+use std::collections::HashMap;
+
+const INITIAL_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+
+#[tokio::test]
+async fn every_chain_is_sampled_until_the_duration_elapses() {
+    let store: MockStore = MockStore::new();
+    let start: BlockId =
+        start_transportation(&store, &INITIAL_BLOCK.to_owned(), "Carrier").await.unwrap();
+
+    let config: AppConfig = AppConfig {
+        sampling_interval_secs: Some(1),
+        total_duration_secs: Some(2),
+        random_seed: Some(1),
+        ..AppConfig::default()
+    };
+
+    let stream = metric_stream(&store, &config, start).unwrap();
+    pin_mut!(stream);
+
+    // The head of every chain, which each reading must link to.
+    let mut heads: HashMap<String, String> = HashMap::new();
+    let mut readings: usize = 0;
+    while let Some(item) = stream.next().await {
+        let (block_id, metric_data): (BlockId, MetricData) = item.unwrap();
+        let head: String = heads
+            .insert(metric_data.metric_type.clone(), block_id.to_string())
+            .unwrap_or_else(|| start.to_string());
+
+        assert_eq!(metric_data.previous_block, head);
+        readings += 1;
+    }
+
+    // The four built-in metrics, sampled at 0s and 1s.
+    assert_eq!(heads.len(), 4);
+    assert_eq!(readings, 8);
+}
+
+#[tokio::test]
+async fn invalid_custom_metrics_fail_before_sampling() {
+    let store: MockStore = MockStore::new();
+    let config: AppConfig = AppConfig {
+        custom_metrics: Some(
+            serde_json::from_str(
+                r#"[{"name": "Temperature", "min": 0, "max": 1, "unit": "C", "tag": "T"}]"#
+            ).unwrap()
+        ),
+        ..AppConfig::default()
+    };
+
+    let result: Result<_, Error> = metric_stream(&store, &config, BlockId::null());
+    assert!(result.is_err());
+}
@@ -0,0 +1,47 @@
+// Warnings about the node a run posts to.
+
+use metrics_board_demo::{network::Network, node_info::node_warnings};
+
+const NOW: i64 = 1_700_000_000;
+
+#[test]
+fn healthy_synced_node_has_no_warnings() {
+    let warnings: Vec<String> = node_warnings(
+        true, Some((NOW - 5) as u32), "rms", Some(Network::ShimmerTestnet), NOW
+    );
+
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn unhealthy_node_is_warned_about() {
+    let warnings: Vec<String> = node_warnings(false, Some(NOW as u32), "rms", None, NOW);
+
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("unhealthy"));
+}
+
+#[test]
+fn stale_milestone_is_warned_about() {
+    let warnings: Vec<String> = node_warnings(true, Some((NOW - 600) as u32), "rms", None, NOW);
+
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("600 seconds old"));
+}
+
+#[test]
+fn node_of_another_network_is_warned_about() {
+    let warnings: Vec<String> = node_warnings(
+        true, Some(NOW as u32), "smr", Some(Network::ShimmerTestnet), NOW
+    );
+
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("smr"));
+}
+
+#[test]
+fn unknown_milestone_time_is_not_warned_about() {
+    let warnings: Vec<String> = node_warnings(true, None, "iota", Some(Network::Mainnet), NOW);
+
+    assert!(warnings.is_empty());
+}
@@ -0,0 +1,24 @@
+// Parsing the comma-separated node list of NODE_URLS.
+
+use metrics_board_demo::parse_node_urls;
+
+#[test]
+fn node_urls_are_split_on_commas_and_trimmed() {
+    assert_eq!(
+        parse_node_urls(" https://node-a.example , https://node-b.example:14265/ "),
+        vec![
+            String::from("https://node-a.example"),
+            String::from("https://node-b.example:14265/"),
+        ]
+    );
+}
+
+#[test]
+fn empty_entries_are_skipped() {
+    assert_eq!(
+        parse_node_urls(",https://node-a.example,, ,https://node-b.example,"),
+        vec![String::from("https://node-a.example"), String::from("https://node-b.example")]
+    );
+    assert!(parse_node_urls(" , ").is_empty());
+    assert!(parse_node_urls("").is_empty());
+}
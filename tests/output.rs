@@ -0,0 +1,60 @@
+// The JSON objects printed for the command results with --json.
+
+use metrics_board_demo::output::{CheckOutcome, JsonOutput, PostedBlock, PreflightReport};
+use serde_json::{json, Value};
+
+#[test]
+fn a_posted_block_is_named_by_its_operation() {
+    let posted_block: PostedBlock = PostedBlock {
+        block_id: String::from("0xab"),
+        tag: String::from("Temperature"),
+        digest: String::from("cd"),
+        elapsed_ms: 1200,
+        explorer_url: Some(String::from("https://explorer.example/block/0xab")),
+    };
+
+    let output: Value = serde_json::to_value(JsonOutput::Post(&posted_block)).unwrap();
+
+    assert_eq!(output, json!({
+        "operation": "post",
+        "blockId": "0xab",
+        "tag": "Temperature",
+        "digest": "cd",
+        "elapsedMs": 1200,
+        "explorerUrl": "https://explorer.example/block/0xab",
+    }));
+}
+
+#[test]
+fn a_preflight_report_lists_every_check() {
+    let report: PreflightReport = PreflightReport {
+        passed: false,
+        checks: vec![
+            CheckOutcome {
+                name: String::from("Node URLs resolve"),
+                status: String::from("ok"),
+                detail: None,
+            },
+            CheckOutcome {
+                name: String::from("Initial block id is valid"),
+                status: String::from("FAIL"),
+                detail: Some(String::from("Invalid BlockId: 0x")),
+            },
+        ],
+    };
+
+    let output: Value = serde_json::to_value(JsonOutput::Validate(&report)).unwrap();
+
+    assert_eq!(output, json!({
+        "operation": "validate",
+        "passed": false,
+        "checks": [
+            { "name": "Node URLs resolve", "status": "ok" },
+            {
+                "name": "Initial block id is valid",
+                "status": "FAIL",
+                "detail": "Invalid BlockId: 0x",
+            },
+        ],
+    }));
+}
@@ -0,0 +1,120 @@
+// Building block data field by field, and the fields build() requires.
+
+use metrics_board_demo::{
+    block_payload::{
+        BlockData, BlockRef, DeliveredTransportationData, MetricData, PaymentInfo,
+        PostedPaymentInfo, ProductInfo, StartTransportationData,
+    },
+    custom_error::Error,
+};
+use std::collections::HashMap;
+
+const PREVIOUS_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+
+#[test]
+fn start_transportation_fields_land_where_they_are_named() {
+    let data: StartTransportationData = StartTransportationData::builder()
+        .previous_block(PREVIOUS_BLOCK)
+        .start_timestamp("2024-01-01T00:00:00Z")
+        .company_info("Carrier")
+        .product_info(ProductInfo::new(String::from("Pallets"), None).unwrap())
+        .build()
+        .unwrap();
+
+    assert_eq!(data.transportation_company_info, "Carrier");
+    assert_eq!(data.transportation_info.info, "Pallets");
+    assert_eq!(data.start_timestamp, "2024-01-01T00:00:00Z");
+    assert_eq!(data.previous_block, PREVIOUS_BLOCK);
+}
+
+#[test]
+fn a_missing_required_field_is_named() {
+    let err: Error = StartTransportationData::builder()
+        .company_info("Carrier")
+        .product_info(ProductInfo::new(String::from("Pallets"), None).unwrap())
+        .previous_block(PREVIOUS_BLOCK)
+        .build()
+        .unwrap_err();
+
+    assert!(matches!(&err, Error::Validation(reason) if reason.starts_with("startTimestamp")));
+}
+
+#[test]
+fn metric_data_is_built_from_named_fields() {
+    let data: MetricData = MetricData::builder()
+        .metric_type("Temperature")
+        .metric_value(21.5)
+        .measurement_unit("celsius")
+        .timestamp("2024-01-01T00:00:00Z")
+        .previous_block(PREVIOUS_BLOCK)
+        .build()
+        .unwrap();
+
+    assert_eq!(data.metric_type, "Temperature");
+    assert_eq!(data.metric_value, 21.5);
+    assert_eq!(data.measurement_unit, "celsius");
+
+    let err: String = MetricData::builder().metric_type("Temperature").build()
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("metricValue"), "{}", err);
+}
+
+#[test]
+fn optional_delivery_fields_default_to_none() {
+    let payment_info: PostedPaymentInfo = PostedPaymentInfo::Clear(PaymentInfo {
+        wallet_address: String::from("smr1"),
+        smr_cost: 1.0,
+    });
+
+    let data: DeliveredTransportationData = DeliveredTransportationData::builder()
+        .product_info(ProductInfo::new(String::from("Pallets"), None).unwrap())
+        .delivery_timestamp("2024-01-02T00:00:00Z")
+        .payment_info(payment_info)
+        .metrics(vec![String::from("0x02")])
+        .next_leg_previous_block(Some(BlockRef::new(PREVIOUS_BLOCK).unwrap()))
+        .build()
+        .unwrap();
+
+    assert_eq!(data.metrics, vec![String::from("0x02")]);
+    assert_eq!(data.next_leg_previous_block.unwrap(), PREVIOUS_BLOCK);
+    assert!(data.resolved_metrics.is_none());
+    assert!(data.legs.is_none());
+}
+
+#[test]
+fn a_malformed_previous_block_is_rejected() {
+    let err: Error = MetricData::builder()
+        .metric_type("Temperature")
+        .metric_value(21.5)
+        .measurement_unit("celsius")
+        .timestamp("2024-01-01T00:00:00Z")
+        .previous_block("0x01")
+        .build()
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InvalidBlockId(_)));
+}
+
+#[test]
+fn metadata_is_posted_only_when_set() {
+    let metadata: HashMap<String, String> =
+        HashMap::from([(String::from("poNumber"), String::from("PO-1042"))]);
+    let builder = || MetricData::builder()
+        .metric_type("Temperature")
+        .metric_value(21.5)
+        .measurement_unit("celsius")
+        .timestamp("2024-01-01T00:00:00Z")
+        .previous_block(PREVIOUS_BLOCK);
+
+    let annotated: String =
+        serde_json::to_string(&builder().metadata(Some(metadata.clone())).build().unwrap())
+            .unwrap();
+    let data: BlockData = serde_json::from_str(&annotated).unwrap();
+    assert_eq!(data.kind(), "MetricData");
+    assert_eq!(data.metadata(), Some(&metadata));
+
+    let plain: String = serde_json::to_string(&builder().build().unwrap()).unwrap();
+    assert!(!plain.contains("metadata"), "{}", plain);
+    assert_eq!(serde_json::from_str::<BlockData>(&plain).unwrap().metadata(), None);
+}
@@ -0,0 +1,75 @@
+// Posting block data in a binary format and decoding it back to JSON.
+
+use metrics_board_demo::{
+    block_payload::{BlockData, BlockRef, MetricData},
+    payload_format::{decode_payload, PayloadFormat},
+};
+use serde_json::Value;
+
+fn metric_json() -> Vec<u8> {
+    let metric_data: MetricData = MetricData::new(
+        String::from("Temperature"),
+        21.57,
+        String::from("Celsius"),
+        String::from("2024-01-01T00:00:00Z"),
+        BlockRef::new("0xabababababababababababababababababababababababababababababababab").unwrap()
+    );
+
+    serde_json::to_vec(&metric_data).unwrap()
+}
+
+fn assert_round_trip(format: PayloadFormat) {
+    let json: Vec<u8> = metric_json();
+    let payload: Vec<u8> = format.encode(&json).unwrap();
+
+    assert_eq!(PayloadFormat::detect(&payload).unwrap(), format);
+
+    let decoded: Vec<u8> = decode_payload(payload).unwrap();
+    assert_eq!(
+        serde_json::from_slice::<Value>(&decoded).unwrap(),
+        serde_json::from_slice::<Value>(&json).unwrap()
+    );
+    assert!(matches!(
+        serde_json::from_slice::<BlockData>(&decoded).unwrap(),
+        BlockData::MetricData(_)
+    ));
+}
+
+#[test]
+fn json_payloads_round_trip_unchanged() {
+    let json: Vec<u8> = metric_json();
+
+    assert_eq!(PayloadFormat::Json.encode(&json).unwrap(), json);
+    assert_round_trip(PayloadFormat::Json);
+}
+
+#[test]
+fn cbor_payloads_round_trip() {
+    assert_round_trip(PayloadFormat::Cbor);
+}
+
+#[test]
+fn msgpack_payloads_round_trip() {
+    assert_round_trip(PayloadFormat::MessagePack);
+}
+
+#[test]
+fn binary_payloads_are_smaller_than_json() {
+    let json: Vec<u8> = metric_json();
+
+    assert!(PayloadFormat::Cbor.encode(&json).unwrap().len() < json.len());
+    assert!(PayloadFormat::MessagePack.encode(&json).unwrap().len() < json.len());
+}
+
+#[test]
+fn unknown_binary_formats_are_rejected() {
+    assert!(decode_payload(vec![0x00, b'x', 0x01]).is_err());
+    assert_eq!(decode_payload(b"plain text".to_vec()).unwrap(), b"plain text");
+}
+
+#[test]
+fn formats_are_parsed_by_name() {
+    assert_eq!("CBOR".parse::<PayloadFormat>(), Ok(PayloadFormat::Cbor));
+    assert_eq!("msgpack".parse::<PayloadFormat>(), Ok(PayloadFormat::MessagePack));
+    assert!("yaml".parse::<PayloadFormat>().is_err());
+}
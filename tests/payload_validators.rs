@@ -0,0 +1,101 @@
+// The payload validators run before a block is posted, and disabling them.
+
+use metrics_board_demo::{
+    block_payload::{BlockData, BlockRef, LocationMetricData, MetricData},
+    block_store::{post_block, MockStore},
+    custom_error::Error,
+    validation::{PayloadValidator, ValidatorRegistry},
+};
+
+const PREVIOUS_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+
+fn location(longitude: f32, latitude: f32) -> BlockData {
+    BlockData::LocationMetricData(LocationMetricData::new(
+        longitude,
+        latitude,
+        String::from("2024-01-01T00:00:00Z"),
+        BlockRef::new(PREVIOUS_BLOCK).unwrap()
+    ))
+}
+
+fn metric(value: f64) -> BlockData {
+    BlockData::MetricData(MetricData::new(
+        String::from("Temperature"),
+        value,
+        String::from("Celsius"),
+        String::from("2024-01-01T00:00:00Z"),
+        BlockRef::new(PREVIOUS_BLOCK).unwrap()
+    ))
+}
+
+struct RejectEverything;
+
+impl PayloadValidator for RejectEverything {
+    fn name(&self) -> &'static str {
+        "reject"
+    }
+
+    fn validate(&self, _data: &BlockData) -> Result<(), Error> {
+        Err(Error::Validation(String::from("data: rejected")))
+    }
+}
+
+#[test]
+fn every_builtin_validator_is_registered() {
+    assert_eq!(
+        ValidatorRegistry::with_builtin().names(),
+        vec!["ranges", "addresses", "coordinates", "cids"]
+    );
+}
+
+#[test]
+fn builtin_validators_reject_invalid_data() {
+    let registry: ValidatorRegistry = ValidatorRegistry::with_builtin();
+
+    assert!(registry.validate(&location(23.7, 37.9)).is_ok());
+    assert!(registry.validate(&metric(21.5)).is_ok());
+
+    let err: String = registry.validate(&location(23.7, 95.0)).unwrap_err().to_string();
+    assert!(err.contains("latitude"), "{}", err);
+
+    let err: String = registry.validate(&metric(f64::NAN)).unwrap_err().to_string();
+    assert!(err.contains("metricValue"), "{}", err);
+}
+
+#[test]
+fn a_disabled_validator_is_skipped() {
+    let mut registry: ValidatorRegistry = ValidatorRegistry::with_builtin();
+    registry.disable("coordinates").unwrap();
+
+    assert!(registry.validate(&location(23.7, 95.0)).is_ok());
+    assert!(registry.validate(&metric(f64::NAN)).is_err());
+}
+
+#[test]
+fn disabling_an_unknown_validator_fails() {
+    let err: String = ValidatorRegistry::with_builtin().disable("coordinate")
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("unknown validator 'coordinate'"), "{}", err);
+}
+
+#[test]
+fn registered_validators_run_in_order() {
+    let mut registry: ValidatorRegistry = ValidatorRegistry::empty();
+    registry.register(Box::new(RejectEverything));
+
+    assert_eq!(registry.names(), vec!["reject"]);
+    assert!(matches!(registry.validate(&metric(21.5)), Err(Error::Validation(_))));
+}
+
+#[tokio::test]
+async fn invalid_block_data_is_not_posted() {
+    let store: MockStore = MockStore::new();
+    let data: Vec<u8> = serde_json::to_vec(&location(190.0, 0.0)).unwrap();
+
+    assert!(post_block(&store, b"LocationMetric".to_vec(), data).await.is_err());
+
+    let data: Vec<u8> = serde_json::to_vec(&location(23.7, 37.9)).unwrap();
+    assert!(post_block(&store, b"LocationMetric".to_vec(), data).await.is_ok());
+}
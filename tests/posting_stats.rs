@@ -0,0 +1,20 @@
+// Posting time tally of the stats module.
+
+use metrics_board_demo::stats::PostingStats;
+use std::time::Duration;
+
+#[test]
+fn posting_stats_track_total_average_min_and_max() {
+    let mut stats: PostingStats = PostingStats::default();
+    assert_eq!(stats.average(), None);
+
+    stats.record(Duration::from_millis(300));
+    stats.record(Duration::from_millis(100));
+    stats.record(Duration::from_millis(200));
+
+    assert_eq!(stats.blocks_posted, 3);
+    assert_eq!(stats.total, Duration::from_millis(600));
+    assert_eq!(stats.average(), Some(Duration::from_millis(200)));
+    assert_eq!(stats.min, Some(Duration::from_millis(100)));
+    assert_eq!(stats.max, Some(Duration::from_millis(300)));
+}
@@ -0,0 +1,84 @@
+// The status line printed while a shipment run lasts, and the block cap of a run.
+
+use iota_sdk::types::block::BlockId;
+use metrics_board_demo::progress::{format_duration, format_status, ShipmentProgress};
+use std::{collections::BTreeMap, time::Duration};
+
+const BLOCK_ID: &str = "0xabababababababababababababababababababababababababababababababab";
+
+#[test]
+fn durations_are_formatted_by_their_largest_unit() {
+    assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+    assert_eq!(format_duration(Duration::from_secs(125)), "2m05s");
+    assert_eq!(format_duration(Duration::from_secs(3725)), "1h02m05s");
+}
+
+#[test]
+fn status_shows_elapsed_time_blocks_and_heads() {
+    let mut heads: BTreeMap<String, BlockId> = BTreeMap::new();
+    heads.insert(String::from("Temperature"), BLOCK_ID.parse::<BlockId>().unwrap());
+
+    let status: String = format_status(
+        Duration::from_secs(60), Duration::from_secs(120), 1, 1, 7, &heads
+    );
+
+    assert_eq!(
+        status,
+        "[##########----------] 1m00s/2m00s | 7 blocks posted | Temperature 0xabababab"
+    );
+}
+
+#[test]
+fn status_shows_the_leg_of_multi_leg_shipments() {
+    let status: String = format_status(
+        Duration::from_secs(10), Duration::from_secs(120), 2, 3, 0, &BTreeMap::new()
+    );
+
+    assert!(status.contains("| leg 2/3 |"), "{}", status);
+}
+
+#[test]
+fn elapsed_time_is_capped_at_the_total() {
+    let status: String = format_status(
+        Duration::from_secs(200), Duration::from_secs(120), 1, 1, 0, &BTreeMap::new()
+    );
+
+    assert!(status.starts_with("[####################] 2m00s/2m00s"), "{}", status);
+}
+
+#[test]
+fn the_block_cap_is_reached_after_max_blocks_chain_blocks() {
+    let block_id: BlockId = BLOCK_ID.parse::<BlockId>().unwrap();
+    let progress: ShipmentProgress = ShipmentProgress::new(Duration::from_secs(120), 1)
+        .with_max_blocks(Some(2));
+
+    progress.record_block("Temperature", block_id);
+    assert!(!progress.block_cap_reached());
+
+    progress.record_block("Location", block_id);
+    assert!(progress.block_cap_reached());
+}
+
+#[test]
+fn without_a_cap_sampling_never_stops_on_blocks() {
+    let progress: ShipmentProgress = ShipmentProgress::new(Duration::from_secs(120), 1);
+    progress.record_block("Temperature", BLOCK_ID.parse::<BlockId>().unwrap());
+
+    assert!(!progress.block_cap_reached());
+}
+
+#[test]
+fn the_cycles_of_a_resumed_leg_carry_over_until_a_new_leg_starts() {
+    let progress: ShipmentProgress = ShipmentProgress::new(Duration::from_secs(60), 2);
+    let cycles: BTreeMap<String, usize> = BTreeMap::from([(String::from("Temperature"), 3)]);
+
+    progress.resume_leg(1, Duration::from_secs(30), BTreeMap::new(), cycles);
+    progress.record_cycle("Temperature");
+    progress.record_cycle("Location");
+
+    assert_eq!(progress.cycles("Temperature"), 4);
+    assert_eq!(progress.cycles("Location"), 1);
+
+    progress.start_leg(2);
+    assert_eq!(progress.cycles("Temperature"), 0);
+}
@@ -0,0 +1,41 @@
+// The blocks of a shipment a block links back to, walked by a query.
+
+use metrics_board_demo::{block_payload::BlockData, query::shipment_links};
+
+fn fixture(json: &str) -> BlockData {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn a_delivery_links_to_its_chain_heads_and_legs() {
+    let delivery: BlockData =
+        fixture(include_str!("fixtures/block_data/MultiLegDeliveredTransportationData.json"));
+
+    assert_eq!(shipment_links(&delivery), vec![
+        "0xabababababababababababababababababababababababababababababababab",
+        "0xcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+        "0x1212121212121212121212121212121212121212121212121212121212121212",
+        "0x3434343434343434343434343434343434343434343434343434343434343434",
+        "0x5656565656565656565656565656565656565656565656565656565656565656",
+    ]);
+}
+
+#[test]
+fn a_chain_block_links_to_its_previous_block() {
+    let metric: BlockData = fixture(include_str!("fixtures/block_data/MetricData.json"));
+
+    match &metric {
+        BlockData::MetricData(data) => {
+            assert_eq!(shipment_links(&metric), vec![data.previous_block.to_string()]);
+        },
+        other => panic!("expected MetricData, got {}", other.kind()),
+    }
+}
+
+#[test]
+fn supply_chain_blocks_end_the_walk() {
+    let producer: BlockData =
+        fixture(include_str!("fixtures/block_data/RawMaterialsProducerBlockData.json"));
+
+    assert!(shipment_links(&producer).is_empty());
+}
@@ -0,0 +1,38 @@
+// The token bucket keeping the posts of a run within the quota of the node.
+
+use metrics_board_demo::rate_limit::TokenBucket;
+use std::time::{Duration, Instant};
+
+#[test]
+fn a_full_bucket_allows_a_minute_worth_of_posts_at_once() {
+    let now: Instant = Instant::now();
+    let mut bucket: TokenBucket = TokenBucket::new(3, now);
+
+    for _ in 0..3 {
+        assert!(bucket.try_take(now).is_ok());
+    }
+
+    // The next token comes after a third of a minute.
+    let wait: Duration = bucket.try_take(now).unwrap_err();
+    assert_eq!(wait.as_millis(), 20_000);
+}
+
+#[test]
+fn tokens_refill_over_time_up_to_the_capacity() {
+    let now: Instant = Instant::now();
+    let mut bucket: TokenBucket = TokenBucket::new(60, now);
+    for _ in 0..60 {
+        assert!(bucket.try_take(now).is_ok());
+    }
+    assert!(bucket.try_take(now).is_err());
+
+    assert!(bucket.try_take(now + Duration::from_secs(1)).is_ok());
+    assert!(bucket.try_take(now + Duration::from_secs(1)).is_err());
+
+    // An idle bucket never holds more than a minute worth of posts.
+    let later: Instant = now + Duration::from_secs(3600);
+    for _ in 0..60 {
+        assert!(bucket.try_take(later).is_ok());
+    }
+    assert!(bucket.try_take(later).is_err());
+}
@@ -0,0 +1,54 @@
+// Rechaining of recorded blocks to the ids they are replayed as.
+
+use metrics_board_demo::{
+    block_payload::{BlockData, BlockRef},
+    replay::remap_block_references,
+};
+use std::collections::HashMap;
+
+const RECORDED_START: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const REPLAYED_START: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+const INITIAL_BLOCK: &str = "0x3333333333333333333333333333333333333333333333333333333333333333";
+
+fn replayed() -> HashMap<String, String> {
+    HashMap::from([(String::from(RECORDED_START), String::from(REPLAYED_START))])
+}
+
+#[test]
+fn recorded_references_point_to_the_replayed_blocks() {
+    let mut data: BlockData = serde_json::from_value(serde_json::json!({
+        "metricType": "Temperature",
+        "metricValue": 21.5,
+        "measurementUnit": "Celsius",
+        "timestamp": "2024-01-01T00:00:00.000Z",
+        "previousBlock": RECORDED_START
+    })).unwrap();
+
+    remap_block_references(&mut data, &replayed());
+
+    match data {
+        BlockData::MetricData(metric_data) => {
+            assert_eq!(metric_data.previous_block, REPLAYED_START)
+        },
+        _ => panic!("expected MetricData"),
+    }
+}
+
+#[test]
+fn references_outside_the_record_are_kept() {
+    let mut data: BlockData = serde_json::from_str(
+        include_str!("fixtures/block_data/StartTransportationData.json")
+    ).unwrap();
+    if let BlockData::StartTransportationData(start) = &mut data {
+        start.previous_block = BlockRef::new(INITIAL_BLOCK).unwrap();
+    }
+
+    remap_block_references(&mut data, &replayed());
+
+    match data {
+        BlockData::StartTransportationData(start) => {
+            assert_eq!(start.previous_block, INITIAL_BLOCK)
+        },
+        _ => panic!("expected StartTransportationData"),
+    }
+}
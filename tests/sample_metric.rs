@@ -0,0 +1,40 @@
+// Sampling a metric reading from its source, chained to the previous block.
+
+use metrics_board_demo::{
+    block_payload::MetricData,
+    block_tag::BlockTag,
+    metric_source::{BoardRng, RandomSource},
+    sample_metric, MetricSpec,
+};
+
+const PREVIOUS_BLOCK: &str = "0xabababababababababababababababababababababababababababababababab";
+
+fn pressure_spec() -> MetricSpec {
+    MetricSpec {
+        metric_type: String::from("Pressure"),
+        min: 950.0,
+        max: 1050.0,
+        unit: String::from("hPa"),
+        tag: BlockTag::PressureMetric,
+        sampling_interval: None,
+    }
+}
+
+#[test]
+fn sampled_reading_is_of_the_spec_and_within_its_range() {
+    let spec: MetricSpec = pressure_spec();
+    let mut source: RandomSource = RandomSource::new(spec.min, spec.max, BoardRng::new(Some(5)));
+
+    for _ in 0..100 {
+        let metric_data: MetricData =
+            sample_metric(&spec, &mut source, &String::from(PREVIOUS_BLOCK)).unwrap();
+
+        assert_eq!(metric_data.metric_type, "Pressure");
+        assert_eq!(metric_data.measurement_unit, "hPa");
+        assert_eq!(metric_data.previous_block, PREVIOUS_BLOCK);
+        assert!(
+            (spec.min..=spec.max).contains(&metric_data.metric_value),
+            "{} left the range", metric_data.metric_value
+        );
+    }
+}
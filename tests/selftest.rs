@@ -0,0 +1,59 @@
+// Comparing the self-test block read back from the node with the one posted.
+
+use metrics_board_demo::{
+    block_payload::BlockData,
+    output::{JsonOutput, SelftestReport},
+    selftest::{check_round_trip, selftest_data, SELFTEST_TAG},
+};
+use serde_json::{json, Value};
+
+#[test]
+fn a_block_read_back_unchanged_passes() {
+    let tag: &[u8] = SELFTEST_TAG.as_bytes();
+    let data: BlockData = selftest_data("2024-01-01T00:00:00Z");
+    let posted: Vec<u8> = serde_json::to_vec(&data).unwrap();
+
+    assert!(check_round_trip(tag, &data, tag, &posted).is_ok());
+}
+
+#[test]
+fn changed_data_or_tag_fails_the_round_trip() {
+    let tag: &[u8] = SELFTEST_TAG.as_bytes();
+    let data: BlockData = selftest_data("2024-01-01T00:00:00Z");
+    let other: Vec<u8> =
+        serde_json::to_vec(&selftest_data("2024-01-01T00:00:01Z")).unwrap();
+
+    let err: String = check_round_trip(tag, &data, tag, &other).unwrap_err().to_string();
+    assert!(err.contains("data read back as"), "{}", err);
+
+    let posted: Vec<u8> = serde_json::to_vec(&data).unwrap();
+    let err: String = check_round_trip(tag, &data, b"Other Tag", &posted)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("tag read back as 'Other Tag'"), "{}", err);
+}
+
+#[test]
+fn undecodable_data_fails_the_round_trip() {
+    let tag: &[u8] = SELFTEST_TAG.as_bytes();
+    let data: BlockData = selftest_data("2024-01-01T00:00:00Z");
+
+    assert!(check_round_trip(tag, &data, tag, b"{").is_err());
+}
+
+#[test]
+fn a_failed_selftest_reports_only_what_it_reached() {
+    let report: SelftestReport = SelftestReport {
+        passed: false,
+        failure: Some(String::from("DRY_RUN is set")),
+        ..SelftestReport::default()
+    };
+
+    let output: Value = serde_json::to_value(JsonOutput::Selftest(&report)).unwrap();
+
+    assert_eq!(output, json!({
+        "operation": "selftest",
+        "passed": false,
+        "failure": "DRY_RUN is set",
+    }));
+}
@@ -0,0 +1,89 @@
+// Signing posted payloads and verifying the embedded signatures.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::SigningKey;
+use metrics_board_demo::{
+    block_payload::{BlockData, BlockRef, MetricData},
+    signing::{parse_signing_key, sign_payload, verify_payload, PayloadSignature},
+};
+use serde_json::Value;
+
+// Base64 of 32 bytes of 7.
+const KEY: &str = "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=";
+
+fn metric_data() -> MetricData {
+    MetricData::new(
+        String::from("Temperature"),
+        21.5,
+        String::from("Celsius"),
+        String::from("2024-01-01T00:00:00Z"),
+        BlockRef::new("0xabababababababababababababababababababababababababababababababab").unwrap()
+    )
+}
+
+fn signed_metric() -> Vec<u8> {
+    let key: SigningKey = parse_signing_key(KEY).unwrap();
+    sign_payload(&serde_json::to_vec(&metric_data()).unwrap(), &key).unwrap()
+}
+
+#[test]
+fn a_signed_payload_verifies_with_its_public_key() {
+    let signature: PayloadSignature = verify_payload(&signed_metric()).unwrap().unwrap();
+
+    let key: SigningKey = parse_signing_key(KEY).unwrap();
+    assert!(signature.valid);
+    assert_eq!(signature.public_key, STANDARD.encode(key.verifying_key().to_bytes()));
+}
+
+#[test]
+fn the_signature_does_not_depend_on_the_field_order() {
+    let mut object: serde_json::Map<String, Value> =
+        serde_json::from_slice(&signed_metric()).unwrap();
+    let value: Value = object.remove("value").unwrap();
+    let mut reordered: serde_json::Map<String, Value> = serde_json::Map::new();
+    reordered.insert(String::from("value"), value);
+    reordered.extend(object);
+
+    let signature: PayloadSignature =
+        verify_payload(&serde_json::to_vec(&reordered).unwrap()).unwrap().unwrap();
+    assert!(signature.valid);
+}
+
+#[test]
+fn a_tampered_payload_fails_verification() {
+    let mut object: Value = serde_json::from_slice(&signed_metric()).unwrap();
+    object["value"] = Value::from(35.0);
+
+    let signature: PayloadSignature =
+        verify_payload(&serde_json::to_vec(&object).unwrap()).unwrap().unwrap();
+    assert!(!signature.valid);
+}
+
+#[test]
+fn unsigned_payloads_have_no_signature() {
+    let data: Vec<u8> = serde_json::to_vec(&metric_data()).unwrap();
+
+    assert_eq!(verify_payload(&data).unwrap(), None);
+    assert_eq!(verify_payload(b"not json").unwrap(), None);
+}
+
+#[test]
+fn a_signature_without_a_public_key_is_rejected() {
+    let mut object: Value = serde_json::from_slice(&signed_metric()).unwrap();
+    object.as_object_mut().unwrap().remove("publicKey");
+
+    assert!(verify_payload(&serde_json::to_vec(&object).unwrap()).is_err());
+}
+
+#[test]
+fn a_signed_payload_still_reads_as_its_block_data() {
+    let block_data: BlockData = serde_json::from_slice(&signed_metric()).unwrap();
+
+    assert!(matches!(block_data, BlockData::MetricData(_)));
+}
+
+#[test]
+fn signing_keys_must_be_32_bytes() {
+    assert!(parse_signing_key("BwcHBw==").is_err());
+    assert!(parse_signing_key("not base64!").is_err());
+}
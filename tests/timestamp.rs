@@ -0,0 +1,46 @@
+// The clocks the block timestamps are read from, and their formats.
+
+use chrono::{DateTime, TimeZone, Utc};
+use metrics_board_demo::timestamp::{
+    check_sim_time_scale, format_timestamp, ScaledClock, TimestampFormat, MAX_SIM_TIME_SCALE,
+};
+use std::time::{Duration, Instant};
+
+#[test]
+fn a_scaled_clock_advances_by_the_interval_times_the_scale() {
+    let origin: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let started: Instant = Instant::now();
+    let clock: ScaledClock = ScaledClock::new(origin, started, 360.0);
+
+    assert_eq!(clock.at(started), origin);
+    assert_eq!(
+        clock.at(started + Duration::from_secs(10)),
+        Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn a_scaled_clock_past_its_range_stops_at_its_end() {
+    let started: Instant = Instant::now();
+    let clock: ScaledClock = ScaledClock::new(Utc::now(), started, 1e13);
+
+    assert_eq!(clock.at(started + Duration::from_secs(10)), DateTime::<Utc>::MAX_UTC);
+}
+
+#[test]
+fn the_time_scale_must_be_positive_and_bounded() {
+    assert!(check_sim_time_scale(720.0).is_ok());
+    assert!(check_sim_time_scale(MAX_SIM_TIME_SCALE).is_ok());
+    assert!(check_sim_time_scale(MAX_SIM_TIME_SCALE * 10.0).is_err());
+    assert!(check_sim_time_scale(0.0).is_err());
+    assert!(check_sim_time_scale(f64::INFINITY).is_err());
+}
+
+#[test]
+fn timestamps_are_formatted_as_configured() {
+    let now: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap();
+
+    assert_eq!(format_timestamp(now, TimestampFormat::Rfc3339), "2024-01-01T12:30:00.000Z");
+    assert_eq!(format_timestamp(now, TimestampFormat::UnixSecs), "1704112200");
+    assert_eq!(format_timestamp(now, TimestampFormat::UnixMillis), "1704112200000");
+}
@@ -0,0 +1,54 @@
+// Conversion math of the units module, at the boundary values of each scale.
+
+use metrics_board_demo::units::{
+    canonical_unit, celsius_to_fahrenheit, celsius_to_kelvin, convert,
+};
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}", expected, actual
+    );
+}
+
+#[test]
+fn celsius_to_fahrenheit_at_fixed_points() {
+    assert_close(celsius_to_fahrenheit(0.0), 32.0);
+    assert_close(celsius_to_fahrenheit(100.0), 212.0);
+    assert_close(celsius_to_fahrenheit(-40.0), -40.0);
+    assert_close(celsius_to_fahrenheit(-273.15), -459.67);
+}
+
+#[test]
+fn celsius_to_kelvin_at_fixed_points() {
+    assert_close(celsius_to_kelvin(-273.15), 0.0);
+    assert_close(celsius_to_kelvin(0.0), 273.15);
+    assert_close(celsius_to_kelvin(100.0), 373.15);
+}
+
+#[test]
+fn convert_between_every_temperature_unit() {
+    assert_close(convert(212.0, "Fahrenheit", "Celsius").unwrap(), 100.0);
+    assert_close(convert(0.0, "Kelvin", "Fahrenheit").unwrap(), -459.67);
+    assert_close(convert(32.0, "F", "K").unwrap(), 273.15);
+    assert_close(convert(-5.0, "Celsius", "kelvin").unwrap(), 268.15);
+}
+
+#[test]
+fn convert_keeps_values_of_the_same_unit() {
+    assert_close(convert(55.5, "%", "%").unwrap(), 55.5);
+    assert_close(convert(21.5, "Celsius", "celsius").unwrap(), 21.5);
+}
+
+#[test]
+fn convert_rejects_incompatible_units() {
+    assert!(convert(50.0, "%", "Celsius").is_err());
+    assert!(convert(1000.0, "hPa", "Kelvin").is_err());
+}
+
+#[test]
+fn canonical_unit_names_known_units() {
+    assert_eq!(canonical_unit("fahrenheit"), "Fahrenheit");
+    assert_eq!(canonical_unit("K"), "Kelvin");
+    assert_eq!(canonical_unit("hPa"), "hPa");
+}
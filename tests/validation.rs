@@ -0,0 +1,121 @@
+// Field checks of block data: coordinate ranges and product quantities.
+
+use metrics_board_demo::{
+    block_payload::{validate_coordinates, BlockData, ExportLocation, ProductInfo},
+    custom_error::Error,
+    validation::validate_block_data,
+};
+use serde_json::Value;
+
+#[test]
+fn coordinates_at_the_bounds_are_valid() {
+    assert!(validate_coordinates(-180.0, -90.0).is_ok());
+    assert!(validate_coordinates(180.0, 90.0).is_ok());
+    assert!(validate_coordinates(0.0, 0.0).is_ok());
+}
+
+#[test]
+fn out_of_range_coordinates_are_rejected() {
+    let err: String = validate_coordinates(180.5, 0.0).unwrap_err().to_string();
+    assert!(err.contains("longitude"), "{}", err);
+
+    let err: String = validate_coordinates(0.0, -90.5).unwrap_err().to_string();
+    assert!(err.contains("latitude"), "{}", err);
+}
+
+#[test]
+fn non_finite_coordinates_are_rejected() {
+    assert!(validate_coordinates(f32::NAN, 0.0).is_err());
+    assert!(validate_coordinates(0.0, f32::INFINITY).is_err());
+
+    let location: ExportLocation = ExportLocation { longitude: 0.0, latitude: f32::NAN };
+    assert!(location.validate().is_err());
+}
+
+#[test]
+fn block_data_with_invalid_coordinates_fails_validation() {
+    let mut producer: Value = serde_json::from_str(
+        include_str!("fixtures/block_data/RawMaterialsProducerBlockData.json")
+    ).unwrap();
+    producer["exportLocation"]["latitude"] = Value::from(123.0);
+    let producer: BlockData = serde_json::from_value(producer).unwrap();
+
+    let err: String = validate_block_data(&producer).unwrap_err().to_string();
+    assert!(err.contains("exportLocation"), "{}", err);
+
+    let mut location: Value = serde_json::from_str(
+        include_str!("fixtures/block_data/LocationMetricData.json")
+    ).unwrap();
+    location["longitude"] = Value::from(-200.0);
+    let location: BlockData = serde_json::from_value(location).unwrap();
+
+    assert!(validate_block_data(&location).is_err());
+}
+
+#[test]
+fn out_of_range_coordinates_are_a_validation_error() {
+    match validate_coordinates(0.0, 91.0) {
+        Err(Error::Validation(reason)) => assert!(reason.starts_with("latitude"), "{}", reason),
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+fn manufacturer_with_quantity(quantity: Value, unit: Value) -> BlockData {
+    let mut manufacturer: Value = serde_json::from_str(
+        include_str!("fixtures/block_data/ManufacturerBlockData.json")
+    ).unwrap();
+    manufacturer["productInfo"]["quantity"] = quantity;
+    manufacturer["productInfo"]["unit"] = unit;
+
+    serde_json::from_value(manufacturer).unwrap()
+}
+
+#[test]
+fn product_quantities_come_with_a_unit() {
+    let manufacturer: BlockData = manufacturer_with_quantity(Value::from(12.5), Value::from("kg"));
+    assert!(validate_block_data(&manufacturer).is_ok());
+
+    let manufacturer: BlockData = manufacturer_with_quantity(Value::from(12.5), Value::Null);
+    let err: String = validate_block_data(&manufacturer).unwrap_err().to_string();
+    assert!(err.contains("productInfo.unit"), "{}", err);
+}
+
+#[test]
+fn negative_product_quantities_are_rejected() {
+    let manufacturer: BlockData = manufacturer_with_quantity(Value::from(-1.0), Value::from("kg"));
+
+    let err: String = validate_block_data(&manufacturer).unwrap_err().to_string();
+    assert!(err.contains("productInfo.quantity"), "{}", err);
+}
+
+#[test]
+fn product_info_without_a_quantity_keeps_its_wire_format() {
+    let product_info: ProductInfo = ProductInfo::new(String::from("Pallets"), None).unwrap();
+    let value: Value = serde_json::to_value(&product_info).unwrap();
+    assert!(value.get("quantity").is_none());
+
+    let product_info: ProductInfo = product_info.with_quantity(4.0, String::from("pallets"));
+    assert_eq!(product_info.quantity, Some(4.0));
+    assert_eq!(product_info.unit.as_deref(), Some("pallets"));
+}
+
+#[test]
+fn consumer_blocks_name_the_delivery_and_its_receipt() {
+    let consumer: Value =
+        serde_json::from_str(include_str!("fixtures/block_data/ConsumerBlockData.json")).unwrap();
+    let valid: BlockData = serde_json::from_value(consumer.clone()).unwrap();
+    assert!(validate_block_data(&valid).is_ok());
+
+    let mut without_receipt: Value = consumer.clone();
+    without_receipt["resource"]["transactionReceipt"] = Value::from(" ");
+    let without_receipt: BlockData = serde_json::from_value(without_receipt).unwrap();
+    let err: String = validate_block_data(&without_receipt).unwrap_err().to_string();
+    assert!(err.contains("resource.transactionReceipt"), "{}", err);
+
+    let mut malformed_block: Value = consumer;
+    malformed_block["resource"]["previousBlock"] = Value::from("");
+    assert!(!matches!(
+        serde_json::from_value::<BlockData>(malformed_block),
+        Ok(BlockData::ConsumerBlockData(_))
+    ));
+}